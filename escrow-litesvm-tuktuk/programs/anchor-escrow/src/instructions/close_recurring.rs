@@ -0,0 +1,178 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use tuktuk_program::tuktuk::{
+    cpi::{accounts::DequeueTaskV0, dequeue_task_v0},
+    program::Tuktuk,
+};
+
+use crate::{state::Recurring, ErrorCode};
+
+// Stops the recurring offer and returns what is left of the balance and the
+// rent set aside for tranches. Tranche tasks that haven't run are dequeued,
+// so pass all of the offer's tranche tasks, in order, as `remaining_accounts`.
+// Open tranche escrows are left as is, and can still be taken or refunded on
+// their own.
+#[derive(Accounts)]
+pub struct CloseRecurring<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"recurring", maker.key().as_ref(), recurring.seed.to_le_bytes().as_ref()],
+        bump = recurring.bump,
+    )]
+    pub recurring: Account<'info, Recurring>,
+    #[account(
+        mut,
+        seeds = [b"recurring_rent", recurring.key().as_ref()],
+        bump = recurring.rent_payer_bump,
+    )]
+    pub rent_payer: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = recurring,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Checked against `recurring.task_queue`
+    #[account(
+        mut,
+        address = recurring.task_queue,
+    )]
+    pub task_queue: UncheckedAccount<'info>,
+    /// CHECK: CPI account
+    pub task_queue_authority: UncheckedAccount<'info>,
+    /// CHECK: Via seeds
+    #[account(
+        seeds = [b"queue_authority"],
+        bump
+    )]
+    pub queue_authority: UncheckedAccount<'info>,
+    pub tuktuk_program: Program<'info, Tuktuk>,
+}
+
+impl<'info> CloseRecurring<'info> {
+    pub fn cancel_tranche_tasks(
+        &mut self,
+        tasks: &[AccountInfo<'info>],
+        bumps: &CloseRecurringBumps,
+    ) -> Result<()> {
+        require!(
+            tasks.len() as u64 == self.recurring.tranches,
+            ErrorCode::MissingTuktukAccounts
+        );
+
+        for (index, task) in tasks.iter().enumerate() {
+            // Same derivation as tuktuk's `task_key`
+            let (expected, _) = Pubkey::find_program_address(
+                &[
+                    b"task",
+                    self.task_queue.key.as_ref(),
+                    &self.recurring.task_id(index as u64).to_le_bytes(),
+                ],
+                &self.tuktuk_program.key(),
+            );
+            require_keys_eq!(task.key(), expected, ErrorCode::TrancheTaskMismatch);
+
+            // Already ran
+            if task.data_is_empty() {
+                continue;
+            }
+
+            // Rent and crank reward go back to the rent payer, then to the maker
+            dequeue_task_v0(CpiContext::new_with_signer(
+                self.tuktuk_program.to_account_info(),
+                DequeueTaskV0 {
+                    queue_authority: self.queue_authority.to_account_info(),
+                    rent_refund: self.rent_payer.to_account_info(),
+                    task_queue_authority: self.task_queue_authority.to_account_info(),
+                    task_queue: self.task_queue.to_account_info(),
+                    task: task.clone(),
+                },
+                &[&[b"queue_authority", &[bumps.queue_authority]]],
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn withdraw_and_close_vault(&mut self) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"recurring",
+            self.maker.key.as_ref(),
+            &self.recurring.seed.to_le_bytes()[..],
+            &[self.recurring.bump],
+        ]];
+
+        if self.vault.amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: self.vault.to_account_info(),
+                to: self.maker_ata_a.to_account_info(),
+                mint: self.mint_a.to_account_info(),
+                authority: self.recurring.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                &signer_seeds,
+            );
+
+            transfer_checked(cpi_ctx, self.vault.amount, self.mint_a.decimals)?;
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.recurring.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            &signer_seeds,
+        );
+
+        close_account(cpi_ctx)
+    }
+
+    pub fn withdraw_rent(&mut self) -> Result<()> {
+        let recurring_key = self.recurring.key();
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"recurring_rent",
+            recurring_key.as_ref(),
+            &[self.recurring.rent_payer_bump],
+        ]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.rent_payer.to_account_info(),
+                to: self.maker.to_account_info(),
+            },
+            &signer_seeds,
+        );
+
+        transfer(cpi_ctx, self.rent_payer.lamports())
+    }
+}
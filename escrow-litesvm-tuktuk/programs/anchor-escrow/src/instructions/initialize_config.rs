@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{program::AnchorEscrow, state::Config, ErrorCode};
+
+// One-off, run right after deployment by the program's upgrade authority,
+// which becomes the config admin
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, AnchorEscrow>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"config"],
+        bump,
+        space = 8 + Config::INIT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(
+        &mut self,
+        default_crank_reward: u64,
+        min_crank_reward: u64,
+        max_crank_reward: u64,
+        free_tasks: u8,
+        bumps: &InitializeConfigBumps,
+    ) -> Result<()> {
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            default_crank_reward,
+            min_crank_reward,
+            max_crank_reward,
+            free_tasks,
+            bump: bumps.config,
+        });
+
+        self.config.check_bounds()
+    }
+}
@@ -4,7 +4,16 @@ use anchor_spl::{
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::state::Escrow;
+use tuktuk_program::tuktuk::{
+    cpi::{accounts::QueueTaskV0, queue_task_v0},
+    program::Tuktuk,
+};
+
+use crate::{
+    instructions::schedule::{queue_authority_bump, refund_task_args},
+    state::{Config, Escrow, RefundTask},
+    ErrorCode,
+};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -37,6 +46,24 @@ pub struct Make<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    // Tuktuk accounts, only needed when `make` also queues the refund task
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Option<Account<'info, Config>>,
+    /// CHECK: CPI account
+    #[account(mut)]
+    pub task_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: CPI account
+    pub task_queue_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Initialized in CPI
+    #[account(mut)]
+    pub task: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the queue_authority PDA
+    pub queue_authority: Option<UncheckedAccount<'info>>,
+    pub tuktuk_program: Option<Program<'info, Tuktuk>>,
 }
 
 impl<'info> Make<'info> {
@@ -54,6 +81,8 @@ impl<'info> Make<'info> {
             mint_b: self.mint_b.key(),
             receive,
             deadline: Clock::get()?.unix_timestamp + expiry,
+            refund_task: None,
+            recurring: None,
             bump: bumps.escrow,
         });
 
@@ -76,4 +105,58 @@ impl<'info> Make<'info> {
 
         Ok(())
     }
+
+    // Queues the expiry refund so the maker doesn't need a separate `schedule` call
+    pub fn schedule_refund(&mut self, task_id: u16) -> Result<()> {
+        let (
+            Some(config),
+            Some(task_queue),
+            Some(task_queue_authority),
+            Some(task),
+            Some(queue_authority),
+            Some(tuktuk_program),
+        ) = (
+            &self.config,
+            &self.task_queue,
+            &self.task_queue_authority,
+            &self.task,
+            &self.queue_authority,
+            &self.tuktuk_program,
+        )
+        else {
+            return err!(ErrorCode::MissingTuktukAccounts);
+        };
+
+        let bump = queue_authority_bump(queue_authority)?;
+
+        queue_task_v0(
+            CpiContext::new_with_signer(
+                tuktuk_program.to_account_info(),
+                QueueTaskV0 {
+                    payer: self.maker.to_account_info(),
+                    queue_authority: queue_authority.to_account_info(),
+                    task_queue: task_queue.to_account_info(),
+                    task_queue_authority: task_queue_authority.to_account_info(),
+                    task: task.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+                &[&[b"queue_authority", &[bump]]],
+            ),
+            refund_task_args(
+                &self.escrow,
+                config,
+                self.token_program.key(),
+                task_id,
+                None,
+            )?,
+        )?;
+
+        self.escrow.refund_task = Some(RefundTask {
+            task_queue: task_queue.key(),
+            task: task.key(),
+            id: task_id,
+        });
+
+        Ok(())
+    }
 }
@@ -0,0 +1,300 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::instruction::Instruction,
+    system_program::{transfer, Transfer},
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+use tuktuk_program::{
+    compile_transaction,
+    tuktuk::{
+        cpi::{accounts::QueueTaskV0, queue_task_v0},
+        program::Tuktuk,
+        types::TriggerV0,
+    },
+    types::QueueTaskArgsV0,
+    TransactionSourceV0,
+};
+
+use crate::{
+    state::{Config, Escrow, Recurring},
+    ErrorCode,
+};
+
+// Queues one `open_tranche` task per tranche, `interval` apart, paid for by
+// the recurring offer's rent payer. Pass the tranche tasks, in order, as
+// `remaining_accounts`.
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeRecurring<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"recurring", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Recurring::INIT_SPACE,
+    )]
+    pub recurring: Account<'info, Recurring>,
+    #[account(
+        mut,
+        seeds = [b"recurring_rent", recurring.key().as_ref()],
+        bump,
+    )]
+    pub rent_payer: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = recurring,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    /// CHECK: CPI account
+    pub task_queue: UncheckedAccount<'info>,
+    /// CHECK: CPI account
+    pub task_queue_authority: UncheckedAccount<'info>,
+    /// CHECK: Via seeds
+    #[account(
+        seeds = [b"queue_authority"],
+        bump
+    )]
+    pub queue_authority: UncheckedAccount<'info>,
+    pub tuktuk_program: Program<'info, Tuktuk>,
+}
+
+impl<'info> MakeRecurring<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_recurring(
+        &mut self,
+        seed: u64,
+        tranche_amount: u64,
+        tranche_receive: u64,
+        interval: i64,
+        expiry: i64,
+        deposit: u64,
+        first_task_id: u16,
+        bumps: &MakeRecurringBumps,
+    ) -> Result<()> {
+        require!(
+            deposit > 0 && tranche_amount > 0 && interval > 0,
+            ErrorCode::InvalidRecurringOffer
+        );
+
+        let tranches = deposit.div_ceil(tranche_amount);
+        require!(
+            tranches <= Recurring::MAX_TRANCHES,
+            ErrorCode::TooManyTranches
+        );
+        require!(
+            first_task_id as u64 + tranches <= u16::MAX as u64 + 1,
+            ErrorCode::InvalidRecurringOffer
+        );
+
+        self.recurring.set_inner(Recurring {
+            seed,
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            tranche_amount,
+            tranche_receive,
+            interval,
+            expiry,
+            // The first tranche can open right away
+            next_tranche_at: Clock::get()?.unix_timestamp,
+            tranches,
+            tranches_opened: 0,
+            task_queue: self.task_queue.key(),
+            first_task_id,
+            bump: bumps.recurring,
+            rent_payer_bump: bumps.rent_payer,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, deposit: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer_checked(cpi_ctx, deposit, self.mint_a.decimals)
+    }
+
+    // Tranches are opened by tasks that can't sign for the maker, so the rent
+    // for every tranche escrow, vault and task is set aside up front, along
+    // with the crank rewards. Each tranche's rent goes back to the maker when
+    // it is taken or refunded, and task rent comes back here once it has run.
+    pub fn fund_rent(&mut self) -> Result<()> {
+        let rent = Rent::get()?;
+        // Tranche vaults are the same kind of account as our own vault
+        let tranche_rent = rent.minimum_balance(8 + Escrow::INIT_SPACE)
+            + rent.minimum_balance(self.vault.to_account_info().data_len())
+            + rent.minimum_balance(Recurring::MAX_TASK_SPACE)
+            + self.config.crank_reward(None)?;
+
+        let cpi_ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.maker.to_account_info(),
+                to: self.rent_payer.to_account_info(),
+            },
+        );
+
+        // Plus the payer's own rent so it stays rent-exempt until closed
+        transfer(
+            cpi_ctx,
+            self.recurring.tranches * tranche_rent + rent.minimum_balance(0),
+        )
+    }
+
+    pub fn queue_tranche_tasks(
+        &mut self,
+        tasks: &[AccountInfo<'info>],
+        bumps: &MakeRecurringBumps,
+    ) -> Result<()> {
+        require!(
+            tasks.len() as u64 == self.recurring.tranches,
+            ErrorCode::MissingTuktukAccounts
+        );
+
+        let recurring_key = self.recurring.key();
+        let signer_seeds: [&[&[u8]]; 2] = [
+            &[b"queue_authority", &[bumps.queue_authority]],
+            &[
+                b"recurring_rent",
+                recurring_key.as_ref(),
+                &[bumps.rent_payer],
+            ],
+        ];
+
+        for (index, task) in tasks.iter().enumerate() {
+            queue_task_v0(
+                CpiContext::new_with_signer(
+                    self.tuktuk_program.to_account_info(),
+                    QueueTaskV0 {
+                        payer: self.rent_payer.to_account_info(),
+                        queue_authority: self.queue_authority.to_account_info(),
+                        task_queue: self.task_queue.to_account_info(),
+                        task_queue_authority: self.task_queue_authority.to_account_info(),
+                        task: task.clone(),
+                        system_program: self.system_program.to_account_info(),
+                    },
+                    &signer_seeds,
+                ),
+                open_tranche_task_args(
+                    &self.recurring,
+                    &self.config,
+                    self.token_program.key(),
+                    index as u64,
+                )?,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Queue arguments for the task that runs `open_tranche` for tranche `index`,
+/// `index` intervals after the first
+pub fn open_tranche_task_args(
+    recurring: &Account<Recurring>,
+    config: &Config,
+    token_program: Pubkey,
+    index: u64,
+) -> Result<QueueTaskArgsV0> {
+    // `open_tranche` needs no signer. Its accounts are:
+    // maker, mint_a, recurring (mut), rent_payer (mut), recurring_vault (mut),
+    // escrow (mut), vault (mut), associated_token_program, token_program,
+    // system_program
+    let recurring_key = recurring.key();
+    let mint_a = recurring.mint_a;
+    let associated_token_program = anchor_spl::associated_token::ID;
+    let system_program = anchor_lang::solana_program::system_program::ID;
+    let rent_payer = Pubkey::create_program_address(
+        &[
+            b"recurring_rent",
+            recurring_key.as_ref(),
+            &[recurring.rent_payer_bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidRecurringOffer)?;
+    let escrow = Pubkey::find_program_address(
+        &[b"escrow", recurring_key.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+    .0;
+
+    let recurring_vault =
+        get_associated_token_address_with_program_id(&recurring_key, &mint_a, &token_program);
+    let vault = get_associated_token_address_with_program_id(&escrow, &mint_a, &token_program);
+
+    let open_tranche_ix_accounts = vec![
+        AccountMeta::new_readonly(recurring.maker, false), // maker
+        AccountMeta::new_readonly(mint_a, false),          // mint_a
+        AccountMeta::new(recurring_key, false),            // recurring
+        AccountMeta::new(rent_payer, false),               // rent_payer
+        AccountMeta::new(recurring_vault, false),          // recurring_vault
+        AccountMeta::new(escrow, false),                   // escrow
+        AccountMeta::new(vault, false),                    // vault
+        AccountMeta::new_readonly(associated_token_program, false), // associated_token_program
+        AccountMeta::new_readonly(token_program, false),   // token_program
+        AccountMeta::new_readonly(system_program, false),  // system_program
+    ];
+
+    let (compiled_tx, _) = compile_transaction(
+        vec![Instruction {
+            program_id: crate::ID,
+            accounts: open_tranche_ix_accounts,
+            data: crate::instruction::OpenTranche { index }.data(),
+        }],
+        vec![],
+    )
+    .unwrap();
+
+    Ok(QueueTaskArgsV0 {
+        trigger: TriggerV0::Timestamp(
+            recurring.next_tranche_at + index as i64 * recurring.interval,
+        ),
+        transaction: TransactionSourceV0::CompiledV0(compiled_tx),
+        crank_reward: Some(config.crank_reward(None)?),
+        free_tasks: config.free_tasks,
+        id: recurring.task_id(index),
+        description: open_tranche_task_description(recurring, index),
+    })
+}
+
+/// `tranche {maker[..8]}:{seed}/{index}`, in the style of `refund_task_description`
+pub fn open_tranche_task_description(recurring: &Recurring, index: u64) -> String {
+    let maker = recurring.maker.to_string();
+    format!("tranche {}:{}/{}", &maker[..8], recurring.seed, index)
+}
@@ -1,9 +1,21 @@
+pub mod close_recurring;
+pub mod initialize_config;
 pub mod make;
+pub mod make_recurring;
+pub mod open_tranche;
 pub mod refund;
+pub mod reschedule;
 pub mod take;
+pub mod update_config;
 
+pub use close_recurring::*;
+pub use initialize_config::*;
 pub use make::*;
+pub use make_recurring::*;
+pub use open_tranche::*;
 pub use refund::*;
+pub use reschedule::*;
 pub use take::*;
+pub use update_config::*;
 pub mod schedule;
 pub use schedule::*;
@@ -0,0 +1,232 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    state::{Escrow, Recurring},
+    ErrorCode,
+};
+
+// Run by the tuktuk task `make_recurring` queued for tranche `index`.
+// Permissionless, since no signer is needed: rent comes from the recurring
+// offer's rent payer.
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct OpenTranche<'info> {
+    /// CHECK: Only used for seeds, checked by `has_one`
+    pub maker: UncheckedAccount<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"recurring", maker.key().as_ref(), recurring.seed.to_le_bytes().as_ref()],
+        bump = recurring.bump,
+    )]
+    pub recurring: Account<'info, Recurring>,
+    #[account(
+        mut,
+        seeds = [b"recurring_rent", recurring.key().as_ref()],
+        bump = recurring.rent_payer_bump,
+    )]
+    pub rent_payer: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = recurring,
+    )]
+    pub recurring_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: The tranche escrow, created here
+    #[account(
+        mut,
+        seeds = [b"escrow", recurring.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow: UncheckedAccount<'info>,
+    /// CHECK: The tranche escrow's ATA, address checked by the associated token program
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenTranche<'info> {
+    pub fn open_tranche(&mut self, index: u64, bumps: &OpenTrancheBumps) -> Result<()> {
+        // Already opened by someone calling ahead of its task. Not an error,
+        // so the task still completes and its rent comes back.
+        if index < self.recurring.tranches_opened {
+            msg!("Tranche {} is already open", index);
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            index == self.recurring.tranches_opened && now >= self.recurring.next_tranche_at,
+            ErrorCode::TrancheNotDue
+        );
+        require!(
+            self.recurring_vault.amount > 0,
+            ErrorCode::RecurringExhausted
+        );
+
+        let amount = self
+            .recurring_vault
+            .amount
+            .min(self.recurring.tranche_amount);
+
+        self.create_escrow(index, bumps.escrow)?;
+        self.escrow_data(index, now, amount, bumps.escrow)
+            .try_serialize(&mut &mut self.escrow.try_borrow_mut_data()?[..])?;
+        self.create_vault()?;
+        self.fill_vault(amount)?;
+
+        self.recurring.tranches_opened += 1;
+        self.recurring.next_tranche_at += self.recurring.interval;
+
+        Ok(())
+    }
+
+    fn escrow_data(&self, index: u64, now: i64, amount: u64, bump: u8) -> Escrow {
+        Escrow {
+            seed: index,
+            maker: self.recurring.maker,
+            mint_a: self.recurring.mint_a,
+            mint_b: self.recurring.mint_b,
+            receive: self.recurring.tranche_receive(amount),
+            deadline: now + self.recurring.expiry,
+            refund_task: None,
+            recurring: Some(self.recurring.key()),
+            bump,
+        }
+    }
+
+    // Same as Anchor's `init`, but paid for by the rent payer PDA
+    fn create_escrow(&self, index: u64, escrow_bump: u8) -> Result<()> {
+        let recurring_key = self.recurring.key();
+        let index = index.to_le_bytes();
+        let signer_seeds: [&[&[u8]]; 2] = [
+            &[
+                b"recurring_rent",
+                recurring_key.as_ref(),
+                &[self.recurring.rent_payer_bump],
+            ],
+            &[
+                b"escrow",
+                recurring_key.as_ref(),
+                &index[..],
+                &[escrow_bump],
+            ],
+        ];
+
+        let space = 8 + Escrow::INIT_SPACE;
+        let lamports = Rent::get()?.minimum_balance(space);
+        let system_program = self.system_program.to_account_info();
+
+        if self.escrow.lamports() == 0 {
+            return create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    CreateAccount {
+                        from: self.rent_payer.to_account_info(),
+                        to: self.escrow.to_account_info(),
+                    },
+                    &signer_seeds,
+                ),
+                lamports,
+                space as u64,
+                &crate::ID,
+            );
+        }
+
+        // Someone sent lamports to the escrow address, which `create_account` refuses
+        let shortfall = lamports.saturating_sub(self.escrow.lamports());
+        if shortfall > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Transfer {
+                        from: self.rent_payer.to_account_info(),
+                        to: self.escrow.to_account_info(),
+                    },
+                    &signer_seeds,
+                ),
+                shortfall,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: self.escrow.to_account_info(),
+                },
+                &signer_seeds,
+            ),
+            space as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system_program,
+                Assign {
+                    account_to_assign: self.escrow.to_account_info(),
+                },
+                &signer_seeds,
+            ),
+            &crate::ID,
+        )
+    }
+
+    fn create_vault(&self) -> Result<()> {
+        let recurring_key = self.recurring.key();
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"recurring_rent",
+            recurring_key.as_ref(),
+            &[self.recurring.rent_payer_bump],
+        ]];
+
+        // Idempotent in case the address was created ahead of us
+        create_idempotent(CpiContext::new_with_signer(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.rent_payer.to_account_info(),
+                associated_token: self.vault.to_account_info(),
+                authority: self.escrow.to_account_info(),
+                mint: self.mint_a.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+            &signer_seeds,
+        ))
+    }
+
+    fn fill_vault(&self, amount: u64) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"recurring",
+            self.maker.key.as_ref(),
+            &self.recurring.seed.to_le_bytes()[..],
+            &[self.recurring.bump],
+        ]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.recurring_vault.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.recurring.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            &signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)
+    }
+}
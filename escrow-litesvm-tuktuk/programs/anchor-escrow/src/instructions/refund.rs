@@ -4,6 +4,9 @@ use anchor_spl::token_interface::{
     TransferChecked,
 };
 
+use tuktuk_program::tuktuk::program::Tuktuk;
+
+use crate::instructions::schedule::dequeue_refund_task;
 use crate::state::Escrow;
 use crate::ErrorCode;
 
@@ -24,7 +27,7 @@ pub struct Refund<'info> {
         close = maker,
         has_one = mint_a,
         has_one = maker,
-        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        seeds = [b"escrow", escrow.seed_key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
@@ -36,17 +39,54 @@ pub struct Refund<'info> {
     vault: InterfaceAccount<'info, TokenAccount>,
     token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
+
+    // Tuktuk accounts, only needed when the maker refunds early while the
+    // escrow still has a pending refund task
+    /// CHECK: Receives the task rent and crank reward, checked by tuktuk
+    #[account(mut)]
+    pub rent_refund: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against `escrow.refund_task`
+    #[account(mut)]
+    pub task_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: CPI account
+    pub task_queue_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against `escrow.refund_task`
+    #[account(mut)]
+    pub task: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the queue_authority PDA
+    pub queue_authority: Option<UncheckedAccount<'info>>,
+    pub tuktuk_program: Option<Program<'info, Tuktuk>>,
 }
 
 impl<'info> Refund<'info> {
+    // A maker refunding in person dequeues the pending refund task so it
+    // doesn't fire against a closed escrow. When the task itself runs the
+    // refund, the maker hasn't signed and tuktuk closes the task.
+    pub fn cancel_refund_task(&mut self) -> Result<()> {
+        if !self.maker.is_signer {
+            return Ok(());
+        }
+
+        dequeue_refund_task(
+            &mut self.escrow,
+            &self.rent_refund,
+            &self.task_queue,
+            &self.task_queue_authority,
+            &self.task,
+            &self.queue_authority,
+            &self.tuktuk_program,
+        )
+    }
+
     pub fn refund_and_close_vault(&mut self) -> Result<()> {
         if !self.maker.is_signer && Clock::get()?.unix_timestamp <= self.escrow.deadline {
             return Err(ErrorCode::EscrowNotExpired.into());
         }
 
+        let seed_key = self.escrow.seed_key();
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
-            self.maker.key.as_ref(),
+            seed_key.as_ref(),
             &self.escrow.seed.to_le_bytes()[..],
             &[self.escrow.bump],
         ]];
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use tuktuk_program::tuktuk::{
+    cpi::{
+        accounts::{DequeueTaskV0, QueueTaskV0},
+        dequeue_task_v0, queue_task_v0,
+    },
+    program::Tuktuk,
+};
+
+use crate::{
+    instructions::schedule::refund_task_args,
+    state::{Config, Escrow, RefundTask},
+    ErrorCode,
+};
+
+// Maker extends the deadline and moves the refund task with it. Tuktuk
+// triggers can't be edited, so the pending task (if any) is dequeued and a
+// new one is queued on the same task queue under `task_id`.
+#[derive(Accounts)]
+pub struct Reschedule<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"escrow", escrow.seed_key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    /// Its owner tells us which token program the refund must go through
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Receives the old task's rent and crank reward, checked by tuktuk
+    #[account(mut)]
+    pub rent_refund: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against `escrow.refund_task`
+    #[account(mut)]
+    pub old_task: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: CPI account
+    #[account(mut)]
+    pub task_queue: UncheckedAccount<'info>,
+    /// CHECK: CPI account
+    pub task_queue_authority: UncheckedAccount<'info>,
+    /// CHECK: Initialized in CPI
+    #[account(mut)]
+    pub task: UncheckedAccount<'info>,
+    /// CHECK: Via seeds
+    #[account(
+        seeds = [b"queue_authority"],
+        bump
+    )]
+    pub queue_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub tuktuk_program: Program<'info, Tuktuk>,
+}
+
+impl<'info> Reschedule<'info> {
+    pub fn extend_deadline(&mut self, deadline: i64) -> Result<()> {
+        require!(deadline > self.escrow.deadline, ErrorCode::InvalidDeadline);
+
+        self.escrow.deadline = deadline;
+
+        Ok(())
+    }
+
+    pub fn cancel_refund_task(&mut self, bumps: &RescheduleBumps) -> Result<()> {
+        let Some(refund_task) = self.escrow.refund_task.take() else {
+            return Ok(());
+        };
+
+        let (Some(rent_refund), Some(old_task)) = (&self.rent_refund, &self.old_task) else {
+            return err!(ErrorCode::MissingTuktukAccounts);
+        };
+
+        require_keys_eq!(
+            self.task_queue.key(),
+            refund_task.task_queue,
+            ErrorCode::RefundTaskMismatch
+        );
+        require_keys_eq!(
+            old_task.key(),
+            refund_task.task,
+            ErrorCode::RefundTaskMismatch
+        );
+
+        // Already ran or was closed by the queue
+        if old_task.data_is_empty() {
+            return Ok(());
+        }
+
+        dequeue_task_v0(CpiContext::new_with_signer(
+            self.tuktuk_program.to_account_info(),
+            DequeueTaskV0 {
+                queue_authority: self.queue_authority.to_account_info(),
+                rent_refund: rent_refund.to_account_info(),
+                task_queue_authority: self.task_queue_authority.to_account_info(),
+                task_queue: self.task_queue.to_account_info(),
+                task: old_task.to_account_info(),
+            },
+            &[&[b"queue_authority", &[bumps.queue_authority]]],
+        ))
+    }
+
+    pub fn schedule_refund(&mut self, task_id: u16, bumps: &RescheduleBumps) -> Result<()> {
+        // SPL Token or Token-2022, whichever owns the mint
+        let token_program = *self.mint_a.to_account_info().owner;
+
+        queue_task_v0(
+            CpiContext::new_with_signer(
+                self.tuktuk_program.to_account_info(),
+                QueueTaskV0 {
+                    payer: self.maker.to_account_info(),
+                    queue_authority: self.queue_authority.to_account_info(),
+                    task_queue: self.task_queue.to_account_info(),
+                    task_queue_authority: self.task_queue_authority.to_account_info(),
+                    task: self.task.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+                &[&[b"queue_authority", &[bumps.queue_authority]]],
+            ),
+            refund_task_args(&self.escrow, &self.config, token_program, task_id, None)?,
+        )?;
+
+        self.escrow.refund_task = Some(RefundTask {
+            task_queue: self.task_queue.key(),
+            task: self.task.key(),
+            id: task_id,
+        });
+
+        Ok(())
+    }
+}
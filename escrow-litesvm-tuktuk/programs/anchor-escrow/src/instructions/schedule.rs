@@ -1,10 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::InstructionData;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token_interface::Mint,
+};
 use tuktuk_program::{
     compile_transaction,
     tuktuk::{
-        cpi::{accounts::QueueTaskV0, queue_task_v0},
+        cpi::{
+            accounts::{DequeueTaskV0, QueueTaskV0},
+            dequeue_task_v0, queue_task_v0,
+        },
         program::Tuktuk,
         types::TriggerV0,
     },
@@ -12,19 +18,34 @@ use tuktuk_program::{
     TransactionSourceV0,
 };
 
-use crate::state::Escrow;
+use crate::{
+    state::{Config, Escrow, RefundTask},
+    ErrorCode,
+};
 
+// Maker queues the expiry refund for an escrow made without one
 #[derive(Accounts)]
 #[instruction(task_id: u16)]
 pub struct Schedule<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub maker: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
 
     #[account(
-        seeds = [b"escrow", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        constraint = escrow.refund_task.is_none() @ ErrorCode::RefundAlreadyScheduled,
+        seeds = [b"escrow", escrow.seed_key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
+    /// Its owner tells us which token program the refund must go through
+    pub mint_a: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     /// CHECK: CPI account
@@ -46,57 +67,147 @@ pub struct Schedule<'info> {
     pub tuktuk_program: Program<'info, Tuktuk>,
 }
 
-pub fn custom_schedule(ctx: Context<Schedule>, task_id: u16) -> Result<()> {
-    // Construct the instruction to be executed by the task (Refund)
-    // We need to construct the `refund` instruction call.
-    // The `refund` instruction expects:
-    // maker (Unchecked), mint_a, maker_ata_a, escrow, vault, token_program, system_program
-
-    // NOTE: For the cron to work permissionlessly, the `refund` instruction must NOT require signer for maker.
-    // We already modified `refund` to allow this if expired.
-
-    // However, typical CPI construction requires known account metas.
-    // The `Refund` accounts are:
-    // 0. maker (mut)
-    // 1. mint_a
-    // 2. maker_ata_a (mut)
-    // 3. escrow (mut)
-    // 4. vault (mut)
-    // 5. token_program
-    // 6. system_program
-
-    // We need these accounts to be passed into `schedule` or derived.
-    // Ideally, we pass them as remaining accounts or explicitly if we want to validiate them.
-    // For simplicity, let's assume we can derive or pass them.
-    // But `compile_transaction` takes `Instruction`.
-
-    // Wait, `escrow` has `mint_a`, `maker` stored. We can derive `vault` and `maker_ata_a`.
-    // But `compile_transaction` needs `AccountMeta`s.
-
-    // Let's use the explicit accounts passed to `Schedule` context if we add them, or just construct manually.
-    // Since `refund` only needs public keys, we can construct the instruction data and accounts.
-
-    // ISSUE: We don't have the `maker_ata_a` and `vault` in `Schedule` context currently.
-    // We should add them to `Schedule` struct to easily reference them, or just use `escrow` data to derive.
-    // Deriving in standard Anchor CPI might be tricky without `AccountInfo`.
-    // But `compile_transaction` just needs `AccountMeta`. We can create `AccountMeta` from Pubkeys.
-
-    // Let's update `Schedule` struct to include necessary accounts for `refund` so we can easily build the CPI.
-    // Or, better, just use the `escrow` data.
-
-    let maker = ctx.accounts.escrow.maker;
-    let mint_a = ctx.accounts.escrow.mint_a;
-    let escrow_key = ctx.accounts.escrow.key();
-
-    // Derive ATAs
-    let maker_ata_a = anchor_spl::associated_token::get_associated_token_address(&mint_a, &maker);
-    let vault = anchor_spl::associated_token::get_associated_token_address(&mint_a, &escrow_key);
-
-    let token_program = anchor_spl::token::ID; // Assuming SPL Token
+pub fn custom_schedule(
+    ctx: Context<Schedule>,
+    task_id: u16,
+    crank_reward: Option<u64>,
+) -> Result<()> {
+    // SPL Token or Token-2022, whichever owns the mint
+    let token_program = *ctx.accounts.mint_a.to_account_info().owner;
+
+    queue_task_v0(
+        CpiContext::new_with_signer(
+            ctx.accounts.tuktuk_program.to_account_info(),
+            QueueTaskV0 {
+                payer: ctx.accounts.maker.to_account_info(),
+                queue_authority: ctx.accounts.queue_authority.to_account_info(),
+                task_queue: ctx.accounts.task_queue.to_account_info(),
+                task_queue_authority: ctx.accounts.task_queue_authority.to_account_info(),
+                task: ctx.accounts.task.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[&[b"queue_authority", &[ctx.bumps.queue_authority]]],
+        ),
+        refund_task_args(
+            &ctx.accounts.escrow,
+            &ctx.accounts.config,
+            token_program,
+            task_id,
+            crank_reward,
+        )?,
+    )?;
+
+    // Recorded so `take` can dequeue it
+    ctx.accounts.escrow.refund_task = Some(RefundTask {
+        task_queue: ctx.accounts.task_queue.key(),
+        task: ctx.accounts.task.key(),
+        id: task_id,
+    });
+
+    Ok(())
+}
+
+/// Bump of the `queue_authority` PDA, for instructions that take it as an optional account
+pub fn queue_authority_bump(queue_authority: &AccountInfo) -> Result<u8> {
+    let (expected, bump) = Pubkey::find_program_address(&[b"queue_authority"], &crate::ID);
+    require_keys_eq!(
+        queue_authority.key(),
+        expected,
+        ErrorCode::InvalidQueueAuthority
+    );
+
+    Ok(bump)
+}
+
+/// Dequeues `escrow.refund_task`, if any, through the optional tuktuk accounts
+/// that `take` and `refund` accept. The task's rent and crank reward go to `rent_refund`.
+pub fn dequeue_refund_task<'info>(
+    escrow: &mut Escrow,
+    rent_refund: &Option<UncheckedAccount<'info>>,
+    task_queue: &Option<UncheckedAccount<'info>>,
+    task_queue_authority: &Option<UncheckedAccount<'info>>,
+    task: &Option<UncheckedAccount<'info>>,
+    queue_authority: &Option<UncheckedAccount<'info>>,
+    tuktuk_program: &Option<Program<'info, Tuktuk>>,
+) -> Result<()> {
+    let Some(refund_task) = escrow.refund_task.clone() else {
+        return Ok(());
+    };
+
+    let (
+        Some(rent_refund),
+        Some(task_queue),
+        Some(task_queue_authority),
+        Some(task),
+        Some(queue_authority),
+        Some(tuktuk_program),
+    ) = (
+        rent_refund,
+        task_queue,
+        task_queue_authority,
+        task,
+        queue_authority,
+        tuktuk_program,
+    )
+    else {
+        return err!(ErrorCode::MissingTuktukAccounts);
+    };
+
+    require_keys_eq!(
+        task_queue.key(),
+        refund_task.task_queue,
+        ErrorCode::RefundTaskMismatch
+    );
+    require_keys_eq!(task.key(), refund_task.task, ErrorCode::RefundTaskMismatch);
+
+    // Nothing left to cancel if the task is already gone
+    if task.data_is_empty() {
+        escrow.refund_task = None;
+        return Ok(());
+    }
+
+    let bump = queue_authority_bump(queue_authority)?;
+
+    dequeue_task_v0(CpiContext::new_with_signer(
+        tuktuk_program.to_account_info(),
+        DequeueTaskV0 {
+            queue_authority: queue_authority.to_account_info(),
+            rent_refund: rent_refund.to_account_info(),
+            task_queue_authority: task_queue_authority.to_account_info(),
+            task_queue: task_queue.to_account_info(),
+            task: task.to_account_info(),
+        },
+        &[&[b"queue_authority", &[bump]]],
+    ))?;
+
+    escrow.refund_task = None;
+
+    Ok(())
+}
+
+/// Queue arguments for a task that runs `refund` on `escrow` once its deadline has passed.
+/// `crank_reward` overrides the config default, within the config bounds.
+pub fn refund_task_args(
+    escrow: &Account<Escrow>,
+    config: &Config,
+    token_program: Pubkey,
+    task_id: u16,
+    crank_reward: Option<u64>,
+) -> Result<QueueTaskArgsV0> {
+    // The task runs `refund` without the maker's signature, which `refund`
+    // allows once the deadline has passed. Its accounts are:
+    // maker (mut), mint_a, maker_ata_a (mut), escrow (mut), vault (mut),
+    // token_program, system_program, then the six optional tuktuk accounts
+    // left empty, since the running task is closed by tuktuk itself
+    let maker = escrow.maker;
+    let mint_a = escrow.mint_a;
+    let escrow_key = escrow.key();
     let system_program = anchor_lang::solana_program::system_program::ID;
 
-    // Construct the instruction
-    let refund_ix_accounts = vec![
+    let maker_ata_a = get_associated_token_address_with_program_id(&maker, &mint_a, &token_program);
+    let vault = get_associated_token_address_with_program_id(&escrow_key, &mint_a, &token_program);
+
+    let mut refund_ix_accounts = vec![
         AccountMeta::new(maker, false),           // maker (mut, not signer)
         AccountMeta::new_readonly(mint_a, false), // mint_a
         AccountMeta::new(maker_ata_a, false),     // maker_ata_a
@@ -105,49 +216,34 @@ pub fn custom_schedule(ctx: Context<Schedule>, task_id: u16) -> Result<()> {
         AccountMeta::new_readonly(token_program, false), // token_program
         AccountMeta::new_readonly(system_program, false), // system_program
     ];
-
-    let refund_ix_data = crate::instruction::Refund {}.data(); // Anchor generates this?
-                                                               // If not, we need to manually compute discriminator.
-                                                               // Anchor encodes instruction data as 8-byte discriminator + args.
-                                                               // `Refund` has no args.
-
-    // Verify `Refund` exists in `crate::instruction`.
+    // Anchor reads an optional account passed as the program id as `None`
+    refund_ix_accounts.extend(vec![AccountMeta::new_readonly(crate::ID, false); 6]);
 
     let (compiled_tx, _) = compile_transaction(
         vec![Instruction {
             program_id: crate::ID,
             accounts: refund_ix_accounts,
-            data: refund_ix_data,
+            data: crate::instruction::Refund {}.data(),
         }],
         vec![],
     )
     .unwrap();
 
-    queue_task_v0(
-        CpiContext::new_with_signer(
-            ctx.accounts.tuktuk_program.to_account_info(),
-            QueueTaskV0 {
-                payer: ctx.accounts.user.to_account_info(),
-                queue_authority: ctx.accounts.queue_authority.to_account_info(),
-                task_queue: ctx.accounts.task_queue.to_account_info(),
-                task_queue_authority: ctx.accounts.task_queue_authority.to_account_info(),
-                task: ctx.accounts.task.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-            },
-            &[&[b"queue_authority", &[0]]], // Need bump? The seed is just "queue_authority" in tuktuk-counter?
-                                            // Wait, tuktuk-counter uses `seeds = [b"queue_authority"], bump`.
-                                            // And passes `&[&["queue_authority".as_bytes(), &[bumps.queue_authority]]]`
-                                            // I need to make sure I pass the bump.
-        ),
-        QueueTaskArgsV0 {
-            trigger: TriggerV0::Timestamp(ctx.accounts.escrow.deadline), // Trigger at deadline
-            transaction: TransactionSourceV0::CompiledV0(compiled_tx),
-            crank_reward: Some(1000000), // 0.001 SOL reward?
-            free_tasks: 1,
-            id: task_id,
-            description: "Refund Escrow".to_string(),
-        },
-    )?;
+    Ok(QueueTaskArgsV0 {
+        // `refund` only opens up strictly after the deadline
+        trigger: TriggerV0::Timestamp(escrow.deadline + 1),
+        transaction: TransactionSourceV0::CompiledV0(compiled_tx),
+        crank_reward: Some(config.crank_reward(crank_reward)?),
+        free_tasks: config.free_tasks,
+        id: task_id,
+        description: refund_task_description(escrow),
+    })
+}
 
-    Ok(())
+/// Identifies the escrow to crankers and dashboards as `refund {maker[..8]}:{seed}`,
+/// e.g. `refund 7xKXtg2C:123` for seed 123 made by `7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU`.
+/// The maker is abbreviated to keep the description short.
+pub fn refund_task_description(escrow: &Escrow) -> String {
+    let maker = escrow.maker.to_string();
+    format!("refund {}:{}", &maker[..8], escrow.seed)
 }
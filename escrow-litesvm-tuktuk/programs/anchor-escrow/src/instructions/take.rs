@@ -7,7 +7,9 @@ use anchor_spl::{
     },
 };
 
-use crate::state::Escrow;
+use tuktuk_program::tuktuk::program::Tuktuk;
+
+use crate::{instructions::schedule::dequeue_refund_task, state::Escrow};
 
 //Create context
 #[derive(Accounts)]
@@ -44,7 +46,7 @@ pub struct Take<'info> {
         has_one = maker,
         has_one = mint_a,
         has_one = mint_b,
-        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        seeds = [b"escrow", escrow.seed_key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
@@ -57,18 +59,48 @@ pub struct Take<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    // Tuktuk accounts, only needed when the escrow has a pending refund task
+    /// CHECK: Receives the task rent and crank reward, checked by tuktuk
+    #[account(mut)]
+    pub rent_refund: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against `escrow.refund_task`
+    #[account(mut)]
+    pub task_queue: Option<UncheckedAccount<'info>>,
+    /// CHECK: CPI account
+    pub task_queue_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against `escrow.refund_task`
+    #[account(mut)]
+    pub task: Option<UncheckedAccount<'info>>,
+    /// CHECK: Checked against the queue_authority PDA
+    pub queue_authority: Option<UncheckedAccount<'info>>,
+    pub tuktuk_program: Option<Program<'info, Tuktuk>>,
 }
 
 //Deposit tokens from taker to maker
 //Transfer tokens from vault to taker
 //Close vault account
 impl<'info> Take<'info> {
+    // Dequeues the pending refund task so it doesn't fire against a closed
+    // escrow, returning its rent and crank reward to whoever queued it
+    pub fn cancel_refund_task(&mut self) -> Result<()> {
+        dequeue_refund_task(
+            &mut self.escrow,
+            &self.rent_refund,
+            &self.task_queue,
+            &self.task_queue_authority,
+            &self.task,
+            &self.queue_authority,
+            &self.tuktuk_program,
+        )
+    }
+
     pub fn deposit(&mut self) -> Result<()> {
         let clock = Clock::get()?;
         let current_time = clock.unix_timestamp;
 
         if current_time <= self.escrow.deadline {
-            return Err(ErrorCode::ConstraintSeeds.into()); // Using a generic error for now, better to define a custom one
+            return Err(crate::ErrorCode::DeadlineNotReached.into());
         }
 
         let cpi_program = self.token_program.to_account_info();
@@ -86,9 +118,10 @@ impl<'info> Take<'info> {
     }
 
     pub fn withdraw_and_close_vault(&mut self) -> Result<()> {
+        let seed_key = self.escrow.seed_key();
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
-            self.maker.key.as_ref(),
+            seed_key.as_ref(),
             &self.escrow.seed.to_le_bytes()[..],
             &[self.escrow.bump],
         ]];
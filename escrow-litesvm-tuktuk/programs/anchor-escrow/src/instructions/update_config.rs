@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(
+        &mut self,
+        new_admin: Pubkey,
+        default_crank_reward: u64,
+        min_crank_reward: u64,
+        max_crank_reward: u64,
+        free_tasks: u8,
+    ) -> Result<()> {
+        self.config.admin = new_admin;
+        self.config.default_crank_reward = default_crank_reward;
+        self.config.min_crank_reward = min_crank_reward;
+        self.config.max_crank_reward = max_crank_reward;
+        self.config.free_tasks = free_tasks;
+
+        self.config.check_bounds()
+    }
+}
@@ -15,29 +15,119 @@ declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 pub mod anchor_escrow {
     use super::*;
 
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        default_crank_reward: u64,
+        min_crank_reward: u64,
+        max_crank_reward: u64,
+        free_tasks: u8,
+    ) -> Result<()> {
+        ctx.accounts.initialize_config(
+            default_crank_reward,
+            min_crank_reward,
+            max_crank_reward,
+            free_tasks,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Pubkey,
+        default_crank_reward: u64,
+        min_crank_reward: u64,
+        max_crank_reward: u64,
+        free_tasks: u8,
+    ) -> Result<()> {
+        ctx.accounts.update_config(
+            new_admin,
+            default_crank_reward,
+            min_crank_reward,
+            max_crank_reward,
+            free_tasks,
+        )
+    }
+
     pub fn make(
         ctx: Context<Make>,
         seed: u64,
         deposit: u64,
         receive: u64,
         expiry: i64,
+        task_id: Option<u16>,
     ) -> Result<()> {
         ctx.accounts
             .init_escrow(seed, receive, expiry, &ctx.bumps)?;
-        ctx.accounts.deposit(deposit)
+        ctx.accounts.deposit(deposit)?;
+
+        match task_id {
+            Some(task_id) => ctx.accounts.schedule_refund(task_id),
+            None => Ok(()),
+        }
     }
 
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        ctx.accounts.cancel_refund_task()?;
         ctx.accounts.refund_and_close_vault()
     }
 
     pub fn take(ctx: Context<Take>) -> Result<()> {
+        ctx.accounts.cancel_refund_task()?;
         ctx.accounts.deposit()?;
         ctx.accounts.withdraw_and_close_vault()
     }
 
-    pub fn schedule(ctx: Context<Schedule>, task_id: u16) -> Result<()> {
-        instructions::schedule::custom_schedule(ctx, task_id)
+    // Sells `deposit` off in tranches of `tranche_amount`, one every `interval`
+    // seconds, each asking a proportional share of `tranche_receive`.
+    // Tranche `index` is opened by task `first_task_id + index` on `task_queue`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_recurring<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeRecurring<'info>>,
+        seed: u64,
+        deposit: u64,
+        tranche_amount: u64,
+        tranche_receive: u64,
+        interval: i64,
+        expiry: i64,
+        first_task_id: u16,
+    ) -> Result<()> {
+        ctx.accounts.init_recurring(
+            seed,
+            tranche_amount,
+            tranche_receive,
+            interval,
+            expiry,
+            deposit,
+            first_task_id,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.deposit(deposit)?;
+        ctx.accounts.fund_rent()?;
+        ctx.accounts
+            .queue_tranche_tasks(ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn open_tranche(ctx: Context<OpenTranche>, index: u64) -> Result<()> {
+        ctx.accounts.open_tranche(index, &ctx.bumps)
+    }
+
+    pub fn close_recurring<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseRecurring<'info>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .cancel_tranche_tasks(ctx.remaining_accounts, &ctx.bumps)?;
+        ctx.accounts.withdraw_and_close_vault()?;
+        ctx.accounts.withdraw_rent()
+    }
+
+    pub fn schedule(ctx: Context<Schedule>, task_id: u16, crank_reward: Option<u64>) -> Result<()> {
+        instructions::schedule::custom_schedule(ctx, task_id, crank_reward)
+    }
+
+    pub fn reschedule(ctx: Context<Reschedule>, deadline: i64, task_id: u16) -> Result<()> {
+        ctx.accounts.extend_deadline(deadline)?;
+        ctx.accounts.cancel_refund_task(&ctx.bumps)?;
+        ctx.accounts.schedule_refund(task_id, &ctx.bumps)
     }
 }
 
@@ -45,4 +135,32 @@ pub mod anchor_escrow {
 pub enum ErrorCode {
     #[msg("Escrow has not expired.")]
     EscrowNotExpired,
+    #[msg("Tuktuk accounts are required to queue or cancel the refund task.")]
+    MissingTuktukAccounts,
+    #[msg("Queue authority is not this program's queue_authority PDA.")]
+    InvalidQueueAuthority,
+    #[msg("Task does not match the refund task recorded on the escrow.")]
+    RefundTaskMismatch,
+    #[msg("A refund task is already queued for this escrow.")]
+    RefundAlreadyScheduled,
+    #[msg("The new deadline must be later than the current one.")]
+    InvalidDeadline,
+    #[msg("Crank reward bounds must satisfy min <= default <= max.")]
+    InvalidCrankReward,
+    #[msg("Crank reward is outside the configured bounds.")]
+    CrankRewardOutOfBounds,
+    #[msg("Tranche amount and interval must be positive.")]
+    InvalidRecurringOffer,
+    #[msg("The next tranche is not due yet.")]
+    TrancheNotDue,
+    #[msg("The recurring offer has no balance left.")]
+    RecurringExhausted,
+    #[msg("The escrow cannot be taken until its deadline has passed.")]
+    DeadlineNotReached,
+    #[msg("Only the program's upgrade authority can initialize the config.")]
+    NotUpgradeAuthority,
+    #[msg("A recurring offer is limited to Recurring::MAX_TRANCHES tranches.")]
+    TooManyTranches,
+    #[msg("Task is not the recurring offer's task for this tranche.")]
+    TrancheTaskMismatch,
 }
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Program-wide settings for queued refund tasks
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Crank reward used when `schedule` is not given an override
+    pub default_crank_reward: u64,
+    pub min_crank_reward: u64,
+    pub max_crank_reward: u64,
+    pub free_tasks: u8,
+    pub bump: u8,
+}
+
+impl Config {
+    pub fn check_bounds(&self) -> Result<()> {
+        require!(
+            self.min_crank_reward <= self.default_crank_reward
+                && self.default_crank_reward <= self.max_crank_reward,
+            ErrorCode::InvalidCrankReward
+        );
+
+        Ok(())
+    }
+
+    pub fn crank_reward(&self, requested: Option<u64>) -> Result<u64> {
+        let crank_reward = requested.unwrap_or(self.default_crank_reward);
+        require!(
+            (self.min_crank_reward..=self.max_crank_reward).contains(&crank_reward),
+            ErrorCode::CrankRewardOutOfBounds
+        );
+
+        Ok(crank_reward)
+    }
+}
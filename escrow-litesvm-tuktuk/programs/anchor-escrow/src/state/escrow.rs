@@ -9,5 +9,24 @@ pub struct Escrow {
     pub mint_b: Pubkey,
     pub receive: u64,
     pub deadline: i64,
+    /// Tuktuk task that refunds the escrow after the deadline, if one is queued
+    pub refund_task: Option<RefundTask>,
+    /// Recurring offer this escrow is a tranche of, if any
+    pub recurring: Option<Pubkey>,
     pub bump: u8,
 }
+
+impl Escrow {
+    /// Key after `b"escrow"` in the escrow's seeds: the maker for escrows from
+    /// `make`, the recurring offer for tranches, so the two never share an address
+    pub fn seed_key(&self) -> Pubkey {
+        self.recurring.unwrap_or(self.maker)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Debug, PartialEq)]
+pub struct RefundTask {
+    pub task_queue: Pubkey,
+    pub task: Pubkey,
+    pub id: u16,
+}
@@ -1,3 +1,7 @@
+pub mod config;
 pub mod escrow;
+pub mod recurring;
 
-pub use escrow::*;
\ No newline at end of file
+pub use config::*;
+pub use escrow::*;
+pub use recurring::*;
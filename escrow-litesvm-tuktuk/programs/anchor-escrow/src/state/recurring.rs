@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// A maker's balance of mint A sold off one tranche escrow at a time.
+/// Tranche `index` is a regular `Escrow` at `[b"escrow", recurring, index]`,
+/// opened by `open_tranche` from a tuktuk task queued for it by `make_recurring`.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Recurring {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Mint A moved into each tranche escrow
+    pub tranche_amount: u64,
+    /// Mint B asked for a full tranche, scaled down for a smaller final tranche
+    pub tranche_receive: u64,
+    /// Seconds between tranches
+    pub interval: i64,
+    /// Time lock of each tranche escrow, as `expiry` in `make`
+    pub expiry: i64,
+    pub next_tranche_at: i64,
+    /// Number of tranches the deposit is split into, one task each
+    pub tranches: u64,
+    pub tranches_opened: u64,
+    /// Queue holding the `open_tranche` tasks
+    pub task_queue: Pubkey,
+    /// Task id of tranche 0; tranche `index` uses `first_task_id + index`
+    pub first_task_id: u16,
+    pub bump: u8,
+    /// Bump of the system-owned PDA that pays tranche escrow and vault rent
+    pub rent_payer_bump: u8,
+}
+
+impl Recurring {
+    /// Each tranche is queued as its own task in `make_recurring`
+    pub const MAX_TRANCHES: u64 = 8;
+    /// Upper bound on the size of an `open_tranche` task account, used to
+    /// set its rent aside. Whatever is left over goes back on `close_recurring`.
+    pub const MAX_TASK_SPACE: usize = 1024;
+
+    pub fn task_id(&self, index: u64) -> u16 {
+        self.first_task_id + index as u16
+    }
+
+    // Mint B asked for a tranche of `amount`
+    pub fn tranche_receive(&self, amount: u64) -> u64 {
+        (self.tranche_receive as u128 * amount as u128 / self.tranche_amount as u128) as u64
+    }
+}
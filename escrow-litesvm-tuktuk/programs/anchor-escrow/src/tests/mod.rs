@@ -1,3 +1,8 @@
+// Shared with escrow-litesvm, which owns the fixture files
+#[cfg(test)]
+#[path = "../../../../../escrow-litesvm/programs/anchor-escrow/src/tests/fixture.rs"]
+mod fixture;
+
 #[cfg(test)]
 mod tests {
 
@@ -11,28 +16,41 @@ mod tests {
             associated_token::{self, spl_associated_token_account},
             token::spl_token,
         },
-        litesvm::{types::TransactionMetadata, LiteSVM},
+        litesvm::{
+            types::{FailedTransactionMetadata, TransactionMetadata},
+            LiteSVM,
+        },
         litesvm_token::{
             spl_token::ID as TOKEN_PROGRAM_ID, CreateAssociatedTokenAccount, CreateMint, MintTo,
         },
-        solana_account::Account,
-        solana_address::Address,
-        solana_instruction::Instruction,
+        solana_instruction::{error::InstructionError, AccountMeta, Instruction},
         solana_keypair::Keypair,
         solana_message::Message,
         solana_native_token::LAMPORTS_PER_SOL,
         solana_pubkey::Pubkey,
-        solana_rpc_client::rpc_client::RpcClient,
-        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_sdk_ids::{
+            bpf_loader_upgradeable::ID as BPF_LOADER_UPGRADEABLE_ID,
+            compute_budget::ID as COMPUTE_BUDGET_PROGRAM_ID,
+            system_program::ID as SYSTEM_PROGRAM_ID,
+        },
         solana_signer::Signer,
         solana_transaction::Transaction,
-        std::{path::PathBuf, str::FromStr},
+        solana_transaction_error::TransactionError,
+        spl_token_2022::extension::StateWithExtensions,
+        std::path::PathBuf,
+        tuktuk_program::{
+            compile_transaction,
+            tuktuk::{types::TriggerV0, ID as TUKTUK_PROGRAM_ID},
+            TransactionSourceV0,
+        },
     };
 
+    use super::fixture::{load_fixture, SYNTHETIC_PAYER_FIXTURE};
+
     static PROGRAM_ID: Pubkey = crate::ID;
 
     // Setup function to initialize LiteSVM and create a payer keypair
-    // Also loads an account from devnet into the LiteSVM environment (for testing purposes)
+    // Also loads a synthetic account fixture into the LiteSVM environment (for testing purposes)
     fn setup() -> (LiteSVM, Keypair) {
         // Initialize LiteSVM and payer
         let mut program = LiteSVM::new();
@@ -49,39 +67,72 @@ mod tests {
 
         let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
 
-        program.add_program(PROGRAM_ID, &program_data);
+        // Deployed upgradeable, with the payer as upgrade authority, so it can
+        // initialize the config
+        add_upgradeable_program(&mut program, &program_data, payer.pubkey());
 
-        // Example on how to Load an account from devnet
-        // LiteSVM does not have access to real Solana network data since it does not have network access,
-        // so we use an RPC client to fetch account data from devnet
-        let rpc_client = RpcClient::new("https://api.devnet.solana.com");
-        let account_address =
-            Address::from_str("DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2").unwrap();
-        let fetched_account = rpc_client
-            .get_account(&account_address)
-            .expect("Failed to fetch account from devnet");
+        // Example on how to Load an account from a fixture
+        // LiteSVM does not have access to real Solana network data, so the account is
+        // read from a file checked into the repo instead of fetched over RPC.
+        // This keeps the suite hermetic (CI, air-gapped machines)
+        let (account_address, fetched_account) = load_fixture(SYNTHETIC_PAYER_FIXTURE);
+        msg!("Loaded fixture for {}", account_address);
+
+        let lamports = fetched_account.lamports;
 
         // Set the fetched account in the LiteSVM environment
         // This allows us to simulate interactions with this account during testing
         program
-            .set_account(
-                payer.pubkey(),
-                Account {
-                    lamports: fetched_account.lamports,
-                    data: fetched_account.data,
-                    owner: Pubkey::from(fetched_account.owner.to_bytes()),
-                    executable: fetched_account.executable,
-                    rent_epoch: fetched_account.rent_epoch,
-                },
-            )
+            .set_account(payer.pubkey(), fetched_account)
             .unwrap();
 
-        msg!("Lamports of fetched account: {}", fetched_account.lamports);
+        msg!("Lamports of fetched account: {}", lamports);
 
         // Return the LiteSVM instance and payer keypair
         (program, payer)
     }
 
+    fn find_program_data() -> Pubkey {
+        Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    // Deploys the program under the upgradeable loader: a program account
+    // pointing at a program data account that holds the upgrade authority
+    // and the ELF, laid out as the loader's `UpgradeableLoaderState`
+    fn add_upgradeable_program(program: &mut LiteSVM, elf: &[u8], upgrade_authority: Pubkey) {
+        let program_data = find_program_data();
+
+        // ProgramData { slot: 0, upgrade_authority_address: Some(..) }
+        let mut program_data_bytes = 3u32.to_le_bytes().to_vec();
+        program_data_bytes.extend_from_slice(&0u64.to_le_bytes());
+        program_data_bytes.push(1);
+        program_data_bytes.extend_from_slice(upgrade_authority.as_ref());
+        program_data_bytes.extend_from_slice(elf);
+
+        // Program { programdata_address }
+        let mut program_bytes = 2u32.to_le_bytes().to_vec();
+        program_bytes.extend_from_slice(program_data.as_ref());
+
+        // The program data must be in place before the program is loaded from it
+        for (address, data, executable) in [
+            (program_data, program_data_bytes, false),
+            (PROGRAM_ID, program_bytes, true),
+        ] {
+            program
+                .set_account(
+                    address,
+                    solana_account::Account {
+                        lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                        data,
+                        owner: BPF_LOADER_UPGRADEABLE_ID,
+                        executable,
+                        rent_epoch: 0,
+                    },
+                )
+                .unwrap();
+        }
+    }
+
     fn setup_escrow_make(
         program: &mut LiteSVM,
         payer: &Keypair,
@@ -93,6 +144,24 @@ mod tests {
         Pubkey,
         Pubkey,
         TransactionMetadata,
+    ) {
+        setup_escrow_make_with_token_program(program, payer, TOKEN_PROGRAM_ID, None)
+    }
+
+    // `refund_task` is the (task_queue, task_id) to queue the refund on at make time
+    fn setup_escrow_make_with_token_program(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        token_program: Pubkey,
+        refund_task: Option<(Pubkey, u16)>,
+    ) -> (
+        Pubkey,
+        Pubkey,
+        Pubkey,
+        Pubkey,
+        Pubkey,
+        Pubkey,
+        TransactionMetadata,
     ) {
         // Get the maker's public key from the payer keypair
         let maker = payer.pubkey();
@@ -102,6 +171,7 @@ mod tests {
         let mint_a = CreateMint::new(program, &payer)
             .decimals(6)
             .authority(&maker)
+            .token_program_id(&token_program)
             .send()
             .unwrap();
         msg!("Mint A: {}\n", mint_a);
@@ -109,6 +179,7 @@ mod tests {
         let mint_b = CreateMint::new(program, &payer)
             .decimals(6)
             .authority(&maker)
+            .token_program_id(&token_program)
             .send()
             .unwrap();
         msg!("Mint B: {}\n", mint_b);
@@ -117,6 +188,7 @@ mod tests {
         // This is done using litesvm-token's CreateAssociatedTokenAccount utility
         let maker_ata_a = CreateAssociatedTokenAccount::new(program, &payer, &mint_a)
             .owner(&maker)
+            .token_program_id(&token_program)
             .send()
             .unwrap();
         msg!("Maker ATA A: {}\n", maker_ata_a);
@@ -130,16 +202,20 @@ mod tests {
         msg!("Escrow PDA: {}\n", escrow);
 
         // Derive the PDA for the vault associated token account using the escrow PDA and Mint A
-        let vault = associated_token::get_associated_token_address(&escrow, &mint_a);
+        let vault = associated_token::get_associated_token_address_with_program_id(
+            &escrow,
+            &mint_a,
+            &token_program,
+        );
         msg!("Vault PDA: {}\n", vault);
 
         // Define program IDs for associated token program, token program, and system program
         let asspciated_token_program = spl_associated_token_account::ID;
-        let token_program = TOKEN_PROGRAM_ID;
         let system_program = SYSTEM_PROGRAM_ID;
 
         // Mint 1,000 tokens (with 6 decimal places) of Mint A to the maker's associated token account
         MintTo::new(program, &payer, &mint_a, &maker_ata_a, 1000000000)
+            .token_program_id(&token_program)
             .send()
             .unwrap();
 
@@ -156,6 +232,13 @@ mod tests {
                 associated_token_program: asspciated_token_program,
                 token_program: token_program,
                 system_program: system_program,
+                config: refund_task.map(|_| find_config()),
+                task_queue: refund_task.map(|(task_queue, _)| task_queue),
+                task_queue_authority: refund_task
+                    .map(|(task_queue, _)| find_task_queue_authority(&task_queue)),
+                task: refund_task.map(|(task_queue, task_id)| find_task(&task_queue, task_id)),
+                queue_authority: refund_task.map(|_| find_queue_authority()),
+                tuktuk_program: refund_task.map(|_| TUKTUK_PROGRAM_ID),
             }
             .to_account_metas(None),
             data: crate::instruction::Make {
@@ -163,6 +246,7 @@ mod tests {
                 seed: 123u64,
                 receive: 10,
                 expiry: 100, // 100 seconds expiry
+                task_id: refund_task.map(|(_, task_id)| task_id),
             }
             .data(),
         };
@@ -227,6 +311,12 @@ mod tests {
                 vault,
                 token_program,
                 system_program,
+                rent_refund: None,
+                task_queue: None,
+                task_queue_authority: None,
+                task: None,
+                queue_authority: None,
+                tuktuk_program: None,
             }
             .to_account_metas(None),
             data: crate::instruction::Refund {}.data(),
@@ -316,6 +406,12 @@ mod tests {
                 associated_token_program,
                 token_program,
                 system_program,
+                rent_refund: None,
+                task_queue: None,
+                task_queue_authority: None,
+                task: None,
+                queue_authority: None,
+                tuktuk_program: None,
             }
             .to_account_metas(None),
             data: crate::instruction::Take {}.data(),
@@ -327,10 +423,7 @@ mod tests {
 
         // This test is expected to fail because of the time lock
         let tx_result = program.send_transaction(transaction);
-        assert!(
-            tx_result.is_err(),
-            "Take transaction should fail before deadline"
-        );
+        assert_escrow_error(tx_result, crate::ErrorCode::DeadlineNotReached);
     }
 
     #[test]
@@ -399,6 +492,12 @@ mod tests {
                 associated_token_program,
                 token_program,
                 system_program,
+                rent_refund: None,
+                task_queue: None,
+                task_queue_authority: None,
+                task: None,
+                queue_authority: None,
+                tuktuk_program: None,
             }
             .to_account_metas(None),
             data: crate::instruction::Take {}.data(),
@@ -433,53 +532,1517 @@ mod tests {
         assert_eq!(escrow_acc.owner, system_program);
     }
 
+    // Built from `programs/tuktuk-mock` and deployed at the real tuktuk address
+    fn add_tuktuk_mock(program: &mut LiteSVM) {
+        let so_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/tuktuk_mock.so");
+
+        let program_data = std::fs::read(so_path).expect("Failed to read tuktuk mock SO file");
+
+        program.add_program(TUKTUK_PROGRAM_ID, &program_data);
+    }
+
+    fn find_config() -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+    }
+
+    fn try_initialize_config(
+        program: &mut LiteSVM,
+        admin: &Keypair,
+        default_crank_reward: u64,
+        min_crank_reward: u64,
+        max_crank_reward: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let initialize_config_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeConfig {
+                admin: admin.pubkey(),
+                program: PROGRAM_ID,
+                program_data: find_program_data(),
+                config: find_config(),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeConfig {
+                default_crank_reward,
+                min_crank_reward,
+                max_crank_reward,
+                free_tasks: 1,
+            }
+            .data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[initialize_config_ix], Some(&admin.pubkey()));
+        let transaction = Transaction::new(&[admin], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_update_config(
+        program: &mut LiteSVM,
+        admin: &Keypair,
+        new_admin: Pubkey,
+        default_crank_reward: u64,
+        max_crank_reward: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let update_config_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::UpdateConfig {
+                admin: admin.pubkey(),
+                config: find_config(),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::UpdateConfig {
+                new_admin,
+                default_crank_reward,
+                min_crank_reward: 0,
+                max_crank_reward,
+                free_tasks: 1,
+            }
+            .data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[update_config_ix], Some(&admin.pubkey()));
+        let transaction = Transaction::new(&[admin], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Deploys the tuktuk mock and creates the config every scheduling path reads
+    fn setup_scheduling(program: &mut LiteSVM, admin: &Keypair) {
+        add_tuktuk_mock(program);
+        try_initialize_config(program, admin, 1_000_000, 0, 10_000_000).unwrap();
+    }
+
+    fn task_state(program: &LiteSVM, task: &Pubkey) -> tuktuk_mock::TaskV0 {
+        let task_account = program.get_account(task).unwrap();
+        tuktuk_mock::TaskV0::try_deserialize(&mut task_account.data.as_ref()).unwrap()
+    }
+
+    fn find_queue_authority() -> Pubkey {
+        Pubkey::find_program_address(&[b"queue_authority"], &crate::ID).0
+    }
+
+    fn find_task_queue_authority(task_queue: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"task_queue_authority",
+                task_queue.as_ref(),
+                find_queue_authority().as_ref(),
+            ],
+            &TUKTUK_PROGRAM_ID,
+        )
+        .0
+    }
+
+    // Same derivation as tuktuk's `task_key`
+    fn find_task(task_queue: &Pubkey, task_id: u16) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"task", task_queue.as_ref(), &task_id.to_le_bytes()],
+            &TUKTUK_PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn token_amount(program: &LiteSVM, ata: &Pubkey) -> u64 {
+        let account = program.get_account(ata).unwrap();
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    fn schedule_refund(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        escrow: Pubkey,
+        mint_a: Pubkey,
+        task_queue: Pubkey,
+        task_id: u16,
+        crank_reward: Option<u64>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let schedule_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Schedule {
+                maker: maker.pubkey(),
+                config: find_config(),
+                escrow,
+                mint_a,
+                task_queue,
+                task_queue_authority: find_task_queue_authority(&task_queue),
+                task: find_task(&task_queue, task_id),
+                queue_authority: find_queue_authority(),
+                system_program: SYSTEM_PROGRAM_ID,
+                tuktuk_program: TUKTUK_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Schedule {
+                task_id,
+                crank_reward,
+            }
+            .data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[schedule_ix], Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[maker], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Cranks the queued refund through the mock's `run_task_v0`, passing the
+    // refund's accounts in compiled-transaction order like a real cranker
+    fn crank_refund(
+        program: &mut LiteSVM,
+        crank_turner: &Keypair,
+        rent_refund: Pubkey,
+        task_queue: Pubkey,
+        task_id: u16,
+        refund_accounts: crate::accounts::Refund,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let (_, transaction_accounts) = compile_transaction(
+            vec![Instruction {
+                program_id: PROGRAM_ID,
+                accounts: refund_accounts.to_account_metas(None),
+                data: crate::instruction::Refund {}.data(),
+            }],
+            vec![],
+        )
+        .unwrap();
+
+        let mut accounts = tuktuk_mock::accounts::RunTaskV0 {
+            crank_turner: crank_turner.pubkey(),
+            rent_refund,
+            task_queue,
+            task: find_task(&task_queue, task_id),
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(transaction_accounts);
+
+        let run_task_ix = Instruction {
+            program_id: TUKTUK_PROGRAM_ID,
+            accounts,
+            data: tuktuk_mock::instruction::RunTaskV0 {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[run_task_ix], Some(&crank_turner.pubkey()));
+        let transaction = Transaction::new(&[crank_turner], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Schedules the expiry refund, then checks the queued task only runs once
+    // the deadline has passed and actually refunds the maker
+    fn schedule_and_crank(token_program: Pubkey) {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let (maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
+            setup_escrow_make_with_token_program(&mut program, &payer, token_program, None);
+
+        let task_queue = Keypair::new().pubkey();
+        let task_id = 1u16;
+
+        let tx = schedule_refund(
+            &mut program,
+            &payer,
+            escrow,
+            mint_a,
+            task_queue,
+            task_id,
+            None,
+        )
+        .unwrap();
+        msg!("Schedule transaction sucessfull");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        let task = program
+            .get_account(&find_task(&task_queue, task_id))
+            .unwrap();
+        assert_eq!(task.owner, TUKTUK_PROGRAM_ID);
+
+        let crank_turner = Keypair::new();
+        program
+            .airdrop(&crank_turner.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let refund_accounts = || crate::accounts::Refund {
+            maker,
+            mint_a,
+            maker_ata_a,
+            escrow,
+            vault,
+            token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            rent_refund: None,
+            task_queue: None,
+            task_queue_authority: None,
+            task: None,
+            queue_authority: None,
+            tuktuk_program: None,
+        };
+
+        // Trigger not reached yet
+        let result = crank_refund(
+            &mut program,
+            &crank_turner,
+            payer.pubkey(),
+            task_queue,
+            task_id,
+            refund_accounts(),
+        );
+        assert!(result.is_err());
+
+        let escrow_account = program.get_account(&escrow).unwrap();
+        let deadline = crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref())
+            .unwrap()
+            .deadline;
+
+        let mut clock: Clock = program.get_sysvar();
+        clock.unix_timestamp = deadline + 1;
+        program.set_sysvar(&clock);
+
+        let crank_balance = program
+            .get_account(&crank_turner.pubkey())
+            .unwrap()
+            .lamports;
+        crank_refund(
+            &mut program,
+            &crank_turner,
+            payer.pubkey(),
+            task_queue,
+            task_id,
+            refund_accounts(),
+        )
+        .unwrap();
+
+        // The queued refund ran: tokens back with the maker, vault and escrow closed
+        assert_eq!(token_amount(&program, &maker_ata_a), 1_000_000_000);
+
+        let vault_acc = program.get_account(&vault).unwrap();
+        assert_eq!(vault_acc.lamports, 0);
+        assert_eq!(vault_acc.owner, SYSTEM_PROGRAM_ID);
+
+        let escrow_acc = program.get_account(&escrow).unwrap();
+        assert_eq!(escrow_acc.lamports, 0);
+        assert_eq!(escrow_acc.owner, SYSTEM_PROGRAM_ID);
+
+        // Task closed and the cranker was paid
+        let task = program
+            .get_account(&find_task(&task_queue, task_id))
+            .unwrap();
+        assert_eq!(task.lamports, 0);
+        assert!(
+            program
+                .get_account(&crank_turner.pubkey())
+                .unwrap()
+                .lamports
+                > crank_balance
+        );
+    }
+
     #[test]
     fn test_schedule() {
+        schedule_and_crank(TOKEN_PROGRAM_ID);
+    }
+
+    #[test]
+    fn test_schedule_token_2022() {
+        schedule_and_crank(spl_token_2022::ID);
+    }
+
+    fn escrow_state(program: &LiteSVM, escrow: &Pubkey) -> crate::state::Escrow {
+        let escrow_account = program.get_account(escrow).unwrap();
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap()
+    }
+
+    // Asserts the transaction failed with the given program error
+    fn assert_escrow_error<T: std::fmt::Debug>(
+        result: Result<T, FailedTransactionMetadata>,
+        error: crate::ErrorCode,
+    ) {
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+            )
+        );
+    }
+
+    fn warp_to(program: &mut LiteSVM, timestamp: i64) {
+        let mut clock: Clock = program.get_sysvar();
+        msg!(
+            "Travelled into future: {} -> {}",
+            clock.unix_timestamp,
+            timestamp
+        );
+        clock.unix_timestamp = timestamp;
+        program.set_sysvar(&clock);
+    }
+
+    fn warp_past_deadline(program: &mut LiteSVM, escrow: &Pubkey) {
+        let deadline = escrow_state(program, escrow).deadline;
+        warp_to(program, deadline + 1);
+    }
+
+    // Funded taker holding 10 of Mint B, plus the maker's Mint B ATA
+    fn setup_taker(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+    ) -> Keypair {
+        let taker = Keypair::new();
+
+        program
+            .airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        CreateAssociatedTokenAccount::new(program, &taker, &mint_a)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
+
+        let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &mint_b)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
+
+        CreateAssociatedTokenAccount::new(program, payer, &mint_b)
+            .owner(&maker)
+            .send()
+            .unwrap();
+
+        MintTo::new(program, payer, &mint_b, &taker_ata_b, 10)
+            .send()
+            .unwrap();
+
+        taker
+    }
+
+    // `refund_task` is the (task_queue, task_id) to dequeue, with the maker as rent refund
+    fn try_take(
+        program: &mut LiteSVM,
+        taker: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+        refund_task: Option<(Pubkey, u16)>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let take_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Take {
+                taker: taker.pubkey(),
+                maker,
+                mint_a,
+                mint_b,
+                taker_ata_a: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_a,
+                ),
+                taker_ata_b: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_b,
+                ),
+                maker_ata_b: associated_token::get_associated_token_address(&maker, &mint_b),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                rent_refund: refund_task.map(|_| maker),
+                task_queue: refund_task.map(|(task_queue, _)| task_queue),
+                task_queue_authority: refund_task
+                    .map(|(task_queue, _)| find_task_queue_authority(&task_queue)),
+                task: refund_task.map(|(task_queue, task_id)| find_task(&task_queue, task_id)),
+                queue_authority: refund_task.map(|_| find_queue_authority()),
+                tuktuk_program: refund_task.map(|_| TUKTUK_PROGRAM_ID),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Take {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+        let transaction = Transaction::new(&[taker], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    #[test]
+    fn test_make_schedules_refund() {
         let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let task_id = 3u16;
+        let (maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
+            setup_escrow_make_with_token_program(
+                &mut program,
+                &payer,
+                TOKEN_PROGRAM_ID,
+                Some((task_queue, task_id)),
+            );
+
+        let task = find_task(&task_queue, task_id);
+        assert_eq!(
+            escrow_state(&program, &escrow).refund_task,
+            Some(crate::state::RefundTask {
+                task_queue,
+                task,
+                id: task_id,
+            })
+        );
+        assert_eq!(program.get_account(&task).unwrap().owner, TUKTUK_PROGRAM_ID);
 
-        // Load Tuktuk Program from Devnet (Pubkey only)
-        let tuktuk_str = "tuktukUrfhXT6ZT77QTU8RQtvgL967uRuVagWF57zVA";
-        let tuktuk_program_id = Pubkey::from_str(tuktuk_str).unwrap();
+        // No separate `schedule` call: the queued task refunds the maker
+        warp_past_deadline(&mut program, &escrow);
 
-        let (maker, mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+        let crank_turner = Keypair::new();
+        program
+            .airdrop(&crank_turner.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        crank_refund(
+            &mut program,
+            &crank_turner,
+            maker,
+            task_queue,
+            task_id,
+            crate::accounts::Refund {
+                maker,
+                mint_a,
+                maker_ata_a,
+                escrow,
+                vault,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                rent_refund: None,
+                task_queue: None,
+                task_queue_authority: None,
+                task: None,
+                queue_authority: None,
+                tuktuk_program: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(token_amount(&program, &maker_ata_a), 1_000_000_000);
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+    }
+
+    #[test]
+    fn test_take_cancels_refund_task() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let task_id = 4u16;
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make_with_token_program(
+                &mut program,
+                &payer,
+                TOKEN_PROGRAM_ID,
+                Some((task_queue, task_id)),
+            );
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+        let task = find_task(&task_queue, task_id);
+
+        warp_past_deadline(&mut program, &escrow);
+
+        // The pending task must be cancelled as part of the take
+        let result = try_take(&mut program, &taker, maker, mint_a, mint_b, escrow, None);
+        assert_escrow_error(result, crate::ErrorCode::MissingTuktukAccounts);
+
+        // Task rent and the escrowed crank reward go back to the maker
+        let task_lamports = program.get_account(&task).unwrap().lamports;
+        let maker_lamports = program.get_account(&maker).unwrap().lamports;
+
+        try_take(
+            &mut program,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            Some((task_queue, task_id)),
+        )
+        .unwrap();
+
+        assert_eq!(program.get_account(&task).unwrap().lamports, 0);
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+        assert!(program.get_account(&maker).unwrap().lamports >= maker_lamports + task_lamports);
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        assert_eq!(token_amount(&program, &taker_ata_a), 10);
+    }
+
+    // Refund signed by the maker, passing the tuktuk accounts for `refund_task` if given
+    fn try_maker_refund(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint_a: Pubkey,
+        escrow: Pubkey,
+        refund_task: Option<(Pubkey, u16)>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                maker: maker.pubkey(),
+                mint_a,
+                maker_ata_a: associated_token::get_associated_token_address(
+                    &maker.pubkey(),
+                    &mint_a,
+                ),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                rent_refund: refund_task.map(|_| maker.pubkey()),
+                task_queue: refund_task.map(|(task_queue, _)| task_queue),
+                task_queue_authority: refund_task
+                    .map(|(task_queue, _)| find_task_queue_authority(&task_queue)),
+                task: refund_task.map(|(task_queue, task_id)| find_task(&task_queue, task_id)),
+                queue_authority: refund_task.map(|_| find_queue_authority()),
+                tuktuk_program: refund_task.map(|_| TUKTUK_PROGRAM_ID),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[refund_ix], Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[maker], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    #[test]
+    fn test_early_refund_cancels_refund_task() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let task_id = 6u16;
+        let (_maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
+            setup_escrow_make_with_token_program(
+                &mut program,
+                &payer,
+                TOKEN_PROGRAM_ID,
+                Some((task_queue, task_id)),
+            );
+        let task = find_task(&task_queue, task_id);
+        assert!(program.get_account(&task).unwrap().lamports > 0);
+
+        // The pending task must be cancelled as part of the refund
+        let result = try_maker_refund(&mut program, &payer, mint_a, escrow, None);
+        assert_escrow_error(result, crate::ErrorCode::MissingTuktukAccounts);
+
+        // Only the recorded task can be cancelled
+        let result = try_maker_refund(
+            &mut program,
+            &payer,
+            mint_a,
+            escrow,
+            Some((task_queue, task_id + 1)),
+        );
+        assert_escrow_error(result, crate::ErrorCode::RefundTaskMismatch);
+
+        // Well before the deadline
+        try_maker_refund(
+            &mut program,
+            &payer,
+            mint_a,
+            escrow,
+            Some((task_queue, task_id)),
+        )
+        .unwrap();
+
+        assert_eq!(program.get_account(&task).unwrap().lamports, 0);
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+        assert_eq!(program.get_account(&vault).unwrap().lamports, 0);
+        assert_eq!(token_amount(&program, &maker_ata_a), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_take_after_third_party_schedule_attempt() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
             setup_escrow_make(&mut program, &payer);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
 
+        // A stranger's task would make every take depend on accounts only they control
         let task_queue = Keypair::new().pubkey();
-        let task_queue_authority = Keypair::new().pubkey();
-        let queue_authority = Pubkey::find_program_address(&[b"queue_authority"], &crate::ID).0;
-        let task = Keypair::new().pubkey();
+        let stranger = Keypair::new();
+        program
+            .airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let result = schedule_refund(&mut program, &stranger, escrow, mint_a, task_queue, 7, None);
+        assert!(result.is_err());
+        assert_eq!(escrow_state(&program, &escrow).refund_task, None);
+        assert!(program.get_account(&find_task(&task_queue, 7)).is_none());
 
-        let task_id = 1u16;
+        let result = try_take(&mut program, &taker, maker, mint_a, mint_b, escrow, None);
+        assert_escrow_error(result, crate::ErrorCode::DeadlineNotReached);
 
-        let schedule_ix = Instruction {
+        // Nothing was queued, so the take needs no tuktuk accounts
+        warp_past_deadline(&mut program, &escrow);
+        try_take(&mut program, &taker, maker, mint_a, mint_b, escrow, None).unwrap();
+
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        assert_eq!(token_amount(&program, &taker_ata_a), 10);
+    }
+
+    // Moves the deadline and re-queues the refund as `task_id` on `task_queue`.
+    // `old_task_id` is the task currently recorded on the escrow, if any.
+    fn try_reschedule(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        escrow: Pubkey,
+        task_queue: Pubkey,
+        old_task_id: Option<u16>,
+        task_id: u16,
+        deadline: i64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let reschedule_ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::Schedule {
-                user: payer.pubkey(),
+            accounts: crate::accounts::Reschedule {
+                maker: maker.pubkey(),
                 escrow,
+                mint_a: escrow_state(program, &escrow).mint_a,
+                config: find_config(),
+                rent_refund: old_task_id.map(|_| maker.pubkey()),
+                old_task: old_task_id.map(|old_task_id| find_task(&task_queue, old_task_id)),
                 task_queue,
-                task_queue_authority,
-                task,
-                queue_authority,
+                task_queue_authority: find_task_queue_authority(&task_queue),
+                task: find_task(&task_queue, task_id),
+                queue_authority: find_queue_authority(),
                 system_program: SYSTEM_PROGRAM_ID,
-                tuktuk_program: tuktuk_program_id,
+                tuktuk_program: TUKTUK_PROGRAM_ID,
             }
             .to_account_metas(None),
-            data: crate::instruction::Schedule { task_id }.data(),
+            data: crate::instruction::Reschedule { deadline, task_id }.data(),
         };
 
-        let message = Message::new(&[schedule_ix], Some(&payer.pubkey()));
-        let transaction = Transaction::new(&[&payer], message, program.latest_blockhash());
+        program.expire_blockhash();
+        let message = Message::new(&[reschedule_ix], Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[maker], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    #[test]
+    fn test_take_cancels_scheduled_task() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
 
-        let tx_result = program.send_transaction(transaction);
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        let task_queue = Keypair::new().pubkey();
+        let task_id = 5u16;
+        schedule_refund(
+            &mut program,
+            &payer,
+            escrow,
+            mint_a,
+            task_queue,
+            task_id,
+            None,
+        )
+        .unwrap();
+
+        let task = find_task(&task_queue, task_id);
+        assert_eq!(
+            escrow_state(&program, &escrow).refund_task,
+            Some(crate::state::RefundTask {
+                task_queue,
+                task,
+                id: task_id,
+            })
+        );
 
-        match tx_result {
-            Ok(tx) => {
-                msg!("Schedule successful");
-                msg!("Tx Signature: {}", tx.signature);
+        // Only one pending refund per escrow
+        let result = schedule_refund(&mut program, &payer, escrow, mint_a, task_queue, 6, None);
+        assert_escrow_error(result, crate::ErrorCode::RefundAlreadyScheduled);
+
+        warp_past_deadline(&mut program, &escrow);
+        try_take(
+            &mut program,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            Some((task_queue, task_id)),
+        )
+        .unwrap();
+
+        // The task will not fire against the closed escrow
+        assert_eq!(program.get_account(&task).unwrap().lamports, 0);
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+    }
+
+    #[test]
+    fn test_reschedule_moves_trigger() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
+            setup_escrow_make_with_token_program(
+                &mut program,
+                &payer,
+                TOKEN_PROGRAM_ID,
+                Some((task_queue, 1)),
+            );
+
+        let old_deadline = escrow_state(&program, &escrow).deadline;
+        let new_deadline = old_deadline + 24 * 60 * 60;
+
+        try_reschedule(
+            &mut program,
+            &payer,
+            escrow,
+            task_queue,
+            Some(1),
+            2,
+            new_deadline,
+        )
+        .unwrap();
+
+        let escrow_data = escrow_state(&program, &escrow);
+        assert_eq!(escrow_data.deadline, new_deadline);
+        assert_eq!(
+            escrow_data.refund_task,
+            Some(crate::state::RefundTask {
+                task_queue,
+                task: find_task(&task_queue, 2),
+                id: 2,
+            })
+        );
+
+        // Old task is gone, new one is queued
+        assert_eq!(
+            program
+                .get_account(&find_task(&task_queue, 1))
+                .unwrap()
+                .lamports,
+            0
+        );
+        assert_eq!(
+            program
+                .get_account(&find_task(&task_queue, 2))
+                .unwrap()
+                .owner,
+            TUKTUK_PROGRAM_ID
+        );
+
+        let crank_turner = Keypair::new();
+        program
+            .airdrop(&crank_turner.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let refund_accounts = || crate::accounts::Refund {
+            maker,
+            mint_a,
+            maker_ata_a,
+            escrow,
+            vault,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            rent_refund: None,
+            task_queue: None,
+            task_queue_authority: None,
+            task: None,
+            queue_authority: None,
+            tuktuk_program: None,
+        };
+
+        // The original trigger no longer fires the refund
+        warp_to(&mut program, old_deadline + 1);
+        let result = crank_refund(
+            &mut program,
+            &crank_turner,
+            maker,
+            task_queue,
+            2,
+            refund_accounts(),
+        );
+        assert!(result.is_err());
+
+        warp_to(&mut program, new_deadline + 1);
+        crank_refund(
+            &mut program,
+            &crank_turner,
+            maker,
+            task_queue,
+            2,
+            refund_accounts(),
+        )
+        .unwrap();
+
+        assert_eq!(token_amount(&program, &maker_ata_a), 1_000_000_000);
+        assert_eq!(program.get_account(&escrow).unwrap().lamports, 0);
+    }
+
+    #[test]
+    fn test_reschedule_is_maker_only() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (_maker, _mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make_with_token_program(
+                &mut program,
+                &payer,
+                TOKEN_PROGRAM_ID,
+                Some((task_queue, 1)),
+            );
+        let deadline = escrow_state(&program, &escrow).deadline;
+
+        let stranger = Keypair::new();
+        program
+            .airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let result = try_reschedule(
+            &mut program,
+            &stranger,
+            escrow,
+            task_queue,
+            Some(1),
+            2,
+            deadline + 60,
+        );
+        assert!(result.is_err());
+
+        // Rescheduling can only push the deadline out
+        let result = try_reschedule(
+            &mut program,
+            &payer,
+            escrow,
+            task_queue,
+            Some(1),
+            2,
+            deadline,
+        );
+        assert_escrow_error(result, crate::ErrorCode::InvalidDeadline);
+
+        assert_eq!(
+            escrow_state(&program, &escrow)
+                .refund_task
+                .map(|task| task.id),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_config_bounds_and_admin() {
+        let (mut program, payer) = setup();
+
+        // Default outside the bounds
+        let result = try_initialize_config(&mut program, &payer, 20, 0, 10);
+        assert_escrow_error(result, crate::ErrorCode::InvalidCrankReward);
+
+        // Only the upgrade authority can claim the admin seat
+        let stranger = Keypair::new();
+        program
+            .airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let result = try_initialize_config(&mut program, &stranger, 1_000, 0, 10_000);
+        assert_escrow_error(result, crate::ErrorCode::NotUpgradeAuthority);
+        assert!(program.get_account(&find_config()).is_none());
+
+        try_initialize_config(&mut program, &payer, 1_000, 0, 10_000).unwrap();
+
+        // Only one config
+        let result = try_initialize_config(&mut program, &payer, 1_000, 0, 10_000);
+        assert!(result.is_err());
+
+        let new_admin = Keypair::new();
+        program
+            .airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let result = try_update_config(&mut program, &new_admin, new_admin.pubkey(), 1_000, 10_000);
+        assert!(result.is_err());
+
+        try_update_config(&mut program, &payer, new_admin.pubkey(), 2_000, 5_000).unwrap();
+
+        let config_account = program.get_account(&find_config()).unwrap();
+        let config =
+            crate::state::Config::try_deserialize(&mut config_account.data.as_ref()).unwrap();
+        assert_eq!(config.admin, new_admin.pubkey());
+        assert_eq!(config.default_crank_reward, 2_000);
+        assert_eq!(config.max_crank_reward, 5_000);
+
+        // The old admin has handed over control
+        let result = try_update_config(&mut program, &payer, payer.pubkey(), 2_000, 5_000);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_schedule_is_maker_only() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let (_maker, mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer);
+        let task_queue = Keypair::new().pubkey();
+
+        let stranger = Keypair::new();
+        program
+            .airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let result = schedule_refund(&mut program, &stranger, escrow, mint_a, task_queue, 1, None);
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(anchor_lang::error::ErrorCode::ConstraintHasOne as u32)
+            )
+        );
+        assert_eq!(escrow_state(&program, &escrow).refund_task, None);
+
+        // The maker can still schedule it
+        schedule_refund(&mut program, &payer, escrow, mint_a, task_queue, 1, None).unwrap();
+        assert!(escrow_state(&program, &escrow).refund_task.is_some());
+    }
+
+    #[test]
+    fn test_schedule_crank_reward() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let (maker, mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer);
+        let task_queue = Keypair::new().pubkey();
+
+        let result = schedule_refund(
+            &mut program,
+            &payer,
+            escrow,
+            mint_a,
+            task_queue,
+            1,
+            Some(10_000_001),
+        );
+        assert_escrow_error(result, crate::ErrorCode::CrankRewardOutOfBounds);
+
+        schedule_refund(
+            &mut program,
+            &payer,
+            escrow,
+            mint_a,
+            task_queue,
+            1,
+            Some(2_500_000),
+        )
+        .unwrap();
+
+        let task = task_state(&program, &find_task(&task_queue, 1));
+        assert_eq!(task.crank_reward, 2_500_000);
+        assert_eq!(
+            task.description,
+            format!("refund {}:123", &maker.to_string()[..8])
+        );
+    }
+
+    #[test]
+    fn test_make_uses_default_crank_reward() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        setup_escrow_make_with_token_program(
+            &mut program,
+            &payer,
+            TOKEN_PROGRAM_ID,
+            Some((task_queue, 1)),
+        );
+
+        let task = task_state(&program, &find_task(&task_queue, 1));
+        assert_eq!(task.crank_reward, 1_000_000);
+    }
+
+    fn find_recurring(maker: &Pubkey, seed: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"recurring", maker.as_ref(), &seed.to_le_bytes()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn find_rent_payer(recurring: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"recurring_rent", recurring.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn assert_closed(program: &LiteSVM, address: &Pubkey) {
+        if let Some(account) = program.get_account(address) {
+            assert_eq!(account.lamports, 0);
+            assert_eq!(account.owner, SYSTEM_PROGRAM_ID);
+        }
+    }
+
+    fn find_tranche_escrow(recurring: &Pubkey, index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"escrow", recurring.as_ref(), &index.to_le_bytes()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    // Task id of tranche 0 in `try_make_recurring`
+    const FIRST_TRANCHE_TASK: u16 = 1;
+
+    fn tranche_tasks(task_queue: &Pubkey, tranches: u64) -> Vec<Pubkey> {
+        (0..tranches as u16)
+            .map(|index| find_task(task_queue, FIRST_TRANCHE_TASK + index))
+            .collect()
+    }
+
+    fn set_compute_unit_limit(units: u32) -> Instruction {
+        let mut data = vec![2];
+        data.extend_from_slice(&units.to_le_bytes());
+        Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    // Mints and funds the maker, then sends `make_recurring` with seed 7,
+    // asking 4 mint B per tranche of `tranche_amount`, one tranche an hour,
+    // with the tranche tasks queued on `task_queue`
+    fn try_make_recurring(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        task_queue: Pubkey,
+        deposit: u64,
+        tranche_amount: u64,
+    ) -> (
+        Pubkey,
+        Pubkey,
+        Pubkey,
+        Result<TransactionMetadata, FailedTransactionMetadata>,
+    ) {
+        let maker = payer.pubkey();
+        let mint_a = CreateMint::new(program, payer)
+            .decimals(6)
+            .authority(&maker)
+            .send()
+            .unwrap();
+        let mint_b = CreateMint::new(program, payer)
+            .decimals(6)
+            .authority(&maker)
+            .send()
+            .unwrap();
+        let maker_ata_a = CreateAssociatedTokenAccount::new(program, payer, &mint_a)
+            .owner(&maker)
+            .send()
+            .unwrap();
+        MintTo::new(program, payer, &mint_a, &maker_ata_a, 1_000_000_000)
+            .send()
+            .unwrap();
+
+        let recurring = find_recurring(&maker, 7);
+        let mut accounts = crate::accounts::MakeRecurring {
+            maker,
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            recurring,
+            rent_payer: find_rent_payer(&recurring),
+            vault: associated_token::get_associated_token_address(&recurring, &mint_a),
+            config: find_config(),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            task_queue,
+            task_queue_authority: find_task_queue_authority(&task_queue),
+            queue_authority: find_queue_authority(),
+            tuktuk_program: TUKTUK_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        let tranches = if tranche_amount == 0 {
+            0
+        } else {
+            deposit.div_ceil(tranche_amount)
+        };
+        accounts.extend(
+            tranche_tasks(&task_queue, tranches)
+                .into_iter()
+                .map(|task| AccountMeta::new(task, false)),
+        );
+
+        let make_recurring_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::MakeRecurring {
+                seed: 7,
+                deposit,
+                tranche_amount,
+                tranche_receive: 4,
+                interval: 60 * 60,
+                expiry: 0,
+                first_task_id: FIRST_TRANCHE_TASK,
             }
-            Err(e) => {
-                msg!("Schedule failed: {:?}", e);
+            .data(),
+        };
+
+        // Queueing a task per tranche needs more than the default budget.
+        // Compute budget instructions apply wherever they sit, and placing it
+        // second keeps `make_recurring` errors at instruction 0.
+        program.expire_blockhash();
+        let message = Message::new(
+            &[make_recurring_ix, set_compute_unit_limit(1_000_000)],
+            Some(&maker),
+        );
+        let transaction = Transaction::new(&[payer], message, program.latest_blockhash());
+
+        (
+            mint_a,
+            mint_b,
+            recurring,
+            program.send_transaction(transaction),
+        )
+    }
+
+    // Called by hand rather than through the tranche's task
+    fn try_open_tranche(
+        program: &mut LiteSVM,
+        caller: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        index: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let recurring = find_recurring(&maker, 7);
+        let escrow = find_tranche_escrow(&recurring, index);
+        let open_tranche_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::OpenTranche {
+                maker,
+                mint_a,
+                recurring,
+                rent_payer: find_rent_payer(&recurring),
+                recurring_vault: associated_token::get_associated_token_address(
+                    &recurring, &mint_a,
+                ),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
             }
+            .to_account_metas(None),
+            data: crate::instruction::OpenTranche { index }.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[open_tranche_ix], Some(&caller.pubkey()));
+        let transaction = Transaction::new(&[caller], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Cranks a queued task through the mock's `run_task_v0` the way a real
+    // cranker does, reading the accounts off the task's compiled transaction
+    fn crank_task(
+        program: &mut LiteSVM,
+        crank_turner: &Keypair,
+        task_queue: Pubkey,
+        task_id: u16,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let task = find_task(&task_queue, task_id);
+        let task_data = task_state(program, &task);
+        let TransactionSourceV0::CompiledV0(transaction) = task_data.transaction else {
+            panic!("Task has no compiled transaction");
+        };
+
+        let num_signers = (transaction.num_rw_signers + transaction.num_ro_signers) as usize;
+        let num_rw = transaction.num_rw as usize;
+        let mut accounts = tuktuk_mock::accounts::RunTaskV0 {
+            crank_turner: crank_turner.pubkey(),
+            rent_refund: task_data.rent_refund,
+            task_queue,
+            task,
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            transaction
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(index, account)| {
+                    if index < transaction.num_rw_signers as usize
+                        || (index >= num_signers && index < num_signers + num_rw)
+                    {
+                        AccountMeta::new(*account, false)
+                    } else {
+                        AccountMeta::new_readonly(*account, false)
+                    }
+                }),
+        );
+
+        let run_task_ix = Instruction {
+            program_id: TUKTUK_PROGRAM_ID,
+            accounts,
+            data: tuktuk_mock::instruction::RunTaskV0 {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[run_task_ix], Some(&crank_turner.pubkey()));
+        let transaction = Transaction::new(&[crank_turner], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_close_recurring(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint_a: Pubkey,
+        task_queue: Pubkey,
+        tasks: Vec<Pubkey>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let recurring = find_recurring(&maker.pubkey(), 7);
+        let mut accounts = crate::accounts::CloseRecurring {
+            maker: maker.pubkey(),
+            mint_a,
+            maker_ata_a: associated_token::get_associated_token_address(&maker.pubkey(), &mint_a),
+            recurring,
+            rent_payer: find_rent_payer(&recurring),
+            vault: associated_token::get_associated_token_address(&recurring, &mint_a),
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            task_queue,
+            task_queue_authority: find_task_queue_authority(&task_queue),
+            queue_authority: find_queue_authority(),
+            tuktuk_program: TUKTUK_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(tasks.into_iter().map(|task| AccountMeta::new(task, false)));
+
+        let close_recurring_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::CloseRecurring {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[close_recurring_ix], Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[maker], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    #[test]
+    fn test_recurring_tranches() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let start = program.get_sysvar::<Clock>().unix_timestamp;
+        let (mint_a, mint_b, recurring, result) =
+            try_make_recurring(&mut program, &payer, task_queue, 25, 10);
+        result.unwrap();
+
+        let maker = payer.pubkey();
+        let rent_payer = find_rent_payer(&recurring);
+        let recurring_vault = associated_token::get_associated_token_address(&recurring, &mint_a);
+        let tranche = |index| {
+            let escrow = find_tranche_escrow(&recurring, index);
+            (
+                escrow,
+                associated_token::get_associated_token_address(&escrow, &mint_a),
+            )
+        };
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        // One task per tranche, an hour apart, all paid for by the rent payer
+        let tasks = tranche_tasks(&task_queue, 3);
+        for (index, task) in tasks.iter().enumerate() {
+            let task = task_state(&program, task);
+            assert_eq!(task.rent_refund, rent_payer);
+            assert_eq!(task.crank_reward, 1_000_000);
+            assert!(matches!(
+                task.trigger,
+                TriggerV0::Timestamp(timestamp) if timestamp == start + index as i64 * 60 * 60
+            ));
+        }
+
+        let crank_turner = Keypair::new();
+        program
+            .airdrop(&crank_turner.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+
+        // The first task opens a full tranche right away
+        crank_task(&mut program, &crank_turner, task_queue, FIRST_TRANCHE_TASK).unwrap();
+        let (escrow, tranche_vault) = tranche(0);
+        assert_eq!(token_amount(&program, &tranche_vault), 10);
+        assert_eq!(token_amount(&program, &recurring_vault), 15);
+        assert_eq!(escrow_state(&program, &escrow).receive, 4);
+        assert_eq!(escrow_state(&program, &escrow).recurring, Some(recurring));
+        assert_closed(&program, &tasks[0]);
+
+        // The next one isn't due within the interval
+        let result = crank_task(
+            &mut program,
+            &crank_turner,
+            task_queue,
+            FIRST_TRANCHE_TASK + 1,
+        );
+        assert!(result.is_err());
+
+        // Each tranche has its own escrow, so the second opens while the first is still up
+        warp_to(&mut program, start + 60 * 60);
+        crank_task(
+            &mut program,
+            &crank_turner,
+            task_queue,
+            FIRST_TRANCHE_TASK + 1,
+        )
+        .unwrap();
+        assert_eq!(token_amount(&program, &tranche_vault), 10);
+        assert_eq!(token_amount(&program, &tranche(1).1), 10);
+        assert_eq!(token_amount(&program, &recurring_vault), 5);
+
+        try_take(&mut program, &taker, maker, mint_a, mint_b, escrow, None).unwrap();
+        assert_closed(&program, &escrow);
+
+        // The final tranche is what is left, asking a proportional amount
+        warp_to(&mut program, start + 2 * 60 * 60);
+        crank_task(
+            &mut program,
+            &crank_turner,
+            task_queue,
+            FIRST_TRANCHE_TASK + 2,
+        )
+        .unwrap();
+        let (final_escrow, final_vault) = tranche(2);
+        assert_eq!(token_amount(&program, &final_vault), 5);
+        assert_eq!(token_amount(&program, &recurring_vault), 0);
+        assert_eq!(escrow_state(&program, &final_escrow).receive, 2);
+
+        try_take(
+            &mut program,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            tranche(1).0,
+            None,
+        )
+        .unwrap();
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        assert_eq!(token_amount(&program, &taker_ata_a), 20);
+
+        // Every task has run; closing hands back what is left of the rent.
+        // The open tranche stays.
+        try_close_recurring(&mut program, &payer, mint_a, task_queue, tasks).unwrap();
+        assert_closed(&program, &recurring);
+        assert_closed(&program, &recurring_vault);
+        assert_closed(&program, &rent_payer);
+        assert_eq!(token_amount(&program, &final_vault), 5);
+    }
+
+    #[test]
+    fn test_open_tranche_ahead_of_its_task() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (mint_a, _mint_b, recurring, result) =
+            try_make_recurring(&mut program, &payer, task_queue, 25, 10);
+        result.unwrap();
+        let maker = payer.pubkey();
+        let recurring_vault = associated_token::get_associated_token_address(&recurring, &mint_a);
+
+        let stranger = Keypair::new();
+        program
+            .airdrop(&stranger.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+
+        // Tranches open in order, each once it is due
+        let result = try_open_tranche(&mut program, &stranger, maker, mint_a, 1);
+        assert_escrow_error(result, crate::ErrorCode::TrancheNotDue);
+        try_open_tranche(&mut program, &stranger, maker, mint_a, 0).unwrap();
+        assert_eq!(token_amount(&program, &recurring_vault), 15);
+
+        // The task still completes, without opening the tranche twice
+        crank_task(&mut program, &stranger, task_queue, FIRST_TRANCHE_TASK).unwrap();
+        assert_closed(&program, &find_task(&task_queue, FIRST_TRANCHE_TASK));
+        assert_eq!(token_amount(&program, &recurring_vault), 15);
+        let escrow = find_tranche_escrow(&recurring, 0);
+        assert_eq!(
+            token_amount(
+                &program,
+                &associated_token::get_associated_token_address(&escrow, &mint_a)
+            ),
+            10
+        );
+    }
+
+    #[test]
+    fn test_close_recurring_dequeues_pending_tasks() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (mint_a, _mint_b, recurring, result) =
+            try_make_recurring(&mut program, &payer, task_queue, 25, 10);
+        result.unwrap();
+
+        let crank_turner = Keypair::new();
+        program
+            .airdrop(&crank_turner.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        crank_task(&mut program, &crank_turner, task_queue, FIRST_TRANCHE_TASK).unwrap();
+
+        // Only the offer's own tasks, in order
+        let mut tasks = tranche_tasks(&task_queue, 3);
+        tasks.swap(1, 2);
+        let result = try_close_recurring(&mut program, &payer, mint_a, task_queue, tasks);
+        assert_escrow_error(result, crate::ErrorCode::TrancheTaskMismatch);
+
+        let result = try_close_recurring(
+            &mut program,
+            &payer,
+            mint_a,
+            task_queue,
+            tranche_tasks(&task_queue, 2),
+        );
+        assert_escrow_error(result, crate::ErrorCode::MissingTuktukAccounts);
+
+        let tasks = tranche_tasks(&task_queue, 3);
+        try_close_recurring(&mut program, &payer, mint_a, task_queue, tasks.clone()).unwrap();
+        for task in &tasks {
+            assert_closed(&program, task);
         }
+        assert_closed(&program, &recurring);
+        assert_closed(&program, &find_rent_payer(&recurring));
+    }
+
+    #[test]
+    fn test_make_recurring_rejects_zero_tranche() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (_mint_a, _mint_b, _recurring, result) =
+            try_make_recurring(&mut program, &payer, task_queue, 25, 0);
+        assert_escrow_error(result, crate::ErrorCode::InvalidRecurringOffer);
+    }
+
+    #[test]
+    fn test_make_recurring_rejects_too_many_tranches() {
+        let (mut program, payer) = setup();
+        setup_scheduling(&mut program, &payer);
+
+        let task_queue = Keypair::new().pubkey();
+        let (_mint_a, _mint_b, _recurring, result) =
+            try_make_recurring(&mut program, &payer, task_queue, 90, 10);
+        assert_escrow_error(result, crate::ErrorCode::TooManyTranches);
     }
 }
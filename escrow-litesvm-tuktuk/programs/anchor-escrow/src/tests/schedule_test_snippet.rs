@@ -1,97 +0,0 @@
-#[test]
-fn test_schedule() {
-    let (mut program, payer) = setup();
-
-    // Load Tuktuk Program from Devnet
-    let rpc_client = RpcClient::new("https://api.devnet.solana.com");
-    let tuktuk_program_id =
-        Pubkey::from_str("tuktukUrfhXT6ZT77QTU8RQtvgL967uRuVagWF57zVA").unwrap();
-
-    // Fetch program account
-    let tuktuk_account = rpc_client
-        .get_account(&tuktuk_program_id)
-        .expect("Failed to fetch Tuktuk program");
-    program
-        .set_account(
-            tuktuk_program_id,
-            Account {
-                lamports: tuktuk_account.lamports,
-                data: tuktuk_account.data.clone(), // This is the program account, maybe we need the executable data?
-                // Ideally we need the BPF loader to execute it.
-                // If it's BPF upgradeable, the logic is more complex (Program Account -> Program Data Account).
-                owner: tuktuk_account.owner,
-                executable: tuktuk_account.executable,
-                rent_epoch: tuktuk_account.rent_epoch,
-            },
-        )
-        .unwrap();
-
-    // For BPF Upgradeable, the code is in the Program Data account.
-    // We might need to fetch that too if litesvm needs it properly linked.
-    // But for simply mocking existance to pass "Program not found", setting the program account might be enough
-    // IF we don't strictly execute it or if litesvm handles simple shell.
-    // But we want actual CPI.
-
-    // Let's assume for now we just want to verify we can call it.
-    // If Tuktuk logic is complex, it might fail inside Tuktuk if state isn't set up (Task Queue, etc).
-    // This test sets up the instruction call structure.
-
-    let (maker, mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
-        setup_escrow_make(&mut program, &payer);
-
-    // We need Tuktuk accounts: task_queue, task_queue_authority, queue_authority, task.
-    // We can just generate random keys for these since we are unlikely to pass Tuktuk's internal checks
-    // without a full Tuktuk setup (initializing queue, etc).
-    // BUT, if we want to confirm `escrow` program logic, we just need `schedule` to run.
-    // If `schedule` fails because Tuktuk returns error, that means `schedule` successfully CPI'd.
-
-    let task_queue = Keypair::new().pubkey();
-    let task_queue_authority = Keypair::new().pubkey();
-    let queue_authority = Pubkey::find_program_address(&[b"queue_authority"], &crate::ID).0;
-    // Task PDA derivation might be specific to Tuktuk.
-
-    let task_id = 1u16;
-    // In `tuktuk-counter`, task is derived from `task_key(task_queue, taskID)`.
-    // We can mimic that derivation if needed, or just pass a random key.
-    let task = Keypair::new().pubkey();
-
-    let schedule_ix = Instruction {
-        program_id: PROGRAM_ID,
-        accounts: crate::accounts::Schedule {
-            user: payer.pubkey(),
-            escrow,
-            task_queue,
-            task_queue_authority,
-            task,
-            queue_authority,
-            system_program: SYSTEM_PROGRAM_ID,
-            tuktuk_program: tuktuk_program_id,
-        }
-        .to_account_metas(None),
-        data: crate::instruction::Schedule { task_id }.data(),
-    };
-
-    let message = Message::new(&[schedule_ix], Some(&payer.pubkey()));
-    let transaction = Transaction::new(&[&payer], message, program.latest_blockhash());
-
-    let tx_result = program.send_transaction(transaction);
-
-    // We expect it might fail inside Tuktuk, but we want to ensure `schedule` itself ran.
-    // If it returns success, great. If it fails with Tuktuk error, also fine.
-    // If it fails with "Program not found", then loading failed.
-
-    match tx_result {
-        Ok(tx) => {
-            msg!("Schedule successful");
-            msg!("Tx Signature: {}", tx.signature);
-        }
-        Err(e) => {
-            msg!(
-                "Schedule failed (expected if Tuktuk state missing): {:?}",
-                e
-            );
-            // Assert that failure is NOT "Program not found"
-            // assert!(!format!("{:?}", e).contains("Program not found"));
-        }
-    }
-}
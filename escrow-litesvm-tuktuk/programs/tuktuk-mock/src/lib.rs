@@ -0,0 +1,252 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+//! Stand-in for the tuktuk program, deployed at the real tuktuk address inside
+//! LiteSVM. It accepts the same `queue_task_v0` CPI as tuktuk, reusing the
+//! argument types from `tuktuk-program`, and stores the task in a PDA at the
+//! same address tuktuk would use. `run_task_v0` plays the cranker: once the
+//! trigger has passed it executes the compiled transaction against the
+//! remaining accounts, pays the crank reward and closes the task.
+//! `dequeue_task_v0` closes a task early, refunding its rent and reward.
+//!
+//! Task queues are not modelled. The queue and its authority are only
+//! address-checked, and `signer_seeds` in compiled transactions are not supported.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use tuktuk_program::{tuktuk::types::TriggerV0, types::QueueTaskArgsV0, TransactionSourceV0};
+
+declare_id!("tuktukUrfhXT6ZT77QTU8RQtvgL967uRuVagWF57zVA");
+
+#[program]
+pub mod tuktuk_mock {
+    use super::*;
+
+    pub fn queue_task_v0(ctx: Context<QueueTaskV0>, args: QueueTaskArgsV0) -> Result<()> {
+        ctx.accounts.queue(args, &ctx.bumps)
+    }
+
+    pub fn dequeue_task_v0(_ctx: Context<DequeueTaskV0>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn run_task_v0<'info>(ctx: Context<'_, '_, 'info, 'info, RunTaskV0<'info>>) -> Result<()> {
+        ctx.accounts.run(ctx.remaining_accounts)
+    }
+}
+
+#[account]
+pub struct TaskV0 {
+    pub task_queue: Pubkey,
+    pub queue_authority: Pubkey,
+    pub rent_refund: Pubkey,
+    pub id: u16,
+    pub trigger: TriggerV0,
+    pub transaction: TransactionSourceV0,
+    pub crank_reward: u64,
+    pub queued_at: i64,
+    pub bump_seed: u8,
+    pub description: String,
+}
+
+impl TaskV0 {
+    pub fn space(args: &QueueTaskArgsV0) -> usize {
+        8 + 32
+            + 32
+            + 32
+            + 2
+            + serialized_len(&args.trigger)
+            + serialized_len(&args.transaction)
+            + 8
+            + 8
+            + 1
+            + 4
+            + args.description.len()
+    }
+}
+
+fn serialized_len<T: AnchorSerialize>(value: &T) -> usize {
+    let mut buf = Vec::new();
+    value.serialize(&mut buf).unwrap();
+    buf.len()
+}
+
+#[derive(Accounts)]
+#[instruction(args: QueueTaskArgsV0)]
+pub struct QueueTaskV0<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub queue_authority: Signer<'info>,
+    /// CHECK: Only the address is checked, queues are not modelled
+    #[account(
+        seeds = [b"task_queue_authority", task_queue.key().as_ref(), queue_authority.key().as_ref()],
+        bump,
+    )]
+    pub task_queue_authority: UncheckedAccount<'info>,
+    /// CHECK: Queues are not modelled
+    #[account(mut)]
+    pub task_queue: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = TaskV0::space(&args),
+        seeds = [b"task", task_queue.key().as_ref(), &args.id.to_le_bytes()[..]],
+        bump,
+    )]
+    pub task: Account<'info, TaskV0>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> QueueTaskV0<'info> {
+    pub fn queue(&mut self, args: QueueTaskArgsV0, bumps: &QueueTaskV0Bumps) -> Result<()> {
+        let crank_reward = args.crank_reward.unwrap_or(0);
+
+        // Escrow the reward in the task so the cranker can be paid from it
+        if crank_reward > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.payer.to_account_info(),
+                        to: self.task.to_account_info(),
+                    },
+                ),
+                crank_reward,
+            )?;
+        }
+
+        self.task.set_inner(TaskV0 {
+            task_queue: self.task_queue.key(),
+            queue_authority: self.queue_authority.key(),
+            rent_refund: self.payer.key(),
+            id: args.id,
+            trigger: args.trigger,
+            transaction: args.transaction,
+            crank_reward,
+            queued_at: Clock::get()?.unix_timestamp,
+            bump_seed: bumps.task,
+            description: args.description,
+        });
+
+        Ok(())
+    }
+}
+
+// The queue authority that queued the task cancels it
+#[derive(Accounts)]
+pub struct DequeueTaskV0<'info> {
+    pub queue_authority: Signer<'info>,
+    /// CHECK: Via has_one
+    #[account(mut)]
+    pub rent_refund: UncheckedAccount<'info>,
+    /// CHECK: Only the address is checked, queues are not modelled
+    #[account(
+        seeds = [b"task_queue_authority", task_queue.key().as_ref(), queue_authority.key().as_ref()],
+        bump,
+    )]
+    pub task_queue_authority: UncheckedAccount<'info>,
+    /// CHECK: Via has_one
+    #[account(mut)]
+    pub task_queue: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = rent_refund,
+        has_one = queue_authority,
+        has_one = rent_refund,
+        has_one = task_queue,
+    )]
+    pub task: Account<'info, TaskV0>,
+}
+
+#[derive(Accounts)]
+pub struct RunTaskV0<'info> {
+    #[account(mut)]
+    pub crank_turner: Signer<'info>,
+    /// CHECK: Via has_one
+    #[account(mut)]
+    pub rent_refund: UncheckedAccount<'info>,
+    /// CHECK: Via has_one
+    pub task_queue: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = rent_refund,
+        has_one = rent_refund,
+        has_one = task_queue,
+        seeds = [b"task", task_queue.key().as_ref(), &task.id.to_le_bytes()[..]],
+        bump = task.bump_seed,
+    )]
+    pub task: Account<'info, TaskV0>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RunTaskV0<'info> {
+    // `remaining_accounts` are the accounts of the compiled transaction, in order
+    pub fn run(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        if let TriggerV0::Timestamp(timestamp) = self.task.trigger {
+            require!(
+                Clock::get()?.unix_timestamp >= timestamp,
+                MockError::TaskNotReady
+            );
+        }
+
+        let TransactionSourceV0::CompiledV0(transaction) = &self.task.transaction else {
+            return err!(MockError::Unsupported);
+        };
+        require!(transaction.signer_seeds.is_empty(), MockError::Unsupported);
+
+        let num_signers = (transaction.num_rw_signers + transaction.num_ro_signers) as usize;
+        let num_rw = transaction.num_rw as usize;
+
+        for compiled in transaction.instructions.iter() {
+            let program = remaining_accounts
+                .get(compiled.program_id_index as usize)
+                .ok_or(MockError::MissingAccount)?;
+
+            let mut accounts = Vec::with_capacity(compiled.accounts.len());
+            for index in compiled.accounts.iter().map(|index| *index as usize) {
+                let account = remaining_accounts
+                    .get(index)
+                    .ok_or(MockError::MissingAccount)?;
+                let is_writable = index < transaction.num_rw_signers as usize
+                    || (index >= num_signers && index < num_signers + num_rw);
+
+                accounts.push(if is_writable {
+                    AccountMeta::new(account.key(), false)
+                } else {
+                    AccountMeta::new_readonly(account.key(), false)
+                });
+            }
+
+            invoke(
+                &Instruction {
+                    program_id: program.key(),
+                    accounts,
+                    data: compiled.data.clone(),
+                },
+                remaining_accounts,
+            )?;
+        }
+
+        // Pay the cranker out of the escrowed reward; `close` returns the rest
+        let reward = self.task.crank_reward;
+        if reward > 0 {
+            self.task.sub_lamports(reward)?;
+            self.crank_turner.add_lamports(reward)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[error_code]
+pub enum MockError {
+    #[msg("Task trigger has not been reached")]
+    TaskNotReady,
+    #[msg("Remote transactions and signer seeds are not supported by the mock")]
+    Unsupported,
+    #[msg("Compiled transaction references an account that was not passed")]
+    MissingAccount,
+}
@@ -78,7 +78,7 @@ describe("schedule-refund", () => {
 
         try {
             const tx = await program.methods
-                .make(seed, deposit, receive, expiry)
+                .make(seed, deposit, receive, expiry, null)
                 .accounts({
                     maker: maker.publicKey,
                     mintA: mintA,
@@ -174,7 +174,7 @@ describe("schedule-refund", () => {
         });
 
         await program.methods
-            .make(seed2, deposit, receive, expiry)
+            .make(seed2, deposit, receive, expiry, null)
             .accounts({
                 maker: maker.publicKey,
                 mintA: mintA,
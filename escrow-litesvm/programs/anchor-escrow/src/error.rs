@@ -0,0 +1,17 @@
+use anchor_lang::error_code;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("The escrow is locked and cannot be taken yet")]
+    EscrowLocked,
+    #[msg("The escrow has expired and can no longer be taken")]
+    EscrowExpired,
+    #[msg("The lock must end before the expiry, and the expiry must be in the future")]
+    InvalidTimeWindow,
+    #[msg("Only the maker or its operator can do this")]
+    Unauthorized,
+    #[msg("Remaining accounts must be (escrow, maker, vault, maker_ata_b) groups")]
+    InvalidEscrowAccounts,
+    #[msg("The escrow's price is not the one the taker agreed to pay")]
+    PriceChanged,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::EscrowError, state::Escrow};
+
+// Reprices the offer or moves its time window. Signed by the maker or its operator.
+#[derive(Accounts)]
+pub struct Amend<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = escrow.is_authority(authority.key) @ EscrowError::Unauthorized,
+        seeds = [b"escrow", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+impl<'info> Amend<'info> {
+    pub fn amend(
+        &mut self,
+        receive: u64,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        Escrow::check_time_window(lock_until, expiry)?;
+
+        self.escrow.receive = receive;
+        self.escrow.lock_until = lock_until;
+        self.escrow.expiry = expiry;
+
+        Ok(())
+    }
+}
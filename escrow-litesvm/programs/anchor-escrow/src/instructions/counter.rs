@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    state::{CounterOffer, Escrow},
+};
+
+#[derive(Accounts)]
+pub struct Counter<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = mint_b,
+        seeds = [b"escrow", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"counter", escrow.key().as_ref(), taker.key().as_ref()],
+        bump,
+        space = 8 + CounterOffer::INIT_SPACE,
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+    #[account(
+        init,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = counter_offer,
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Counter<'info> {
+    pub fn init_counter(&mut self, receive: u64, bumps: &CounterBumps) -> Result<()> {
+        // No point proposing on an offer that can no longer be filled
+        if let Some(expiry) = self.escrow.expiry {
+            require!(
+                Clock::get()?.unix_timestamp < expiry,
+                EscrowError::EscrowExpired
+            );
+        }
+
+        self.counter_offer.set_inner(CounterOffer {
+            escrow: self.escrow.key(),
+            taker: self.taker.key(),
+            mint_b: self.mint_b.key(),
+            receive,
+            bump: bumps.counter_offer,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, receive: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.taker_ata_b.to_account_info(),
+            to: self.counter_vault.to_account_info(),
+            authority: self.taker.to_account_info(),
+            mint: self.mint_b.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer_checked(cpi_ctx, receive, self.mint_b.decimals)
+    }
+}
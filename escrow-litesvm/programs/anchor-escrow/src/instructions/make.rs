@@ -40,14 +40,26 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn init_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        receive: u64,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+        operator: Option<Pubkey>,
+        bumps: &MakeBumps,
+    ) -> Result<()> {
+        Escrow::check_time_window(lock_until, expiry)?;
+
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             receive,
-            deadline: Clock::get()?.unix_timestamp + 86400 * 5, // 5 days
+            lock_until,
+            expiry,
+            operator,
             bump: bumps.escrow,
         });
 
@@ -1,7 +1,19 @@
+pub mod accept_counter;
+pub mod amend;
+pub mod counter;
 pub mod make;
 pub mod refund;
+pub mod set_operator;
 pub mod take;
+pub mod take_many;
+pub mod withdraw_counter;
 
+pub use accept_counter::*;
+pub use amend::*;
+pub use counter::*;
 pub use make::*;
 pub use refund::*;
-pub use take::*;
\ No newline at end of file
+pub use set_operator::*;
+pub use take::*;
+pub use take_many::*;
+pub use withdraw_counter::*;
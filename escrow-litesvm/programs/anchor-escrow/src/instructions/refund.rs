@@ -1,12 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account};
 
-use crate::state::Escrow;
+use crate::{error::EscrowError, state::Escrow};
 
+// Signed by the maker or its operator. Funds and rent always go back to the maker.
 #[derive(Accounts)]
 pub struct Refund<'info> {
     #[account(mut)]
-    maker: Signer<'info>,
+    authority: Signer<'info>,
+    #[account(mut)]
+    maker: SystemAccount<'info>,
     mint_a: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
@@ -19,6 +22,7 @@ pub struct Refund<'info> {
         close = maker,
         has_one = mint_a,
         has_one = maker,
+        constraint = escrow.is_authority(authority.key) @ EscrowError::Unauthorized,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
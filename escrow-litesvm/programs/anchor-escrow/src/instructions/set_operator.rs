@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Escrow;
+
+// Only the maker can register, replace or clear the operator
+#[derive(Accounts)]
+pub struct SetOperator<'info> {
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+impl<'info> SetOperator<'info> {
+    pub fn set_operator(&mut self, operator: Option<Pubkey>) -> Result<()> {
+        self.escrow.operator = operator;
+
+        Ok(())
+    }
+}
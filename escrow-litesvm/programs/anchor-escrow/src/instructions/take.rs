@@ -63,13 +63,11 @@ pub struct Take<'info> {
 //Transfer tokens from vault to taker
 //Close vault account
 impl<'info> Take<'info> {
-    pub fn deposit(&mut self) -> Result<()> {
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
-
-        if current_time <= self.escrow.deadline {
-            return Err(ErrorCode::ConstraintSeeds.into()); // Using a generic error for now, better to define a custom one
-        }
+    // `expected_receive` is the price the taker saw, so an `amend` landing
+    // first can't change what they pay
+    pub fn deposit(&mut self, expected_receive: u64) -> Result<()> {
+        self.escrow.check_takeable()?;
+        self.escrow.check_price(expected_receive)?;
 
         let cpi_program = self.token_program.to_account_info();
 
@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{error::EscrowError, state::Escrow};
+
+// Same as `Take`, but for every escrow passed in `remaining_accounts` as
+// (escrow, maker, vault, maker_ata_b) groups. All escrows must share the
+// mint pair, and the makers' mint B ATAs must already exist.
+// `expected_receive` holds the agreed price of each group, in order.
+#[derive(Accounts)]
+pub struct TakeMany<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeMany<'info> {
+    pub fn take_many(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        expected_receive: &[u64],
+    ) -> Result<()> {
+        require!(
+            !remaining_accounts.is_empty()
+                && remaining_accounts.len() % 4 == 0
+                && remaining_accounts.len() / 4 == expected_receive.len(),
+            EscrowError::InvalidEscrowAccounts
+        );
+
+        for (group, expected_receive) in remaining_accounts.chunks_exact(4).zip(expected_receive) {
+            self.take_one(
+                &group[0],
+                &group[1],
+                &group[2],
+                &group[3],
+                *expected_receive,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Re-applies the `Take` constraints by hand, then settles and closes one escrow
+    fn take_one(
+        &self,
+        escrow_info: &'info AccountInfo<'info>,
+        maker: &'info AccountInfo<'info>,
+        vault_info: &'info AccountInfo<'info>,
+        maker_ata_b: &'info AccountInfo<'info>,
+        expected_receive: u64,
+    ) -> Result<()> {
+        require!(
+            escrow_info.is_writable
+                && maker.is_writable
+                && vault_info.is_writable
+                && maker_ata_b.is_writable,
+            EscrowError::InvalidEscrowAccounts
+        );
+
+        let escrow = Account::<Escrow>::try_from(escrow_info)?;
+
+        // has_one = maker, mint_a, mint_b
+        require_keys_eq!(
+            escrow.maker,
+            maker.key(),
+            EscrowError::InvalidEscrowAccounts
+        );
+        require_keys_eq!(
+            escrow.mint_a,
+            self.mint_a.key(),
+            EscrowError::InvalidEscrowAccounts
+        );
+        require_keys_eq!(
+            escrow.mint_b,
+            self.mint_b.key(),
+            EscrowError::InvalidEscrowAccounts
+        );
+
+        let seed = escrow.seed.to_le_bytes();
+        let bump = [escrow.bump];
+        let seeds: &[&[u8]] = &[b"escrow", maker.key.as_ref(), &seed, &bump];
+        let expected_escrow = Pubkey::create_program_address(seeds, &crate::ID)
+            .map_err(|_| error!(EscrowError::InvalidEscrowAccounts))?;
+        require_keys_eq!(
+            expected_escrow,
+            escrow.key(),
+            EscrowError::InvalidEscrowAccounts
+        );
+
+        let token_program = self.token_program.key();
+        require_keys_eq!(
+            vault_info.key(),
+            get_associated_token_address_with_program_id(
+                &escrow.key(),
+                &self.mint_a.key(),
+                &token_program
+            ),
+            EscrowError::InvalidEscrowAccounts
+        );
+        require_keys_eq!(
+            maker_ata_b.key(),
+            get_associated_token_address_with_program_id(
+                maker.key,
+                &self.mint_b.key(),
+                &token_program
+            ),
+            EscrowError::InvalidEscrowAccounts
+        );
+        let vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+
+        escrow.check_takeable()?;
+        escrow.check_price(expected_receive)?;
+
+        let cpi_accounts = TransferChecked {
+            from: self.taker_ata_b.to_account_info(),
+            to: maker_ata_b.clone(),
+            authority: self.taker.to_account_info(),
+            mint: self.mint_b.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(cpi_ctx, escrow.receive, self.mint_b.decimals)?;
+
+        let signer_seeds: [&[&[u8]]; 1] = [seeds];
+
+        let cpi_accounts = TransferChecked {
+            from: vault_info.clone(),
+            to: self.taker_ata_a.to_account_info(),
+            authority: escrow_info.clone(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            &signer_seeds,
+        );
+
+        transfer_checked(cpi_context, vault.amount, self.mint_a.decimals)?;
+
+        let cpi_accounts = CloseAccount {
+            account: vault_info.clone(),
+            destination: maker.clone(),
+            authority: escrow_info.clone(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            &signer_seeds,
+        );
+
+        close_account(cpi_context)?;
+
+        // Closing also stops the same escrow from being settled twice in one sweep
+        escrow.close(maker.clone())
+    }
+}
@@ -3,6 +3,7 @@
 
 use anchor_lang::prelude::*;
 
+mod error;
 mod instructions;
 mod state;
 mod tests;
@@ -15,8 +16,17 @@ declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 pub mod anchor_escrow {
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, deposit: u64, receive: u64) -> Result<()> {
-        ctx.accounts.init_escrow(seed, receive, &ctx.bumps)?;
+    pub fn make(
+        ctx: Context<Make>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+        operator: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts
+            .init_escrow(seed, receive, lock_until, expiry, operator, &ctx.bumps)?;
         ctx.accounts.deposit(deposit)
     }
 
@@ -24,8 +34,43 @@ pub mod anchor_escrow {
         ctx.accounts.refund_and_close_vault()
     }
 
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.deposit()?;
+    pub fn set_operator(ctx: Context<SetOperator>, operator: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.set_operator(operator)
+    }
+
+    pub fn amend(
+        ctx: Context<Amend>,
+        receive: u64,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.amend(receive, lock_until, expiry)
+    }
+
+    pub fn take(ctx: Context<Take>, expected_receive: u64) -> Result<()> {
+        ctx.accounts.deposit(expected_receive)?;
+        ctx.accounts.withdraw_and_close_vault()
+    }
+
+    pub fn take_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakeMany<'info>>,
+        expected_receive: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .take_many(ctx.remaining_accounts, &expected_receive)
+    }
+
+    pub fn counter(ctx: Context<Counter>, receive: u64) -> Result<()> {
+        ctx.accounts.init_counter(receive, &ctx.bumps)?;
+        ctx.accounts.deposit(receive)
+    }
+
+    pub fn accept_counter(ctx: Context<AcceptCounter>) -> Result<()> {
+        ctx.accounts.release_counter_vault()?;
+        ctx.accounts.release_vault()
+    }
+
+    pub fn withdraw_counter(ctx: Context<WithdrawCounter>) -> Result<()> {
         ctx.accounts.withdraw_and_close_vault()
     }
 }
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// A taker's proposal to fill an escrow for a different amount of mint B.
+/// The proposed amount is locked in the counter vault until the maker
+/// accepts it or the taker withdraws it.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct CounterOffer {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: u8,
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::error::EscrowError;
+
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Escrow {
@@ -8,6 +10,55 @@ pub struct Escrow {
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
     pub receive: u64,
-    pub deadline: i64,
+    /// Unix timestamp before which the escrow cannot be taken
+    pub lock_until: Option<i64>,
+    /// Unix timestamp from which the escrow can no longer be taken
+    pub expiry: Option<i64>,
+    /// Key allowed to refund or amend on the maker's behalf
+    pub operator: Option<Pubkey>,
     pub bump: u8,
 }
+
+impl Escrow {
+    /// The maker, or the operator if one is registered
+    pub fn is_authority(&self, key: &Pubkey) -> bool {
+        self.maker == *key || self.operator == Some(*key)
+    }
+
+    /// Takeable in [lock_until, expiry)
+    pub fn check_takeable(&self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if let Some(lock_until) = self.lock_until {
+            require!(current_time >= lock_until, EscrowError::EscrowLocked);
+        }
+
+        if let Some(expiry) = self.expiry {
+            require!(current_time < expiry, EscrowError::EscrowExpired);
+        }
+
+        Ok(())
+    }
+
+    /// The taker pays exactly the price they agreed to
+    pub fn check_price(&self, expected_receive: u64) -> Result<()> {
+        require!(self.receive == expected_receive, EscrowError::PriceChanged);
+
+        Ok(())
+    }
+
+    pub fn check_time_window(lock_until: Option<i64>, expiry: Option<i64>) -> Result<()> {
+        if let Some(expiry) = expiry {
+            require!(
+                expiry > Clock::get()?.unix_timestamp,
+                EscrowError::InvalidTimeWindow
+            );
+
+            if let Some(lock_until) = lock_until {
+                require!(lock_until < expiry, EscrowError::InvalidTimeWindow);
+            }
+        }
+
+        Ok(())
+    }
+}
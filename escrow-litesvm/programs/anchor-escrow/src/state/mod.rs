@@ -1,3 +1,5 @@
+pub mod counter_offer;
 pub mod escrow;
 
-pub use escrow::*;
\ No newline at end of file
+pub use counter_offer::*;
+pub use escrow::*;
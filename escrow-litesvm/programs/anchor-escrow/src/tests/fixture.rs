@@ -0,0 +1,54 @@
+// Account fixtures for the LiteSVM tests.
+//
+// escrow-litesvm-tuktuk includes this file with `#[path]` rather than keeping
+// its own copy, so the fixtures directory is resolved through the
+// `escrow-litesvm` project from either crate's manifest dir (both live at
+// `<project>/programs/anchor-escrow`).
+
+use {
+    base64::{engine::general_purpose::STANDARD as BASE64, Engine},
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    std::{path::PathBuf, str::FromStr},
+};
+
+const FIXTURES_DIR: &str = "../../../escrow-litesvm/programs/anchor-escrow/src/tests/fixtures";
+
+// Synthetic, not a devnet capture: written by hand as a system-owned wallet
+// holding 5 SOL, in place of the devnet account DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2
+pub const SYNTHETIC_PAYER_FIXTURE: &str = "synthetic/payer";
+
+// Loads an account snapshot from `src/tests/fixtures/<name>.json`.
+// The format is the output of `solana account <address> --output json`.
+// Captures sit at the top level, named by address, and
+// `scripts/refresh-fixtures.sh` regenerates them when online; hand-written
+// fixtures live under `synthetic/` and the script leaves them alone.
+pub fn load_fixture(name: &str) -> (Pubkey, Account) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(FIXTURES_DIR)
+        .join(format!("{}.json", name));
+    let raw = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing account fixture {:?}", path));
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&raw).expect("Fixture is not valid JSON");
+
+    let pubkey = Pubkey::from_str(snapshot["pubkey"].as_str().unwrap()).unwrap();
+    let account = &snapshot["account"];
+
+    // data is ["<payload>", "base64"]
+    assert_eq!(account["data"][1], "base64", "Fixture data must be base64");
+    let data = BASE64
+        .decode(account["data"][0].as_str().unwrap())
+        .expect("Fixture data is not valid base64");
+
+    (
+        pubkey,
+        Account {
+            lamports: account["lamports"].as_u64().unwrap(),
+            data,
+            owner: Pubkey::from_str(account["owner"].as_str().unwrap()).unwrap(),
+            executable: account["executable"].as_bool().unwrap(),
+            rent_epoch: account["rentEpoch"].as_u64().unwrap(),
+        },
+    )
+}
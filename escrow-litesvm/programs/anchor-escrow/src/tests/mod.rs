@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod fixture;
+
 #[cfg(test)]
 mod tests {
 
@@ -11,28 +14,47 @@ mod tests {
             associated_token::{self, spl_associated_token_account},
             token::spl_token,
         },
-        litesvm::{types::TransactionMetadata, LiteSVM},
+        litesvm::{
+            types::{FailedTransactionMetadata, TransactionMetadata},
+            LiteSVM,
+        },
         litesvm_token::{
             spl_token::ID as TOKEN_PROGRAM_ID, CreateAssociatedTokenAccount, CreateMint, MintTo,
         },
-        solana_account::Account,
-        solana_address::Address,
-        solana_instruction::Instruction,
+        solana_instruction::{error::InstructionError, AccountMeta, Instruction},
         solana_keypair::Keypair,
         solana_message::Message,
         solana_native_token::LAMPORTS_PER_SOL,
         solana_pubkey::Pubkey,
-        solana_rpc_client::rpc_client::RpcClient,
         solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
         solana_signer::Signer,
         solana_transaction::Transaction,
-        std::{path::PathBuf, str::FromStr},
+        solana_transaction_error::TransactionError,
+        std::path::PathBuf,
     };
 
+    use super::fixture::{load_fixture, SYNTHETIC_PAYER_FIXTURE};
+    use crate::error::EscrowError;
+
     static PROGRAM_ID: Pubkey = crate::ID;
 
+    // Asserts the transaction failed with the given `EscrowError`
+    fn assert_escrow_error<T: std::fmt::Debug>(
+        result: Result<T, FailedTransactionMetadata>,
+        error: EscrowError,
+    ) {
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+            )
+        );
+    }
+
     // Setup function to initialize LiteSVM and create a payer keypair
-    // Also loads an account from devnet into the LiteSVM environment (for testing purposes)
+    // Also loads a synthetic account fixture into the LiteSVM environment (for testing purposes)
     fn setup() -> (LiteSVM, Keypair) {
         // Initialize LiteSVM and payer
         let mut program = LiteSVM::new();
@@ -51,41 +73,29 @@ mod tests {
 
         program.add_program(PROGRAM_ID, &program_data);
 
-        // Example on how to Load an account from devnet
-        // LiteSVM does not have access to real Solana network data since it does not have network access,
-        // so we use an RPC client to fetch account data from devnet
-        let rpc_client = RpcClient::new("https://api.devnet.solana.com");
-        let account_address =
-            Address::from_str("DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2").unwrap();
-        let fetched_account = rpc_client
-            .get_account(&account_address)
-            .expect("Failed to fetch account from devnet");
+        // Example on how to Load an account from a fixture
+        // LiteSVM does not have access to real Solana network data, so the account is
+        // read from a file checked into the crate instead of fetched over RPC.
+        // This keeps the suite hermetic (CI, air-gapped machines)
+        let (account_address, fetched_account) = load_fixture(SYNTHETIC_PAYER_FIXTURE);
+        msg!("Loaded fixture for {}", account_address);
+
+        let lamports = fetched_account.lamports;
 
         // Set the fetched account in the LiteSVM environment
         // This allows us to simulate interactions with this account during testing
         program
-            .set_account(
-                payer.pubkey(),
-                Account {
-                    lamports: fetched_account.lamports,
-                    data: fetched_account.data,
-                    owner: Pubkey::from(fetched_account.owner.to_bytes()),
-                    executable: fetched_account.executable,
-                    rent_epoch: fetched_account.rent_epoch,
-                },
-            )
+            .set_account(payer.pubkey(), fetched_account)
             .unwrap();
 
-        msg!("Lamports of fetched account: {}", fetched_account.lamports);
+        msg!("Lamports of fetched account: {}", lamports);
 
         // Return the LiteSVM instance and payer keypair
         (program, payer)
     }
 
-    fn setup_escrow_make(
-        program: &mut LiteSVM,
-        payer: &Keypair,
-    ) -> (
+    // (maker, mint_a, mint_b, maker_ata_a, escrow, vault, make transaction)
+    type EscrowSetup = (
         Pubkey,
         Pubkey,
         Pubkey,
@@ -93,7 +103,40 @@ mod tests {
         Pubkey,
         Pubkey,
         TransactionMetadata,
-    ) {
+    );
+
+    // Current on-chain time, to build lock/expiry timestamps relative to it
+    fn now(program: &LiteSVM) -> i64 {
+        program.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    fn warp_to(program: &mut LiteSVM, timestamp: i64) {
+        let mut clock: Clock = program.get_sysvar();
+        msg!(
+            "Travelled into future: {} -> {}",
+            clock.unix_timestamp,
+            timestamp
+        );
+        clock.unix_timestamp = timestamp;
+        program.set_sysvar(&clock);
+    }
+
+    fn setup_escrow_make(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+    ) -> EscrowSetup {
+        try_setup_escrow_make(program, payer, lock_until, expiry, None).unwrap()
+    }
+
+    fn try_setup_escrow_make(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+        operator: Option<Pubkey>,
+    ) -> Result<EscrowSetup, FailedTransactionMetadata> {
         // Get the maker's public key from the payer keypair
         let maker = payer.pubkey();
 
@@ -104,14 +147,22 @@ mod tests {
             .authority(&maker)
             .send()
             .unwrap();
-        msg!("Mint A: {}\n", mint_a);
+        msg!(
+            "Mint A: {}
+",
+            mint_a
+        );
 
         let mint_b = CreateMint::new(program, &payer)
             .decimals(6)
             .authority(&maker)
             .send()
             .unwrap();
-        msg!("Mint B: {}\n", mint_b);
+        msg!(
+            "Mint B: {}
+",
+            mint_b
+        );
 
         // Create the maker's associated token account for Mint A
         // This is done using litesvm-token's CreateAssociatedTokenAccount utility
@@ -119,7 +170,11 @@ mod tests {
             .owner(&maker)
             .send()
             .unwrap();
-        msg!("Maker ATA A: {}\n", maker_ata_a);
+        msg!(
+            "Maker ATA A: {}
+",
+            maker_ata_a
+        );
 
         // Derive the PDA for the escrow account using the maker's public key and a seed value
         let escrow = Pubkey::find_program_address(
@@ -127,11 +182,19 @@ mod tests {
             &PROGRAM_ID,
         )
         .0;
-        msg!("Escrow PDA: {}\n", escrow);
+        msg!(
+            "Escrow PDA: {}
+",
+            escrow
+        );
 
         // Derive the PDA for the vault associated token account using the escrow PDA and Mint A
         let vault = associated_token::get_associated_token_address(&escrow, &mint_a);
-        msg!("Vault PDA: {}\n", vault);
+        msg!(
+            "Vault PDA: {}
+",
+            vault
+        );
 
         // Define program IDs for associated token program, token program, and system program
         let asspciated_token_program = spl_associated_token_account::ID;
@@ -162,6 +225,9 @@ mod tests {
                 deposit: 10,
                 seed: 123u64,
                 receive: 10,
+                lock_until,
+                expiry,
+                operator,
             }
             .data(),
         };
@@ -173,17 +239,481 @@ mod tests {
         let transaction = Transaction::new(&[&payer], message, recent_blockhash);
 
         // Send the transaction and capture the result
-        let tx = program.send_transaction(transaction).unwrap();
+        program
+            .send_transaction(transaction)
+            .map(|tx| (maker, mint_a, mint_b, maker_ata_a, escrow, vault, tx))
+    }
+    // Funded taker holding 10 of Mint B, plus the maker's Mint B ATA
+    fn setup_taker(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+    ) -> Keypair {
+        let taker = Keypair::new();
+
+        program
+            .airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        CreateAssociatedTokenAccount::new(program, &taker, &mint_a)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
+
+        let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &mint_b)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
 
-        (maker, mint_a, mint_b, maker_ata_a, escrow, vault, tx)
+        CreateAssociatedTokenAccount::new(program, payer, &mint_b)
+            .owner(&maker)
+            .send()
+            .unwrap();
+
+        MintTo::new(program, payer, &mint_b, &taker_ata_b, 10)
+            .send()
+            .unwrap();
+
+        taker
     }
+
+    // The escrow's current price, as a client would quote it before taking
+    fn quoted_receive(program: &LiteSVM, escrow: &Pubkey) -> u64 {
+        program
+            .get_account(escrow)
+            .and_then(|account| {
+                crate::state::Escrow::try_deserialize(&mut account.data.as_ref()).ok()
+            })
+            .map_or(0, |escrow| escrow.receive)
+    }
+
+    // Takes at the currently quoted price
+    fn try_take(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        taker: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let expected_receive = quoted_receive(program, &escrow);
+        try_take_at(
+            program,
+            payer,
+            taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            expected_receive,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn try_take_at(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        taker: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+        expected_receive: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let take_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Take {
+                taker: taker.pubkey(),
+                maker,
+                mint_a,
+                mint_b,
+                taker_ata_a: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_a,
+                ),
+                taker_ata_b: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_b,
+                ),
+                maker_ata_b: associated_token::get_associated_token_address(&maker, &mint_b),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Take { expected_receive }.data(),
+        };
+
+        // Fresh blockhash so a retried take isn't rejected as a duplicate
+        program.expire_blockhash();
+        let msg = Message::new(&[take_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[payer, taker], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn find_counter_offer(escrow: &Pubkey, taker: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"counter", escrow.as_ref(), taker.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn token_amount(program: &LiteSVM, ata: &Pubkey) -> u64 {
+        let account = program.get_account(ata).unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    fn assert_closed(program: &LiteSVM, address: &Pubkey) {
+        let account = program.get_account(address).unwrap();
+        assert_eq!(account.data.len(), 0);
+        assert_eq!(account.lamports, 0);
+        assert_eq!(account.owner, SYSTEM_PROGRAM_ID);
+    }
+
+    fn try_counter(
+        program: &mut LiteSVM,
+        taker: &Keypair,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+        receive: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let counter_offer = find_counter_offer(&escrow, &taker.pubkey());
+
+        let counter_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Counter {
+                taker: taker.pubkey(),
+                mint_b,
+                taker_ata_b: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_b,
+                ),
+                escrow,
+                counter_offer,
+                counter_vault: associated_token::get_associated_token_address(
+                    &counter_offer,
+                    &mint_b,
+                ),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Counter { receive }.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[counter_ix], Some(&taker.pubkey()));
+        let transaction = Transaction::new(&[taker], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_accept_counter(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        taker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let maker = payer.pubkey();
+        let counter_offer = find_counter_offer(&escrow, &taker);
+
+        let accept_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AcceptCounter {
+                maker,
+                taker,
+                mint_a,
+                mint_b,
+                taker_ata_a: associated_token::get_associated_token_address(&taker, &mint_a),
+                maker_ata_b: associated_token::get_associated_token_address(&maker, &mint_b),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                counter_offer,
+                counter_vault: associated_token::get_associated_token_address(
+                    &counter_offer,
+                    &mint_b,
+                ),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::AcceptCounter {}.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[accept_ix], Some(&maker));
+        let transaction = Transaction::new(&[payer], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_withdraw_counter(
+        program: &mut LiteSVM,
+        taker: &Keypair,
+        mint_b: Pubkey,
+        escrow: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let counter_offer = find_counter_offer(&escrow, &taker.pubkey());
+
+        let withdraw_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::WithdrawCounter {
+                taker: taker.pubkey(),
+                mint_b,
+                taker_ata_b: associated_token::get_associated_token_address(
+                    &taker.pubkey(),
+                    &mint_b,
+                ),
+                counter_offer,
+                counter_vault: associated_token::get_associated_token_address(
+                    &counter_offer,
+                    &mint_b,
+                ),
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::WithdrawCounter {}.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[withdraw_ix], Some(&taker.pubkey()));
+        let transaction = Transaction::new(&[taker], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn escrow_state(program: &LiteSVM, escrow: &Pubkey) -> crate::state::Escrow {
+        let escrow_account = program.get_account(escrow).unwrap();
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap()
+    }
+
+    // Refund signed by `authority`; the payer only covers the fee
+    fn try_refund(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        authority: &Keypair,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        escrow: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                authority: authority.pubkey(),
+                maker,
+                mint_a,
+                maker_ata_a: associated_token::get_associated_token_address(&maker, &mint_a),
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[refund_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[payer, authority], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_set_operator(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        escrow: Pubkey,
+        operator: Option<Pubkey>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let set_operator_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetOperator {
+                maker: payer.pubkey(),
+                escrow,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetOperator { operator }.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[set_operator_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[payer], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn try_amend(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        authority: &Keypair,
+        escrow: Pubkey,
+        receive: u64,
+        lock_until: Option<i64>,
+        expiry: Option<i64>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let amend_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Amend {
+                authority: authority.pubkey(),
+                escrow,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Amend {
+                receive,
+                lock_until,
+                expiry,
+            }
+            .data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[amend_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[payer, authority], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Second maker offering `deposit` of an existing Mint A for `receive` of Mint B.
+    // `payer` is the mint authority for both mints.
+    fn setup_second_maker(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        deposit: u64,
+        receive: u64,
+        lock_until: Option<i64>,
+    ) -> (Keypair, Pubkey) {
+        let maker = Keypair::new();
+        program
+            .airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let maker_ata_a = CreateAssociatedTokenAccount::new(program, &maker, &mint_a)
+            .owner(&maker.pubkey())
+            .send()
+            .unwrap();
+        CreateAssociatedTokenAccount::new(program, &maker, &mint_b)
+            .owner(&maker.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(program, payer, &mint_a, &maker_ata_a, deposit)
+            .send()
+            .unwrap();
+
+        let seed = 7u64;
+        let escrow = Pubkey::find_program_address(
+            &[b"escrow", maker.pubkey().as_ref(), &seed.to_le_bytes()],
+            &PROGRAM_ID,
+        )
+        .0;
+
+        let make_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Make {
+                maker: maker.pubkey(),
+                mint_a,
+                mint_b,
+                maker_ata_a,
+                escrow,
+                vault: associated_token::get_associated_token_address(&escrow, &mint_a),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Make {
+                seed,
+                deposit,
+                receive,
+                lock_until,
+                expiry: None,
+                operator: None,
+            }
+            .data(),
+        };
+
+        let msg = Message::new(&[make_ix], Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[&maker], msg, program.latest_blockhash());
+        program.send_transaction(transaction).unwrap();
+
+        (maker, escrow)
+    }
+
+    // `escrows` are (escrow, maker) pairs, expanded into the
+    // (escrow, maker, vault, maker_ata_b) groups `take_many` expects.
+    // Each escrow is taken at its currently quoted price.
+    fn try_take_many(
+        program: &mut LiteSVM,
+        taker: &Keypair,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrows: &[(Pubkey, Pubkey)],
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let expected_receive = escrows
+            .iter()
+            .map(|(escrow, _)| quoted_receive(program, escrow))
+            .collect();
+        try_take_many_at(program, taker, mint_a, mint_b, escrows, expected_receive)
+    }
+
+    fn try_take_many_at(
+        program: &mut LiteSVM,
+        taker: &Keypair,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        escrows: &[(Pubkey, Pubkey)],
+        expected_receive: Vec<u64>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let mut accounts = crate::accounts::TakeMany {
+            taker: taker.pubkey(),
+            mint_a,
+            mint_b,
+            taker_ata_a: associated_token::get_associated_token_address(&taker.pubkey(), &mint_a),
+            taker_ata_b: associated_token::get_associated_token_address(&taker.pubkey(), &mint_b),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+
+        for (escrow, maker) in escrows {
+            accounts.extend([
+                AccountMeta::new(*escrow, false),
+                AccountMeta::new(*maker, false),
+                AccountMeta::new(
+                    associated_token::get_associated_token_address(escrow, &mint_a),
+                    false,
+                ),
+                AccountMeta::new(
+                    associated_token::get_associated_token_address(maker, &mint_b),
+                    false,
+                ),
+            ]);
+        }
+
+        let take_many_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::TakeMany { expected_receive }.data(),
+        };
+
+        program.expire_blockhash();
+        let msg = Message::new(&[take_many_ix], Some(&taker.pubkey()));
+        let transaction = Transaction::new(&[taker], msg, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
     #[test]
     fn test_make() {
         // Setup the test environment by initializing LiteSVM and creating a payer keypair
         let (mut program, payer) = setup();
 
         let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, tx) =
-            setup_escrow_make(&mut program, &payer);
+            setup_escrow_make(&mut program, &payer, None, None);
         // Log transaction details
         msg!("\n\nMake transaction sucessfull");
         msg!("CUs Consumed: {}", tx.compute_units_consumed);
@@ -204,6 +734,9 @@ mod tests {
         assert_eq!(escrow_data.mint_a, mint_a);
         assert_eq!(escrow_data.mint_b, mint_b);
         assert_eq!(escrow_data.receive, 10);
+        assert_eq!(escrow_data.lock_until, None);
+        assert_eq!(escrow_data.expiry, None);
+        assert_eq!(escrow_data.operator, None);
     }
 
     #[test]
@@ -212,13 +745,14 @@ mod tests {
         let (mut program, payer) = setup();
 
         let (maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
-            setup_escrow_make(&mut program, &payer);
+            setup_escrow_make(&mut program, &payer, None, None);
 
         let token_program = TOKEN_PROGRAM_ID;
         let system_program = SYSTEM_PROGRAM_ID;
         let refund_ix = Instruction {
             program_id: PROGRAM_ID,
             accounts: crate::accounts::Refund {
+                authority: maker,
                 maker,
                 mint_a,
                 maker_ata_a,
@@ -264,8 +798,9 @@ mod tests {
     fn test_take_before_deadline_fails() {
         let (mut program, payer) = setup();
 
+        let lock_until = now(&program) + 5 * 24 * 60 * 60; // 5 days
         let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
-            setup_escrow_make(&mut program, &payer);
+            setup_escrow_make(&mut program, &payer, Some(lock_until), None);
 
         let taker = Keypair::new();
 
@@ -317,7 +852,10 @@ mod tests {
                 system_program,
             }
             .to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
+            data: crate::instruction::Take {
+                expected_receive: 10,
+            }
+            .data(),
         };
 
         let msg = Message::new(&[take_ix], Some(&payer.pubkey()));
@@ -326,18 +864,16 @@ mod tests {
 
         // This test is expected to fail because of the time lock
         let tx_result = program.send_transaction(transaction);
-        assert!(
-            tx_result.is_err(),
-            "Take transaction should fail before deadline"
-        );
+        assert_escrow_error(tx_result, EscrowError::EscrowLocked);
     }
 
     #[test]
     fn test_take_after_deadline_succeeds() {
         let (mut program, payer) = setup();
 
+        let lock_until = now(&program) + 5 * 24 * 60 * 60; // 5 days
         let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
-            setup_escrow_make(&mut program, &payer);
+            setup_escrow_make(&mut program, &payer, Some(lock_until), None);
 
         let taker = Keypair::new();
 
@@ -368,15 +904,7 @@ mod tests {
             .unwrap();
 
         // Time Travel
-        let mut clock: Clock = program.get_sysvar();
-        let current_time = clock.unix_timestamp;
-        let five_days = 5 * 24 * 60 * 60 + 1; // 5 days in seconds
-        let future_time = current_time + five_days;
-
-        clock.unix_timestamp = future_time;
-        program.set_sysvar(&clock);
-
-        msg!("Travelled into future: {} -> {}", current_time, future_time);
+        warp_to(&mut program, lock_until);
 
         // Define program IDs for associated token program, token program, and system program
         let associated_token_program = spl_associated_token_account::ID;
@@ -400,7 +928,10 @@ mod tests {
                 system_program,
             }
             .to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
+            data: crate::instruction::Take {
+                expected_receive: 10,
+            }
+            .data(),
         };
 
         let msg = Message::new(&[take_ix], Some(&payer.pubkey()));
@@ -431,4 +962,606 @@ mod tests {
         assert_eq!(escrow_acc.lamports, 0);
         assert_eq!(escrow_acc.owner, system_program);
     }
+
+    #[test]
+    fn test_take_at_lock_boundary() {
+        let (mut program, payer) = setup();
+
+        let lock_until = now(&program) + 3600;
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, Some(lock_until), None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        // One second before the lock ends
+        warp_to(&mut program, lock_until - 1);
+        let result = try_take(&mut program, &payer, &taker, maker, mint_a, mint_b, escrow);
+        assert_escrow_error(result, EscrowError::EscrowLocked);
+
+        // The lock ends at `lock_until` itself
+        warp_to(&mut program, lock_until);
+        try_take(&mut program, &payer, &taker, maker, mint_a, mint_b, escrow).unwrap();
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        let taker_a = program.get_account(&taker_ata_a).unwrap();
+        let taker_a_state = spl_token::state::Account::unpack(&taker_a.data).unwrap();
+        assert_eq!(taker_a_state.amount, 10);
+    }
+
+    #[test]
+    fn test_take_before_expiry_succeeds() {
+        let (mut program, payer) = setup();
+
+        let expiry = now(&program) + 2 * 24 * 60 * 60; // 2 days
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, None, Some(expiry));
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        let escrow_account = program.get_account(&escrow).unwrap();
+        let escrow_data =
+            crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+        assert_eq!(escrow_data.lock_until, None);
+        assert_eq!(escrow_data.expiry, Some(expiry));
+
+        // Last second of the offer
+        warp_to(&mut program, expiry - 1);
+        try_take(&mut program, &payer, &taker, maker, mint_a, mint_b, escrow).unwrap();
+
+        let maker_ata_b = associated_token::get_associated_token_address(&maker, &mint_b);
+        let maker_b = program.get_account(&maker_ata_b).unwrap();
+        let maker_b_state = spl_token::state::Account::unpack(&maker_b.data).unwrap();
+        assert_eq!(maker_b_state.amount, 10);
+    }
+
+    #[test]
+    fn test_take_after_expiry_fails() {
+        let (mut program, payer) = setup();
+
+        let lock_until = now(&program) + 60;
+        let expiry = lock_until + 2 * 24 * 60 * 60;
+        let (maker, mint_a, mint_b, maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, Some(lock_until), Some(expiry));
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        warp_to(&mut program, expiry);
+        let result = try_take(&mut program, &payer, &taker, maker, mint_a, mint_b, escrow);
+        assert_escrow_error(result, EscrowError::EscrowExpired);
+
+        // The maker can still reclaim the deposit once the offer expires
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                authority: maker,
+                maker,
+                mint_a,
+                maker_ata_a,
+                escrow,
+                vault,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        let message = Message::new(&[refund_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[&payer], message, program.latest_blockhash());
+        program.send_transaction(transaction).unwrap();
+
+        let maker_acc = program.get_account(&maker_ata_a).unwrap();
+        let maker_state = spl_token::state::Account::unpack(&maker_acc.data).unwrap();
+        assert_eq!(maker_state.amount, 1_000_000_000);
+    }
+
+    #[test]
+    fn test_make_rejects_invalid_time_window() {
+        let (mut program, payer) = setup();
+        let current_time = now(&program);
+
+        // Lock ends after the offer expires
+        let result = try_setup_escrow_make(
+            &mut program,
+            &payer,
+            Some(current_time + 200),
+            Some(current_time + 100),
+            None,
+        );
+        assert_escrow_error(result, EscrowError::InvalidTimeWindow);
+
+        // Expiry already in the past
+        let result = try_setup_escrow_make(&mut program, &payer, None, Some(current_time), None);
+        assert_escrow_error(result, EscrowError::InvalidTimeWindow);
+    }
+
+    #[test]
+    fn test_accept_counter() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        // Maker asked for 10 of Mint B, taker offers 7
+        try_counter(&mut program, &taker, mint_b, escrow, 7).unwrap();
+
+        let counter_offer = find_counter_offer(&escrow, &taker.pubkey());
+        let counter_vault = associated_token::get_associated_token_address(&counter_offer, &mint_b);
+        let counter_account = program.get_account(&counter_offer).unwrap();
+        let counter_data =
+            crate::state::CounterOffer::try_deserialize(&mut counter_account.data.as_ref())
+                .unwrap();
+        assert_eq!(counter_data.escrow, escrow);
+        assert_eq!(counter_data.taker, taker.pubkey());
+        assert_eq!(counter_data.mint_b, mint_b);
+        assert_eq!(counter_data.receive, 7);
+        assert_eq!(token_amount(&program, &counter_vault), 7);
+
+        let tx = try_accept_counter(&mut program, &payer, taker.pubkey(), mint_a, mint_b, escrow)
+            .unwrap();
+        msg!("\n\nAccept counter transaction sucessfull");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        // Both legs settled in the same transaction
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        let maker_ata_b = associated_token::get_associated_token_address(&maker, &mint_b);
+        assert_eq!(token_amount(&program, &taker_ata_a), 10);
+        assert_eq!(token_amount(&program, &taker_ata_b), 3);
+        assert_eq!(token_amount(&program, &maker_ata_b), 7);
+
+        assert_closed(&program, &vault);
+        assert_closed(&program, &escrow);
+        assert_closed(&program, &counter_vault);
+        assert_closed(&program, &counter_offer);
+    }
+
+    #[test]
+    fn test_withdraw_counter() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        try_counter(&mut program, &taker, mint_b, escrow, 7).unwrap();
+        try_withdraw_counter(&mut program, &taker, mint_b, escrow).unwrap();
+
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        assert_eq!(token_amount(&program, &taker_ata_b), 10);
+
+        let counter_offer = find_counter_offer(&escrow, &taker.pubkey());
+        assert_closed(&program, &counter_offer);
+        assert_closed(
+            &program,
+            &associated_token::get_associated_token_address(&counter_offer, &mint_b),
+        );
+
+        // The escrow itself is untouched and can no longer be settled against the counter
+        assert_eq!(token_amount(&program, &vault), 10);
+        let result =
+            try_accept_counter(&mut program, &payer, taker.pubkey(), mint_a, mint_b, escrow);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_withdraw_counter_after_escrow_taken() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let counter_taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        try_counter(&mut program, &counter_taker, mint_b, escrow, 5).unwrap();
+
+        // Someone else fills the escrow at the maker's price
+        let taker = Keypair::new();
+        program
+            .airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+        CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_a)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
+        let taker_ata_b = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_b)
+            .owner(&taker.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 10)
+            .send()
+            .unwrap();
+        try_take(&mut program, &payer, &taker, maker, mint_a, mint_b, escrow).unwrap();
+        assert_closed(&program, &escrow);
+
+        // The stale counter is still withdrawable
+        try_withdraw_counter(&mut program, &counter_taker, mint_b, escrow).unwrap();
+
+        let counter_ata_b =
+            associated_token::get_associated_token_address(&counter_taker.pubkey(), &mint_b);
+        assert_eq!(token_amount(&program, &counter_ata_b), 10);
+        assert_closed(
+            &program,
+            &find_counter_offer(&escrow, &counter_taker.pubkey()),
+        );
+    }
+
+    #[test]
+    fn test_accept_counter_after_expiry_fails() {
+        let (mut program, payer) = setup();
+
+        let expiry = now(&program) + 24 * 60 * 60;
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, None, Some(expiry));
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        try_counter(&mut program, &taker, mint_b, escrow, 7).unwrap();
+
+        warp_to(&mut program, expiry);
+        let result =
+            try_accept_counter(&mut program, &payer, taker.pubkey(), mint_a, mint_b, escrow);
+        assert_escrow_error(result, EscrowError::EscrowExpired);
+
+        // The taker gets their funds back
+        try_withdraw_counter(&mut program, &taker, mint_b, escrow).unwrap();
+    }
+
+    #[test]
+    fn test_accept_counter_while_locked_fails() {
+        let (mut program, payer) = setup();
+
+        let lock_until = now(&program) + 3600;
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, Some(lock_until), None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        // Countering is allowed during the lock, settling is not
+        try_counter(&mut program, &taker, mint_b, escrow, 7).unwrap();
+
+        warp_to(&mut program, lock_until - 1);
+        let result =
+            try_accept_counter(&mut program, &payer, taker.pubkey(), mint_a, mint_b, escrow);
+        assert_escrow_error(result, EscrowError::EscrowLocked);
+
+        warp_to(&mut program, lock_until);
+        try_accept_counter(&mut program, &payer, taker.pubkey(), mint_a, mint_b, escrow).unwrap();
+        assert_closed(&program, &escrow);
+    }
+
+    #[test]
+    fn test_operator_refund() {
+        let (mut program, payer) = setup();
+        let operator = Keypair::new();
+
+        let (maker, mint_a, _mint_b, maker_ata_a, escrow, vault, _) =
+            try_setup_escrow_make(&mut program, &payer, None, None, Some(operator.pubkey()))
+                .unwrap();
+        assert_eq!(
+            escrow_state(&program, &escrow).operator,
+            Some(operator.pubkey())
+        );
+
+        // Any other key is rejected
+        let stranger = Keypair::new();
+        let result = try_refund(&mut program, &payer, &stranger, maker, mint_a, escrow);
+        assert_escrow_error(result, EscrowError::Unauthorized);
+
+        let maker_lamports = program.get_account(&maker).unwrap().lamports;
+        try_refund(&mut program, &payer, &operator, maker, mint_a, escrow).unwrap();
+
+        // Tokens and rent went back to the maker, not the operator
+        assert_eq!(token_amount(&program, &maker_ata_a), 1_000_000_000);
+        assert!(program.get_account(&maker).unwrap().lamports > maker_lamports);
+        assert_closed(&program, &vault);
+        assert_closed(&program, &escrow);
+    }
+
+    #[test]
+    fn test_set_operator() {
+        let (mut program, payer) = setup();
+        let operator = Keypair::new();
+
+        let (maker, mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+
+        // No operator registered yet
+        let result = try_amend(&mut program, &payer, &operator, escrow, 20, None, None);
+        assert_escrow_error(result, EscrowError::Unauthorized);
+
+        try_set_operator(&mut program, &payer, escrow, Some(operator.pubkey())).unwrap();
+
+        let expiry = now(&program) + 24 * 60 * 60;
+        try_amend(
+            &mut program,
+            &payer,
+            &operator,
+            escrow,
+            20,
+            None,
+            Some(expiry),
+        )
+        .unwrap();
+
+        let escrow_data = escrow_state(&program, &escrow);
+        assert_eq!(escrow_data.receive, 20);
+        assert_eq!(escrow_data.expiry, Some(expiry));
+        assert_eq!(escrow_data.operator, Some(operator.pubkey()));
+
+        // Amend goes through the same time window checks as make
+        let result = try_amend(
+            &mut program,
+            &payer,
+            &operator,
+            escrow,
+            20,
+            Some(expiry),
+            Some(expiry),
+        );
+        assert_escrow_error(result, EscrowError::InvalidTimeWindow);
+
+        // Once cleared, the old operator can no longer act
+        try_set_operator(&mut program, &payer, escrow, None).unwrap();
+        let result = try_refund(&mut program, &payer, &operator, maker, mint_a, escrow);
+        assert_escrow_error(result, EscrowError::Unauthorized);
+
+        // The maker still can
+        try_refund(&mut program, &payer, &payer, maker, mint_a, escrow).unwrap();
+        assert_closed(&program, &escrow);
+    }
+
+    #[test]
+    fn test_operator_cannot_set_operator() {
+        let (mut program, payer) = setup();
+        let operator = Keypair::new();
+
+        let (_maker, _mint_a, _mint_b, _maker_ata_a, escrow, _vault, _) =
+            try_setup_escrow_make(&mut program, &payer, None, None, Some(operator.pubkey()))
+                .unwrap();
+
+        // Signed by the operator in the maker slot: the escrow PDA does not match
+        let set_operator_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetOperator {
+                maker: operator.pubkey(),
+                escrow,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetOperator {
+                operator: Some(operator.pubkey()),
+            }
+            .data(),
+        };
+
+        let msg = Message::new(&[set_operator_ix], Some(&payer.pubkey()));
+        let transaction = Transaction::new(&[&payer, &operator], msg, program.latest_blockhash());
+        assert!(program.send_transaction(transaction).is_err());
+    }
+
+    #[test]
+    fn test_take_many() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let (second_maker, second_escrow) =
+            setup_second_maker(&mut program, &payer, mint_a, mint_b, 20, 5, None);
+
+        // 10 + 5 of Mint B for both offers
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 5)
+            .send()
+            .unwrap();
+
+        let tx = try_take_many(
+            &mut program,
+            &taker,
+            mint_a,
+            mint_b,
+            &[(escrow, maker), (second_escrow, second_maker.pubkey())],
+        )
+        .unwrap();
+        msg!("\n\nTake many transaction sucessfull");
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        assert_eq!(token_amount(&program, &taker_ata_a), 30);
+        assert_eq!(token_amount(&program, &taker_ata_b), 0);
+        assert_eq!(
+            token_amount(
+                &program,
+                &associated_token::get_associated_token_address(&maker, &mint_b)
+            ),
+            10
+        );
+        assert_eq!(
+            token_amount(
+                &program,
+                &associated_token::get_associated_token_address(&second_maker.pubkey(), &mint_b)
+            ),
+            5
+        );
+
+        assert_closed(&program, &vault);
+        assert_closed(&program, &escrow);
+        assert_closed(
+            &program,
+            &associated_token::get_associated_token_address(&second_escrow, &mint_a),
+        );
+        assert_closed(&program, &second_escrow);
+    }
+
+    #[test]
+    fn test_take_many_rejects_mismatched_accounts() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let (second_maker, second_escrow) =
+            setup_second_maker(&mut program, &payer, mint_a, mint_b, 20, 5, None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+
+        // First escrow paired with the wrong maker
+        let result = try_take_many(
+            &mut program,
+            &taker,
+            mint_a,
+            mint_b,
+            &[(escrow, second_maker.pubkey())],
+        );
+        assert_escrow_error(result, EscrowError::InvalidEscrowAccounts);
+
+        // Same escrow twice: the second group finds it already closed
+        let result = try_take_many(
+            &mut program,
+            &taker,
+            mint_a,
+            mint_b,
+            &[
+                (second_escrow, second_maker.pubkey()),
+                (second_escrow, second_maker.pubkey()),
+            ],
+        );
+        assert!(result.is_err());
+
+        // No escrows at all
+        let result = try_take_many(&mut program, &taker, mint_a, mint_b, &[]);
+        assert_escrow_error(result, EscrowError::InvalidEscrowAccounts);
+
+        assert_eq!(token_amount(&program, &vault), 10);
+        assert_eq!(escrow_state(&program, &second_escrow).receive, 5);
+    }
+
+    #[test]
+    fn test_take_many_is_atomic() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let lock_until = now(&program) + 3600;
+        let (second_maker, second_escrow) = setup_second_maker(
+            &mut program,
+            &payer,
+            mint_a,
+            mint_b,
+            20,
+            5,
+            Some(lock_until),
+        );
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 5)
+            .send()
+            .unwrap();
+
+        // The second escrow is still locked, so the first one is not settled either
+        let result = try_take_many(
+            &mut program,
+            &taker,
+            mint_a,
+            mint_b,
+            &[(escrow, maker), (second_escrow, second_maker.pubkey())],
+        );
+        assert_escrow_error(result, EscrowError::EscrowLocked);
+
+        assert_eq!(token_amount(&program, &vault), 10);
+        assert_eq!(token_amount(&program, &taker_ata_b), 15);
+        assert_eq!(escrow_state(&program, &escrow).maker, maker);
+    }
+
+    #[test]
+    fn test_take_rejects_repriced_escrow() {
+        let (mut program, payer) = setup();
+        let operator = Keypair::new();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            try_setup_escrow_make(&mut program, &payer, None, None, Some(operator.pubkey()))
+                .unwrap();
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+
+        // The taker quotes 10, then the operator lowers the price to 0 ahead of the take
+        try_amend(&mut program, &payer, &operator, escrow, 0, None, None).unwrap();
+        let result = try_take_at(
+            &mut program,
+            &payer,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            10,
+        );
+        assert_escrow_error(result, EscrowError::PriceChanged);
+
+        // Raising it ahead of the take is rejected the same way
+        try_amend(&mut program, &payer, &operator, escrow, 15, None, None).unwrap();
+        let result = try_take_at(
+            &mut program,
+            &payer,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            10,
+        );
+        assert_escrow_error(result, EscrowError::PriceChanged);
+        assert_eq!(token_amount(&program, &vault), 10);
+        assert_eq!(token_amount(&program, &taker_ata_b), 10);
+
+        // Back at the quoted price, the take goes through
+        try_amend(&mut program, &payer, &operator, escrow, 10, None, None).unwrap();
+        try_take_at(
+            &mut program,
+            &payer,
+            &taker,
+            maker,
+            mint_a,
+            mint_b,
+            escrow,
+            10,
+        )
+        .unwrap();
+        assert_eq!(token_amount(&program, &taker_ata_b), 0);
+        assert_closed(&program, &escrow);
+    }
+
+    #[test]
+    fn test_take_many_rejects_repriced_escrow() {
+        let (mut program, payer) = setup();
+
+        let (maker, mint_a, mint_b, _maker_ata_a, escrow, vault, _) =
+            setup_escrow_make(&mut program, &payer, None, None);
+        let (second_maker, second_escrow) =
+            setup_second_maker(&mut program, &payer, mint_a, mint_b, 20, 5, None);
+        let taker = setup_taker(&mut program, &payer, maker, mint_a, mint_b);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 5)
+            .send()
+            .unwrap();
+        let escrows = [(escrow, maker), (second_escrow, second_maker.pubkey())];
+
+        // The second maker reprices after the taker quoted 10 and 5
+        try_amend(
+            &mut program,
+            &payer,
+            &second_maker,
+            second_escrow,
+            4,
+            None,
+            None,
+        )
+        .unwrap();
+        let result = try_take_many_at(&mut program, &taker, mint_a, mint_b, &escrows, vec![10, 5]);
+        assert_escrow_error(result, EscrowError::PriceChanged);
+        assert_eq!(token_amount(&program, &vault), 10);
+        assert_eq!(token_amount(&program, &taker_ata_b), 15);
+
+        // One expected price per escrow
+        let result = try_take_many_at(&mut program, &taker, mint_a, mint_b, &escrows, vec![10]);
+        assert_escrow_error(result, EscrowError::InvalidEscrowAccounts);
+
+        try_take_many_at(&mut program, &taker, mint_a, mint_b, &escrows, vec![10, 4]).unwrap();
+        assert_eq!(token_amount(&program, &taker_ata_b), 1);
+        assert_closed(&program, &escrow);
+        assert_closed(&program, &second_escrow);
+    }
 }
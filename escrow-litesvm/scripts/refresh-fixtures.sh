@@ -0,0 +1,41 @@
+#!/usr/bin/env bash
+# Re-snapshots every account fixture used by the LiteSVM tests.
+#
+# Each file in programs/anchor-escrow/src/tests/fixtures is named after the
+# account address and holds the output of `solana account --output json`.
+# To add a fixture, pass its address; with no arguments every existing
+# fixture is refreshed. Hand-written fixtures under fixtures/synthetic are
+# not captures and are never touched.
+#
+#   scripts/refresh-fixtures.sh                      # refresh all
+#   scripts/refresh-fixtures.sh <ADDRESS> [...]      # add / refresh specific accounts
+#
+# RPC_URL defaults to devnet.
+set -euo pipefail
+
+RPC_URL="${RPC_URL:-https://api.devnet.solana.com}"
+FIXTURES_DIR="$(cd "$(dirname "$0")/.." && pwd)/programs/anchor-escrow/src/tests/fixtures"
+
+mkdir -p "$FIXTURES_DIR"
+
+if [ "$#" -gt 0 ]; then
+  addresses=("$@")
+else
+  addresses=()
+  for file in "$FIXTURES_DIR"/*.json; do
+    [ -e "$file" ] || continue
+    addresses+=("$(basename "$file" .json)")
+  done
+fi
+
+if [ "${#addresses[@]}" -eq 0 ]; then
+  echo "No fixtures to refresh; pass one or more account addresses." >&2
+  exit 1
+fi
+
+for address in "${addresses[@]}"; do
+  echo "Fetching $address from $RPC_URL"
+  tmp="$(mktemp)"
+  solana account "$address" --url "$RPC_URL" --output json > "$tmp"
+  mv "$tmp" "$FIXTURES_DIR/$address.json"
+done
@@ -1,5 +1,8 @@
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
+// In base units, not whole tokens: at the minimum raise of 3 whole tokens the
+// 10% cap is 0.3 tokens, so a whole-token floor would make such campaigns unfundable
+pub const MIN_CONTRIBUTION: u64 = 1;
 pub const SECONDS_TO_DAYS: i64 = 86400;
 pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 10;
 pub const PERCENTAGE_SCALER: u64 = 100;
\ No newline at end of file
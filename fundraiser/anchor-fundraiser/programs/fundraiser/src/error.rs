@@ -16,6 +16,8 @@ pub enum FundraiserError {
     FundraiserNotEnded,
     #[msg("The fundraiser has ended")]
     FundraiserEnded,
-    #[msg("Invalid total amount. i should be bigger than 3")]
-    InvalidAmount
+    #[msg("Invalid total amount. It should be at least 3 whole tokens")]
+    InvalidAmount,
+    #[msg("There are no receipt tokens to redeem")]
+    NothingToRefund
 }
\ No newline at end of file
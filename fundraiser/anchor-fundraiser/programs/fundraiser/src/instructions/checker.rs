@@ -1,28 +1,30 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken, 
-    token::{
-        transfer, 
+    token_interface::{
+        transfer_checked, 
         Mint, 
-        Token, 
         TokenAccount, 
-        Transfer
+        TokenInterface, 
+        TransferChecked
     }
 };
 
 use crate::{
     state::Fundraiser, 
-    FundraiserError
+    FundraiserError, 
+    SECONDS_TO_DAYS
 };
 
 #[derive(Accounts)]
 pub struct CheckContributions<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
-    pub mint_to_raise: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_to_raise: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
-        seeds = [b"fundraiser".as_ref(), maker.key().as_ref()],
+        seeds = [b"fundraiser".as_ref(), maker.key().as_ref(), fundraiser.campaign_id.to_le_bytes().as_ref()],
         bump = fundraiser.bump,
         close = maker,
     )]
@@ -31,23 +33,32 @@ pub struct CheckContributions<'info> {
         mut,
         associated_token::mint = mint_to_raise,
         associated_token::authority = fundraiser,
+        associated_token::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed,
         payer = maker,
         associated_token::mint = mint_to_raise,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
-    pub maker_ata: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 impl<'info> CheckContributions<'info> {
     pub fn check_contributions(&self) -> Result<()> {
-        
+
+        // The maker can only claim once the fundraising duration has been reached
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            self.fundraiser.duration <= ((current_time - self.fundraiser.time_started) / SECONDS_TO_DAYS) as u8,
+            FundraiserError::FundraiserNotEnded
+        );
+
         // Check if the target amount has been met
         require!(
             self.vault.amount >= self.fundraiser.amount_to_raise,
@@ -59,8 +70,9 @@ impl<'info> CheckContributions<'info> {
         let cpi_program = self.token_program.to_account_info();
 
         // Transfer the funds from the vault to the maker
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.vault.to_account_info(),
+            mint: self.mint_to_raise.to_account_info(),
             to: self.maker_ata.to_account_info(),
             authority: self.fundraiser.to_account_info(),
         };
@@ -69,6 +81,7 @@ impl<'info> CheckContributions<'info> {
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"fundraiser".as_ref(),
             self.maker.to_account_info().key.as_ref(),
+            &self.fundraiser.campaign_id.to_le_bytes()[..],
             &[self.fundraiser.bump],
         ]];
 
@@ -76,7 +89,7 @@ impl<'info> CheckContributions<'info> {
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
 
         // Transfer the funds from the vault to the maker
-        transfer(cpi_ctx, self.vault.amount)?;
+        transfer_checked(cpi_ctx, self.vault.amount, self.mint_to_raise.decimals)?;
 
         Ok(())
     }
@@ -1,10 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    Mint, 
-    transfer, 
-    Token, 
-    TokenAccount, 
-    Transfer
+use anchor_spl::{
+    associated_token::AssociatedToken, 
+    token_interface::{
+        mint_to, 
+        transfer_checked, 
+        Mint, 
+        MintTo, 
+        TokenAccount, 
+        TokenInterface, 
+        TransferChecked
+    }
 };
 
 use crate::{
@@ -14,6 +19,7 @@ use crate::{
     }, FundraiserError, 
     ANCHOR_DISCRIMINATOR, 
     MAX_CONTRIBUTION_PERCENTAGE, 
+    MIN_CONTRIBUTION, 
     PERCENTAGE_SCALER, SECONDS_TO_DAYS
 };
 
@@ -21,11 +27,12 @@ use crate::{
 pub struct Contribute<'info> {
     #[account(mut)]
     pub contributor: Signer<'info>,
-    pub mint_to_raise: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_to_raise: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         has_one = mint_to_raise,
-        seeds = [b"fundraiser".as_ref(), fundraiser.maker.as_ref()],
+        seeds = [b"fundraiser".as_ref(), fundraiser.maker.as_ref(), fundraiser.campaign_id.to_le_bytes().as_ref()],
         bump = fundraiser.bump,
     )]
     pub fundraiser: Account<'info, Fundraiser>,
@@ -40,16 +47,34 @@ pub struct Contribute<'info> {
     #[account(
         mut,
         associated_token::mint = mint_to_raise,
-        associated_token::authority = contributor
+        associated_token::authority = contributor,
+        associated_token::token_program = token_program,
     )]
-    pub contributor_ata: Account<'info, TokenAccount>,
+    pub contributor_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = fundraiser.mint_to_raise,
-        associated_token::authority = fundraiser
+        associated_token::authority = fundraiser,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"receipt", fundraiser.key().as_ref()],
+        bump = fundraiser.receipt_bump,
+        mint::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = contributor,
+        associated_token::token_program = token_program,
+    )]
+    pub contributor_receipt_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -58,7 +83,7 @@ impl<'info> Contribute<'info> {
 
         // Check if the amount to contribute meets the minimum amount required
         require!(
-            amount > 1_u8.pow(self.mint_to_raise.decimals as u32) as u64, 
+            amount >= MIN_CONTRIBUTION, 
             FundraiserError::ContributionTooSmall
         );
 
@@ -68,13 +93,19 @@ impl<'info> Contribute<'info> {
             FundraiserError::ContributionTooBig
         );
 
-        // Check if the fundraising duration has been reached
+        // Contributions are only accepted before the fundraising duration has been reached
         let current_time = Clock::get()?.unix_timestamp;
         require!(
-            self.fundraiser.duration <= ((current_time - self.fundraiser.time_started) / SECONDS_TO_DAYS) as u8,
+            self.fundraiser.duration > ((current_time - self.fundraiser.time_started) / SECONDS_TO_DAYS) as u8,
             crate::FundraiserError::FundraiserEnded
         );
 
+        // Check if the target amount has already been met
+        require!(
+            self.fundraiser.current_amount < self.fundraiser.amount_to_raise,
+            FundraiserError::TargetMet
+        );
+
         // Check if the maximum contributions per contributor have been reached
         require!(
             (self.contributor_account.amount <= (self.fundraiser.amount_to_raise * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER)
@@ -87,8 +118,9 @@ impl<'info> Contribute<'info> {
         let cpi_program = self.token_program.to_account_info();
 
         // Transfer the funds from the contributor to the vault
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.contributor_ata.to_account_info(),
+            mint: self.mint_to_raise.to_account_info(),
             to: self.vault.to_account_info(),
             authority: self.contributor.to_account_info(),
         };
@@ -96,13 +128,47 @@ impl<'info> Contribute<'info> {
         // Crete a CPI context
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
+        // Snapshot the vault so transfer-fee mints are credited with what actually arrived
+        let vault_before = self.vault.amount;
+
         // Transfer the funds from the contributor to the vault
-        transfer(cpi_ctx, amount)?;
+        transfer_checked(cpi_ctx, amount, self.mint_to_raise.decimals)?;
+
+        self.vault.reload()?;
+        let received = self.vault.amount - vault_before;
+
+        // The contribution may meet the target but not overshoot it
+        require!(
+            self.fundraiser.current_amount + received <= self.fundraiser.amount_to_raise,
+            FundraiserError::TargetMet
+        );
+
+        // Update the fundraiser and contributor accounts with the amount received
+        self.fundraiser.current_amount += received;
 
-        // Update the fundraiser and contributor accounts with the new amounts
-        self.fundraiser.current_amount += amount;
+        self.contributor_account.amount += received;
+
+        // Mint receipt tokens 1:1 with the amount received, signed by the fundraiser PDA
+        let cpi_accounts = MintTo {
+            mint: self.receipt_mint.to_account_info(),
+            to: self.contributor_receipt_ata.to_account_info(),
+            authority: self.fundraiser.to_account_info(),
+        };
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"fundraiser".as_ref(),
+            self.fundraiser.maker.as_ref(),
+            &self.fundraiser.campaign_id.to_le_bytes()[..],
+            &[self.fundraiser.bump],
+        ]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            &signer_seeds,
+        );
 
-        self.contributor_account.amount += amount;
+        mint_to(cpi_ctx, received)?;
 
         Ok(())
     }
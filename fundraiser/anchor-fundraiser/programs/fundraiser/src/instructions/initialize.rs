@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken, 
-    token::{
+    token_interface::{
         Mint, 
-        Token, 
-        TokenAccount
+        TokenAccount, 
+        TokenInterface
     }
 };
 
@@ -13,14 +13,16 @@ use crate::{
 };
 
 #[derive(Accounts)]
+#[instruction(campaign_id: u64)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
-    pub mint_to_raise: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_to_raise: InterfaceAccount<'info, Mint>,
     #[account(
         init,
         payer = maker,
-        seeds = [b"fundraiser", maker.key().as_ref()],
+        seeds = [b"fundraiser", maker.key().as_ref(), campaign_id.to_le_bytes().as_ref()],
         bump,
         space = ANCHOR_DISCRIMINATOR + Fundraiser::INIT_SPACE,
     )]
@@ -30,31 +32,48 @@ pub struct Initialize<'info> {
         payer = maker,
         associated_token::mint = mint_to_raise,
         associated_token::authority = fundraiser,
+        associated_token::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"receipt", fundraiser.key().as_ref()],
+        bump,
+        mint::decimals = mint_to_raise.decimals,
+        mint::authority = fundraiser,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 impl<'info> Initialize<'info> {
-    pub fn initialize(&mut self, amount: u64, duration: u8, bumps: &InitializeBumps) -> Result<()> {
+    pub fn initialize(&mut self, campaign_id: u64, amount: u64, duration: u8, bumps: &InitializeBumps) -> Result<()> {
 
-        // Check if the amount to raise meets the minimum amount required
+        // Check if the amount to raise meets the minimum amount required (in whole tokens)
+        let min_amount_to_raise = 10_u64
+            .checked_pow(self.mint_to_raise.decimals as u32)
+            .and_then(|scale| scale.checked_mul(MIN_AMOUNT_TO_RAISE))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
         require!(
-            amount > MIN_AMOUNT_TO_RAISE.pow(self.mint_to_raise.decimals as u32),
+            amount >= min_amount_to_raise,
             FundraiserError::InvalidAmount
         );
 
         // Initialize the fundraiser account
         self.fundraiser.set_inner(Fundraiser {
             maker: self.maker.key(),
+            campaign_id,
             mint_to_raise: self.mint_to_raise.key(),
             amount_to_raise: amount,
             current_amount: 0,
             time_started: Clock::get()?.unix_timestamp,
             duration,
-            bump: bumps.fundraiser
+            bump: bumps.fundraiser,
+            receipt_bump: bumps.receipt_mint,
         });
         
         Ok(())
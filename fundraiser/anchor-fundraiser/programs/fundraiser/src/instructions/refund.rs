@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    transfer, 
+use anchor_spl::token_interface::{
+    burn, 
+    transfer_checked, 
+    Burn, 
     Mint, 
-    Token, 
     TokenAccount, 
-    Transfer
+    TokenInterface, 
+    TransferChecked
 };
 
 use crate::{
@@ -15,16 +17,20 @@ use crate::{
     SECONDS_TO_DAYS
 };
 
+/// Redeems receipt tokens for the funds backing them. `contributor` is whoever
+/// holds the receipts, not necessarily the original backer; the original
+/// backer passes their `Contributor` PDA to have it closed.
 #[derive(Accounts)]
 pub struct Refund<'info> {
     #[account(mut)]
     pub contributor: Signer<'info>,
     pub maker: SystemAccount<'info>,
-    pub mint_to_raise: Account<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_to_raise: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         has_one = mint_to_raise,
-        seeds = [b"fundraiser", maker.key().as_ref()],
+        seeds = [b"fundraiser", maker.key().as_ref(), fundraiser.campaign_id.to_le_bytes().as_ref()],
         bump = fundraiser.bump,
     )]
     pub fundraiser: Account<'info, Fundraiser>,
@@ -34,20 +40,36 @@ pub struct Refund<'info> {
         bump,
         close = contributor,
     )]
-    pub contributor_account: Account<'info, Contributor>,
+    pub contributor_account: Option<Account<'info, Contributor>>,
     #[account(
         mut,
         associated_token::mint = mint_to_raise,
-        associated_token::authority = contributor
+        associated_token::authority = contributor,
+        associated_token::token_program = token_program,
     )]
-    pub contributor_ata: Account<'info, TokenAccount>,
+    pub contributor_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_to_raise,
-        associated_token::authority = fundraiser
+        associated_token::authority = fundraiser,
+        associated_token::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"receipt", fundraiser.key().as_ref()],
+        bump = fundraiser.receipt_bump,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = contributor,
+        associated_token::token_program = token_program,
+    )]
+    pub contributor_receipt_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -58,22 +80,47 @@ impl<'info> Refund<'info> {
         let current_time = Clock::get()?.unix_timestamp;
  
         require!(
-            self.fundraiser.duration >= ((current_time - self.fundraiser.time_started) / SECONDS_TO_DAYS) as u8,
+            self.fundraiser.duration <= ((current_time - self.fundraiser.time_started) / SECONDS_TO_DAYS) as u8,
             crate::FundraiserError::FundraiserNotEnded
         );
 
+        // Check if the target amount has been met, either by the vault or the recorded contributions
         require!(
-            self.vault.amount < self.fundraiser.amount_to_raise,
+            self.vault.amount < self.fundraiser.amount_to_raise
+                && self.fundraiser.current_amount < self.fundraiser.amount_to_raise,
             crate::FundraiserError::TargetMet
         );
 
+        // Every receipt held is redeemable 1:1 for the funds it was minted against
+        let amount = self.contributor_receipt_ata.amount;
+
+        // A backer whose receipts have all moved on has nothing to redeem, but
+        // can still close their Contributor PDA to recover its rent
+        if amount == 0 {
+            require!(
+                self.contributor_account.is_some(),
+                crate::FundraiserError::NothingToRefund
+            );
+            return Ok(());
+        }
+
+        // Burn the receipts before releasing the funds
+        let cpi_accounts = Burn {
+            mint: self.receipt_mint.to_account_info(),
+            from: self.contributor_receipt_ata.to_account_info(),
+            authority: self.contributor.to_account_info(),
+        };
+
+        burn(CpiContext::new(self.token_program.to_account_info(), cpi_accounts), amount)?;
+
         // Transfer the funds back to the contributor
         // CPI to the token program to transfer the funds
         let cpi_program = self.token_program.to_account_info();
 
         // Transfer the funds from the vault to the contributor
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.vault.to_account_info(),
+            mint: self.mint_to_raise.to_account_info(),
             to: self.contributor_ata.to_account_info(),
             authority: self.fundraiser.to_account_info(),
         };
@@ -82,17 +129,20 @@ impl<'info> Refund<'info> {
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"fundraiser".as_ref(),
             self.maker.to_account_info().key.as_ref(),
+            &self.fundraiser.campaign_id.to_le_bytes()[..],
             &[self.fundraiser.bump],
         ]];
 
         // CPI context with signer since the fundraiser account is a PDA
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
 
-        // Transfer the funds from the vault to the contributor
-        transfer(cpi_ctx, self.contributor_account.amount)?;
+        // Transfer the funds from the vault to the contributor.
+        // `amount` is net of any inbound transfer fee; a fee on the way out is
+        // withheld from what the contributor receives, not from the vault.
+        transfer_checked(cpi_ctx, amount, self.mint_to_raise.decimals)?;
 
-        // Update the fundraiser state by reducing the amount contributed
-        self.fundraiser.current_amount -= self.contributor_account.amount;
+        // Update the fundraiser state by reducing the amount redeemed
+        self.fundraiser.current_amount -= amount;
 
         Ok(())
     }
@@ -1,3 +1,5 @@
+#![allow(unexpected_cfgs)]
+
 use anchor_lang::prelude::*;
 
 declare_id!("Eoiuq1dXvHxh6dLx3wh9gj8kSAUpga11krTrbfF5XYsC");
@@ -6,6 +8,7 @@ mod state;
 mod instructions;
 mod error;
 mod constants;
+mod tests;
 
 use instructions::*;
 use error::*;
@@ -15,9 +18,9 @@ pub use constants::*;
 pub mod fundraiser {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, amount: u64, duration: u8) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, campaign_id: u64, amount: u64, duration: u8) -> Result<()> {
 
-        ctx.accounts.initialize(amount, duration, &ctx.bumps)?;
+        ctx.accounts.initialize(campaign_id, amount, duration, &ctx.bumps)?;
 
         Ok(())
     }
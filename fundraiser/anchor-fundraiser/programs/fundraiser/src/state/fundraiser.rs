@@ -4,10 +4,12 @@ use anchor_lang::prelude::*;
 #[derive(InitSpace)]
 pub struct Fundraiser {
     pub maker: Pubkey,
+    pub campaign_id: u64,
     pub mint_to_raise: Pubkey,
     pub amount_to_raise: u64,
     pub current_amount: u64,
     pub time_started: i64,
     pub duration: u8,
     pub bump: u8,
+    pub receipt_bump: u8,
 }
\ No newline at end of file
@@ -0,0 +1,1102 @@
+#[cfg(test)]
+mod tests {
+
+    use {
+        anchor_lang::{
+            prelude::{msg, Clock},
+            solana_program::program_pack::Pack,
+            AccountDeserialize, InstructionData, ToAccountMetas,
+        },
+        anchor_spl::{
+            associated_token::{self, spl_associated_token_account},
+            token::spl_token,
+            token_2022::spl_token_2022::{
+                self,
+                extension::{
+                    transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType,
+                    StateWithExtensions,
+                },
+            },
+        },
+        litesvm::{
+            types::{FailedTransactionMetadata, TransactionMetadata},
+            LiteSVM,
+        },
+        litesvm_token::{
+            spl_token::ID as TOKEN_PROGRAM_ID, CreateAssociatedTokenAccount, CreateMint, MintTo,
+        },
+        solana_instruction::{error::InstructionError, Instruction},
+        solana_keypair::Keypair,
+        solana_message::Message,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_pubkey::Pubkey,
+        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_signer::Signer,
+        solana_transaction::Transaction,
+        solana_transaction_error::TransactionError,
+        std::path::PathBuf,
+    };
+
+    use crate::{
+        state::{Contributor, Fundraiser},
+        FundraiserError, SECONDS_TO_DAYS,
+    };
+
+    static PROGRAM_ID: Pubkey = crate::ID;
+
+    // Setup function to initialize LiteSVM, load the program and create a
+    // maker keypair together with the mint to raise (6 decimals, maker is authority)
+    fn setup() -> (LiteSVM, Keypair, Pubkey) {
+        let mut program = LiteSVM::new();
+        let maker = Keypair::new();
+
+        program
+            .airdrop(&maker.pubkey(), 50 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to maker");
+
+        // Load program SO file
+        let so_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/fundraiser.so");
+
+        let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
+
+        program.add_program(PROGRAM_ID, &program_data);
+
+        let mint = CreateMint::new(&mut program, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+
+        (program, maker, mint)
+    }
+
+    fn send(
+        program: &mut LiteSVM,
+        ix: Instruction,
+        signer: &Keypair,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        program.expire_blockhash();
+        let message = Message::new(&[ix], Some(&signer.pubkey()));
+        let transaction = Transaction::new(&[signer], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    fn find_fundraiser(maker: &Pubkey, campaign_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"fundraiser", maker.as_ref(), &campaign_id.to_le_bytes()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn find_receipt_mint(fundraiser: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"receipt", fundraiser.as_ref()], &PROGRAM_ID).0
+    }
+
+    // Reads legacy SPL Token and Token-2022 accounts alike
+    fn token_balance(program: &LiteSVM, ata: &Pubkey) -> u64 {
+        let account = program.get_account(ata).unwrap();
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    // The token program that owns `mint`, so helpers work for legacy and Token-2022 mints
+    fn token_program_of(program: &LiteSVM, mint: &Pubkey) -> Pubkey {
+        program.get_account(mint).unwrap().owner
+    }
+
+    fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        associated_token::get_associated_token_address_with_program_id(owner, mint, token_program)
+    }
+
+    fn fundraiser_state(program: &LiteSVM, fundraiser: &Pubkey) -> Fundraiser {
+        let account = program.get_account(fundraiser).unwrap();
+        Fundraiser::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    fn contributor_state(program: &LiteSVM, fundraiser: &Pubkey, contributor: &Pubkey) -> Contributor {
+        let contributor_account = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), contributor.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let account = program.get_account(&contributor_account).unwrap();
+        Contributor::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    fn is_closed(program: &LiteSVM, address: &Pubkey) -> bool {
+        program
+            .get_account(address)
+            .map_or(true, |account| account.lamports == 0)
+    }
+
+    // Starts campaign `campaign_id` for the maker and returns (fundraiser, vault)
+    fn initialize(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint: Pubkey,
+        campaign_id: u64,
+        amount: u64,
+        duration: u8,
+    ) -> Result<(Pubkey, Pubkey), FailedTransactionMetadata> {
+        let fundraiser = find_fundraiser(&maker.pubkey(), campaign_id);
+        let token_program = token_program_of(program, &mint);
+        let vault = ata(&fundraiser, &mint, &token_program);
+
+        let initialize_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Initialize {
+                maker: maker.pubkey(),
+                mint_to_raise: mint,
+                fundraiser,
+                vault,
+                receipt_mint: find_receipt_mint(&fundraiser),
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Initialize {
+                campaign_id,
+                amount,
+                duration,
+            }
+            .data(),
+        };
+
+        send(program, initialize_ix, maker).map(|_| (fundraiser, vault))
+    }
+
+    // Funded contributor with an ATA holding `tokens` of the mint
+    fn new_contributor(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint: Pubkey,
+        tokens: u64,
+    ) -> (Keypair, Pubkey) {
+        let contributor = Keypair::new();
+        program
+            .airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let contributor_ata = CreateAssociatedTokenAccount::new(program, &contributor, &mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+
+        MintTo::new(program, maker, &mint, &contributor_ata, tokens)
+            .send()
+            .unwrap();
+
+        (contributor, contributor_ata)
+    }
+
+    fn contribute(
+        program: &mut LiteSVM,
+        contributor: &Keypair,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+        amount: u64,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let contributor_account = Pubkey::find_program_address(
+            &[
+                b"contributor",
+                fundraiser.as_ref(),
+                contributor.pubkey().as_ref(),
+            ],
+            &PROGRAM_ID,
+        )
+        .0;
+        let token_program = token_program_of(program, &mint);
+
+        let contribute_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Contribute {
+                contributor: contributor.pubkey(),
+                mint_to_raise: mint,
+                fundraiser,
+                contributor_account,
+                contributor_ata: ata(&contributor.pubkey(), &mint, &token_program),
+                vault: ata(&fundraiser, &mint, &token_program),
+                receipt_mint: find_receipt_mint(&fundraiser),
+                contributor_receipt_ata: ata(
+                    &contributor.pubkey(),
+                    &find_receipt_mint(&fundraiser),
+                    &token_program,
+                ),
+                token_program,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Contribute { amount }.data(),
+        };
+
+        send(program, contribute_ix, contributor)
+    }
+
+    fn check_contributions(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let token_program = token_program_of(program, &mint);
+        let check_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::CheckContributions {
+                maker: maker.pubkey(),
+                mint_to_raise: mint,
+                fundraiser,
+                vault: ata(&fundraiser, &mint, &token_program),
+                maker_ata: ata(&maker.pubkey(), &mint, &token_program),
+                token_program,
+                system_program: SYSTEM_PROGRAM_ID,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CheckContributions {}.data(),
+        };
+
+        send(program, check_ix, maker)
+    }
+
+    fn refund_ix(
+        holder: Pubkey,
+        maker: Pubkey,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+        contributor_account: Option<Pubkey>,
+        token_program: Pubkey,
+    ) -> Instruction {
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                contributor: holder,
+                maker,
+                mint_to_raise: mint,
+                fundraiser,
+                contributor_account,
+                contributor_ata: ata(&holder, &mint, &token_program),
+                vault: ata(&fundraiser, &mint, &token_program),
+                receipt_mint,
+                contributor_receipt_ata: ata(&holder, &receipt_mint, &token_program),
+                token_program,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        }
+    }
+
+    // Original backer redeems their receipts and closes their Contributor PDA
+    fn refund(
+        program: &mut LiteSVM,
+        contributor: &Keypair,
+        maker: Pubkey,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let contributor_account = Pubkey::find_program_address(
+            &[
+                b"contributor",
+                fundraiser.as_ref(),
+                contributor.pubkey().as_ref(),
+            ],
+            &PROGRAM_ID,
+        )
+        .0;
+
+        let token_program = token_program_of(program, &mint);
+        let refund_ix = refund_ix(
+            contributor.pubkey(),
+            maker,
+            mint,
+            fundraiser,
+            Some(contributor_account),
+            token_program,
+        );
+        send(program, refund_ix, contributor)
+    }
+
+    // Any receipt holder redeems without a Contributor PDA
+    fn redeem(
+        program: &mut LiteSVM,
+        holder: &Keypair,
+        maker: Pubkey,
+        mint: Pubkey,
+        fundraiser: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let token_program = token_program_of(program, &mint);
+        let refund_ix = refund_ix(holder.pubkey(), maker, mint, fundraiser, None, token_program);
+        send(program, refund_ix, holder)
+    }
+
+    // Asserts the transaction failed with the given `FundraiserError`
+    fn assert_fundraiser_error<T: std::fmt::Debug>(
+        result: Result<T, FailedTransactionMetadata>,
+        error: FundraiserError,
+    ) {
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(
+                    error as u32 + anchor_lang::error::ERROR_CODE_OFFSET
+                )
+            )
+        );
+    }
+
+    fn warp_days(program: &mut LiteSVM, days: i64) {
+        warp_seconds(program, days * SECONDS_TO_DAYS);
+    }
+
+    // Time Travel
+    fn warp_seconds(program: &mut LiteSVM, seconds: i64) {
+        let mut clock: Clock = program.get_sysvar();
+        let current_time = clock.unix_timestamp;
+        clock.unix_timestamp = current_time + seconds;
+        program.set_sysvar(&clock);
+
+        msg!(
+            "Travelled into future: {} -> {}",
+            current_time,
+            clock.unix_timestamp
+        );
+    }
+
+    #[test]
+    fn test_contribute_before_deadline() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let (contributor, contributor_ata) =
+            new_contributor(&mut program, &maker, mint, 5_000_000);
+
+        let tx = contribute(&mut program, &contributor, mint, fundraiser, 400_000).unwrap();
+        msg!("Contribute CUs Consumed: {}", tx.compute_units_consumed);
+
+        // One second before the 3-day deadline is still open
+        warp_seconds(&mut program, 3 * SECONDS_TO_DAYS - 1);
+        contribute(&mut program, &contributor, mint, fundraiser, 600_000).unwrap();
+
+        assert_eq!(token_balance(&program, &contributor_ata), 4_000_000);
+        assert_eq!(token_balance(&program, &vault), 1_000_000);
+        assert_eq!(
+            contributor_state(&program, &fundraiser, &contributor.pubkey()).amount,
+            1_000_000
+        );
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 1_000_000);
+
+        // The deadline itself is closed
+        warp_seconds(&mut program, 1);
+        let result = contribute(&mut program, &contributor, mint, fundraiser, 1);
+        assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+        assert_eq!(token_balance(&program, &vault), 1_000_000);
+    }
+
+    #[test]
+    fn test_check_contributions_after_deadline() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+
+        for _ in 0..10 {
+            let (contributor, contributor_ata) =
+                new_contributor(&mut program, &maker, mint, 1_000_000);
+            contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+            assert_eq!(token_balance(&program, &contributor_ata), 0);
+        }
+        assert_eq!(token_balance(&program, &vault), 10_000_000);
+
+        // Target met, but the maker still has to wait for the deadline
+        warp_days(&mut program, 2);
+        let result = check_contributions(&mut program, &maker, mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+
+        warp_days(&mut program, 1);
+        let tx = check_contributions(&mut program, &maker, mint, fundraiser).unwrap();
+        msg!("CheckContributions CUs Consumed: {}", tx.compute_units_consumed);
+
+        // maker_ata is created on the fly and receives the whole vault
+        let maker_ata = associated_token::get_associated_token_address(&maker.pubkey(), &mint);
+        assert_eq!(token_balance(&program, &maker_ata), 10_000_000);
+        assert_eq!(token_balance(&program, &vault), 0);
+        assert!(is_closed(&program, &fundraiser));
+    }
+
+    #[test]
+    fn test_refund_after_deadline() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+
+        let (first, first_ata) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        let (second, second_ata) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &first, mint, fundraiser, 1_000_000).unwrap();
+        contribute(&mut program, &second, mint, fundraiser, 250_000).unwrap();
+        assert_eq!(token_balance(&program, &vault), 1_250_000);
+
+        let result = refund(&mut program, &first, maker.pubkey(), mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+
+        warp_days(&mut program, 3);
+
+        let tx = refund(&mut program, &first, maker.pubkey(), mint, fundraiser).unwrap();
+        msg!("Refund CUs Consumed: {}", tx.compute_units_consumed);
+
+        assert_eq!(token_balance(&program, &first_ata), 1_000_000);
+        assert_eq!(token_balance(&program, &vault), 250_000);
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 250_000);
+
+        let first_account = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), first.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        assert!(is_closed(&program, &first_account));
+
+        refund(&mut program, &second, maker.pubkey(), mint, fundraiser).unwrap();
+        assert_eq!(token_balance(&program, &second_ata), 1_000_000);
+        assert_eq!(token_balance(&program, &vault), 0);
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 0);
+
+        // A failed raise can't be claimed by the maker
+        let result = check_contributions(&mut program, &maker, mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::TargetNotMet);
+    }
+
+    #[test]
+    fn test_deadline_boundary() {
+        let (mut program, maker, mint) = setup();
+        let (met, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 2).unwrap();
+        let (failed, _) = initialize(&mut program, &maker, mint, 2, 10_000_000, 2).unwrap();
+
+        for _ in 0..10 {
+            let (contributor, _) = new_contributor(&mut program, &maker, mint, 1_000_000);
+            contribute(&mut program, &contributor, mint, met, 1_000_000).unwrap();
+        }
+        let (backer, backer_ata) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &backer, mint, failed, 1_000_000).unwrap();
+
+        // One second before the deadline the raise is still running
+        warp_seconds(&mut program, 2 * SECONDS_TO_DAYS - 1);
+        let (late, _) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &late, mint, failed, 1).unwrap();
+        let result = check_contributions(&mut program, &maker, mint, met);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+        let result = refund(&mut program, &backer, maker.pubkey(), mint, failed);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+
+        // At the deadline contributions close and claims and refunds open
+        warp_seconds(&mut program, 1);
+        let result = contribute(&mut program, &late, mint, failed, 1);
+        assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+        check_contributions(&mut program, &maker, mint, met).unwrap();
+        refund(&mut program, &backer, maker.pubkey(), mint, failed).unwrap();
+        assert_eq!(token_balance(&program, &backer_ata), 1_000_000);
+    }
+
+    #[test]
+    fn test_two_concurrent_campaigns() {
+        let (mut program, maker, mint) = setup();
+
+        // Short campaign that will hit its target, and a longer one running alongside it
+        let (short_fundraiser, short_vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (long_fundraiser, long_vault) =
+            initialize(&mut program, &maker, mint, 2, 50_000_000, 10).unwrap();
+        assert_ne!(short_fundraiser, long_fundraiser);
+
+        let short_state = fundraiser_state(&program, &short_fundraiser);
+        let long_state = fundraiser_state(&program, &long_fundraiser);
+        assert_eq!(short_state.campaign_id, 1);
+        assert_eq!(long_state.campaign_id, 2);
+        assert_eq!(short_state.maker, maker.pubkey());
+        assert_eq!(long_state.maker, maker.pubkey());
+
+        // The same backers fund both campaigns
+        for _ in 0..10 {
+            let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+            contribute(&mut program, &contributor, mint, short_fundraiser, 1_000_000).unwrap();
+            contribute(&mut program, &contributor, mint, long_fundraiser, 500_000).unwrap();
+        }
+
+        assert_eq!(token_balance(&program, &short_vault), 10_000_000);
+        assert_eq!(token_balance(&program, &long_vault), 5_000_000);
+        assert_eq!(
+            fundraiser_state(&program, &short_fundraiser).current_amount,
+            10_000_000
+        );
+        assert_eq!(
+            fundraiser_state(&program, &long_fundraiser).current_amount,
+            5_000_000
+        );
+
+        // Closing the short campaign only pays out its own vault
+        warp_days(&mut program, 2);
+        CreateAssociatedTokenAccount::new(&mut program, &maker, &mint)
+            .owner(&maker.pubkey())
+            .send()
+            .unwrap();
+        let tx = check_contributions(&mut program, &maker, mint, short_fundraiser).unwrap();
+        msg!("CheckContributions CUs Consumed: {}", tx.compute_units_consumed);
+
+        let maker_ata = associated_token::get_associated_token_address(&maker.pubkey(), &mint);
+        assert_eq!(token_balance(&program, &maker_ata), 10_000_000);
+        assert_eq!(token_balance(&program, &short_vault), 0);
+        assert_eq!(token_balance(&program, &long_vault), 5_000_000);
+
+        assert!(is_closed(&program, &short_fundraiser));
+
+        // The long campaign keeps accepting contributions
+        let (late, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        contribute(&mut program, &late, mint, long_fundraiser, 2_000_000).unwrap();
+        assert_eq!(token_balance(&program, &long_vault), 7_000_000);
+        assert_eq!(
+            fundraiser_state(&program, &long_fundraiser).current_amount,
+            7_000_000
+        );
+    }
+
+    #[test]
+    fn test_duplicate_campaign_id_fails() {
+        let (mut program, maker, mint) = setup();
+
+        initialize(&mut program, &maker, mint, 7, 10_000_000, 1).unwrap();
+
+        let result = initialize(&mut program, &maker, mint, 7, 20_000_000, 5);
+        assert!(
+            result.is_err(),
+            "Initialize should fail while the campaign ID is in use"
+        );
+
+        // A different ID from the same maker is fine
+        initialize(&mut program, &maker, mint, 8, 20_000_000, 5).unwrap();
+    }
+
+    // ─── Validation matrix: one test per FundraiserError variant ────────
+
+    #[test]
+    fn test_initialize_below_minimum_fails() {
+        let (mut program, maker, mint) = setup();
+
+        // MIN_AMOUNT_TO_RAISE is 3 whole tokens, i.e. 3_000_000 at 6 decimals
+        let result = initialize(&mut program, &maker, mint, 1, 2_999_999, 1);
+        assert_fundraiser_error(result, FundraiserError::InvalidAmount);
+
+        initialize(&mut program, &maker, mint, 1, 3_000_000, 1).unwrap();
+    }
+
+    #[test]
+    fn test_contribute_zero_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+
+        let result = contribute(&mut program, &contributor, mint, fundraiser, 0);
+        assert_fundraiser_error(result, FundraiserError::ContributionTooSmall);
+    }
+
+    #[test]
+    fn test_contribute_above_cap_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+
+        // 10% of the target is the most a single contribution can be
+        let result = contribute(&mut program, &contributor, mint, fundraiser, 1_000_001);
+        assert_fundraiser_error(result, FundraiserError::ContributionTooBig);
+    }
+
+    #[test]
+    fn test_contribute_past_contributor_cap_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+
+        contribute(&mut program, &contributor, mint, fundraiser, 600_000).unwrap();
+
+        // Each call is within the cap, but the running total is not
+        let result = contribute(&mut program, &contributor, mint, fundraiser, 600_000);
+        assert_fundraiser_error(result, FundraiserError::MaximumContributionsReached);
+        assert_eq!(token_balance(&program, &vault), 600_000);
+    }
+
+    #[test]
+    fn test_contribute_after_target_met_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+
+        for _ in 0..10 {
+            let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+            contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+        }
+
+        let (late, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        let result = contribute(&mut program, &late, mint, fundraiser, 1_000_000);
+        assert_fundraiser_error(result, FundraiserError::TargetMet);
+        assert_eq!(token_balance(&program, &vault), 10_000_000);
+    }
+
+    #[test]
+    fn test_contribute_overshooting_target_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+
+        for _ in 0..9 {
+            let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+            contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+        }
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        contribute(&mut program, &contributor, mint, fundraiser, 600_000).unwrap();
+
+        // 9_600_000 raised, so 600_000 more would overshoot the target
+        let (last, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        let result = contribute(&mut program, &last, mint, fundraiser, 600_000);
+        assert_fundraiser_error(result, FundraiserError::TargetMet);
+        assert_eq!(token_balance(&program, &vault), 9_600_000);
+
+        // Landing exactly on the target is fine
+        contribute(&mut program, &last, mint, fundraiser, 400_000).unwrap();
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 10_000_000);
+    }
+
+    #[test]
+    fn test_contribute_after_deadline_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+
+        warp_days(&mut program, 1);
+
+        let result = contribute(&mut program, &contributor, mint, fundraiser, 1_000_000);
+        assert_fundraiser_error(result, FundraiserError::FundraiserEnded);
+    }
+
+    #[test]
+    fn test_check_contributions_before_deadline_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+
+        let result = check_contributions(&mut program, &maker, mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+    }
+
+    #[test]
+    fn test_check_contributions_target_not_met_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+
+        warp_days(&mut program, 2);
+
+        let result = check_contributions(&mut program, &maker, mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::TargetNotMet);
+    }
+
+    #[test]
+    fn test_refund_before_deadline_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+        contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+
+        let result = refund(&mut program, &contributor, maker.pubkey(), mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+    }
+
+    #[test]
+    fn test_refund_after_target_met_fails() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 1).unwrap();
+
+        let mut contributors = Vec::new();
+        for _ in 0..10 {
+            let (contributor, _) = new_contributor(&mut program, &maker, mint, 10_000_000);
+            contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+            contributors.push(contributor);
+        }
+
+        warp_days(&mut program, 2);
+
+        let result = refund(&mut program, &contributors[0], maker.pubkey(), mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::TargetMet);
+        assert_eq!(token_balance(&program, &vault), 10_000_000);
+    }
+
+    // ─── Receipt tokens ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_contribute_mints_receipts() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, _) = initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        let receipt_state = spl_token::state::Mint::unpack(
+            &program.get_account(&receipt_mint).unwrap().data,
+        )
+        .unwrap();
+        assert_eq!(receipt_state.decimals, 6);
+        assert_eq!(
+            receipt_state.mint_authority,
+            Some(fundraiser).into()
+        );
+        assert_eq!(receipt_state.supply, 0);
+
+        let (contributor, _) = new_contributor(&mut program, &maker, mint, 5_000_000);
+        contribute(&mut program, &contributor, mint, fundraiser, 400_000).unwrap();
+        contribute(&mut program, &contributor, mint, fundraiser, 300_000).unwrap();
+
+        let receipt_ata =
+            associated_token::get_associated_token_address(&contributor.pubkey(), &receipt_mint);
+        assert_eq!(token_balance(&program, &receipt_ata), 700_000);
+
+        let receipt_state = spl_token::state::Mint::unpack(
+            &program.get_account(&receipt_mint).unwrap().data,
+        )
+        .unwrap();
+        assert_eq!(receipt_state.supply, 700_000);
+    }
+
+    #[test]
+    fn test_refund_burns_receipts() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        let (contributor, contributor_ata) =
+            new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &contributor, mint, fundraiser, 800_000).unwrap();
+
+        warp_days(&mut program, 3);
+        refund(&mut program, &contributor, maker.pubkey(), mint, fundraiser).unwrap();
+
+        let receipt_ata =
+            associated_token::get_associated_token_address(&contributor.pubkey(), &receipt_mint);
+        assert_eq!(token_balance(&program, &receipt_ata), 0);
+        assert_eq!(token_balance(&program, &contributor_ata), 1_000_000);
+        assert_eq!(token_balance(&program, &vault), 0);
+
+        let receipt_state = spl_token::state::Mint::unpack(
+            &program.get_account(&receipt_mint).unwrap().data,
+        )
+        .unwrap();
+        assert_eq!(receipt_state.supply, 0);
+    }
+
+    #[test]
+    fn test_secondary_holder_redeems_receipts() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        let (backer, backer_ata) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &backer, mint, fundraiser, 1_000_000).unwrap();
+
+        // The backer sells 600_000 receipts to a buyer who never contributed
+        let (buyer, buyer_ata) = new_contributor(&mut program, &maker, mint, 0);
+        let buyer_receipt_ata = CreateAssociatedTokenAccount::new(&mut program, &buyer, &receipt_mint)
+            .owner(&buyer.pubkey())
+            .send()
+            .unwrap();
+        let backer_receipt_ata =
+            associated_token::get_associated_token_address(&backer.pubkey(), &receipt_mint);
+
+        let transfer_ix = spl_token::instruction::transfer(
+            &TOKEN_PROGRAM_ID,
+            &backer_receipt_ata,
+            &buyer_receipt_ata,
+            &backer.pubkey(),
+            &[],
+            600_000,
+        )
+        .unwrap();
+        send(&mut program, transfer_ix, &backer).unwrap();
+
+        // Receipts can't be redeemed while the raise is still running
+        let result = redeem(&mut program, &buyer, maker.pubkey(), mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::FundraiserNotEnded);
+
+        warp_days(&mut program, 3);
+
+        redeem(&mut program, &buyer, maker.pubkey(), mint, fundraiser).unwrap();
+        assert_eq!(token_balance(&program, &buyer_ata), 600_000);
+        assert_eq!(token_balance(&program, &buyer_receipt_ata), 0);
+        assert_eq!(token_balance(&program, &vault), 400_000);
+
+        // The backer only gets back what their remaining receipts cover
+        refund(&mut program, &backer, maker.pubkey(), mint, fundraiser).unwrap();
+        assert_eq!(token_balance(&program, &backer_ata), 400_000);
+        assert_eq!(token_balance(&program, &vault), 0);
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 0);
+
+        // Nothing left to redeem
+        let result = redeem(&mut program, &buyer, maker.pubkey(), mint, fundraiser);
+        assert_fundraiser_error(result, FundraiserError::NothingToRefund);
+    }
+
+    #[test]
+    fn test_backer_without_receipts_closes_contributor_account() {
+        let (mut program, maker, mint) = setup();
+        let (fundraiser, vault) =
+            initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        let (backer, backer_ata) = new_contributor(&mut program, &maker, mint, 1_000_000);
+        contribute(&mut program, &backer, mint, fundraiser, 1_000_000).unwrap();
+
+        // The backer sells every receipt
+        let (buyer, buyer_ata) = new_contributor(&mut program, &maker, mint, 0);
+        let buyer_receipt_ata = CreateAssociatedTokenAccount::new(&mut program, &buyer, &receipt_mint)
+            .owner(&buyer.pubkey())
+            .send()
+            .unwrap();
+        let backer_receipt_ata =
+            associated_token::get_associated_token_address(&backer.pubkey(), &receipt_mint);
+        let transfer_ix = spl_token::instruction::transfer(
+            &TOKEN_PROGRAM_ID,
+            &backer_receipt_ata,
+            &buyer_receipt_ata,
+            &backer.pubkey(),
+            &[],
+            1_000_000,
+        )
+        .unwrap();
+        send(&mut program, transfer_ix, &backer).unwrap();
+
+        warp_days(&mut program, 3);
+        redeem(&mut program, &buyer, maker.pubkey(), mint, fundraiser).unwrap();
+        assert_eq!(token_balance(&program, &buyer_ata), 1_000_000);
+
+        // Nothing to redeem, but the backer still gets their PDA rent back
+        let contributor_account = Pubkey::find_program_address(
+            &[b"contributor", fundraiser.as_ref(), backer.pubkey().as_ref()],
+            &PROGRAM_ID,
+        )
+        .0;
+        let rent = program.get_account(&contributor_account).unwrap().lamports;
+        let backer_lamports = program.get_account(&backer.pubkey()).unwrap().lamports;
+
+        refund(&mut program, &backer, maker.pubkey(), mint, fundraiser).unwrap();
+        assert!(is_closed(&program, &contributor_account));
+        // Rent back, less the 5_000 lamport signature fee
+        assert_eq!(
+            program.get_account(&backer.pubkey()).unwrap().lamports,
+            backer_lamports + rent - 5_000
+        );
+        assert_eq!(token_balance(&program, &backer_ata), 0);
+        assert_eq!(token_balance(&program, &vault), 0);
+    }
+
+    // ─── Token-2022 transfer-fee mints ───────────────────────────────────
+
+    const FEE_BASIS_POINTS: u16 = 100; // 1%
+
+    // Token-2022 mint (6 decimals, maker is authority) that withholds a 1% fee on every transfer
+    fn create_fee_mint(program: &mut LiteSVM, maker: &Keypair) -> Pubkey {
+        let mint = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+
+        let instructions = [
+            solana_system_interface::instruction::create_account(
+                &maker.pubkey(),
+                &mint.pubkey(),
+                program.minimum_balance_for_rent_exemption(space),
+                space as u64,
+                &spl_token_2022::ID,
+            ),
+            initialize_transfer_fee_config(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                Some(&maker.pubkey()),
+                Some(&maker.pubkey()),
+                FEE_BASIS_POINTS,
+                u64::MAX,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                &maker.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ];
+        let message = Message::new(&instructions, Some(&maker.pubkey()));
+        let transaction = Transaction::new(&[maker, &mint], message, program.latest_blockhash());
+        program.send_transaction(transaction).unwrap();
+
+        mint.pubkey()
+    }
+
+    // Funded contributor with a Token-2022 ATA holding `tokens` of the fee mint
+    fn new_fee_contributor(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        mint: Pubkey,
+        tokens: u64,
+    ) -> (Keypair, Pubkey) {
+        let contributor = Keypair::new();
+        program
+            .airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let create_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &contributor.pubkey(),
+                &contributor.pubkey(),
+                &mint,
+                &spl_token_2022::ID,
+            );
+        send(program, create_ata_ix, &contributor).unwrap();
+
+        let contributor_ata = ata(&contributor.pubkey(), &mint, &spl_token_2022::ID);
+        let mint_ix = spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            &mint,
+            &contributor_ata,
+            &maker.pubkey(),
+            &[],
+            tokens,
+        )
+        .unwrap();
+        send(program, mint_ix, maker).unwrap();
+
+        (contributor, contributor_ata)
+    }
+
+    #[test]
+    fn test_transfer_fee_contribute_and_refund() {
+        let (mut program, maker, _) = setup();
+        let mint = create_fee_mint(&mut program, &maker);
+        let (fundraiser, vault) = initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+        let receipt_mint = find_receipt_mint(&fundraiser);
+
+        let (first, first_ata) = new_fee_contributor(&mut program, &maker, mint, 1_000_000);
+        let (second, _) = new_fee_contributor(&mut program, &maker, mint, 1_000_000);
+
+        // The fee is withheld on the way in, so only the net amount reaches the vault
+        contribute(&mut program, &first, mint, fundraiser, 1_000_000).unwrap();
+        let first_net = token_balance(&program, &vault);
+        assert!(first_net < 1_000_000);
+        assert_eq!(
+            fundraiser_state(&program, &fundraiser).current_amount,
+            first_net
+        );
+        assert_eq!(
+            contributor_state(&program, &fundraiser, &first.pubkey()).amount,
+            first_net
+        );
+        assert_eq!(
+            token_balance(
+                &program,
+                &ata(&first.pubkey(), &receipt_mint, &spl_token_2022::ID)
+            ),
+            first_net
+        );
+
+        contribute(&mut program, &second, mint, fundraiser, 500_000).unwrap();
+        let second_net = token_balance(&program, &vault) - first_net;
+        assert!(second_net < 500_000);
+        assert_eq!(
+            fundraiser_state(&program, &fundraiser).current_amount,
+            first_net + second_net
+        );
+        assert_eq!(
+            contributor_state(&program, &fundraiser, &second.pubkey()).amount,
+            second_net
+        );
+
+        // Refunds release exactly what was recorded, and the vault drops by that much
+        warp_days(&mut program, 3);
+        let vault_before = token_balance(&program, &vault);
+        refund(&mut program, &first, maker.pubkey(), mint, fundraiser).unwrap();
+        let released = vault_before - token_balance(&program, &vault);
+        assert_eq!(released, first_net);
+        assert_eq!(
+            fundraiser_state(&program, &fundraiser).current_amount,
+            second_net
+        );
+        assert_eq!(token_balance(&program, &vault), second_net);
+
+        // The outbound fee comes out of what the contributor receives
+        let first_received = token_balance(&program, &first_ata);
+        assert!(first_received < first_net);
+
+        refund(&mut program, &second, maker.pubkey(), mint, fundraiser).unwrap();
+        assert_eq!(token_balance(&program, &vault), 0);
+        assert_eq!(fundraiser_state(&program, &fundraiser).current_amount, 0);
+    }
+
+    #[test]
+    fn test_transfer_fee_check_contributions() {
+        let (mut program, maker, _) = setup();
+        let mint = create_fee_mint(&mut program, &maker);
+        let (fundraiser, vault) = initialize(&mut program, &maker, mint, 1, 10_000_000, 3).unwrap();
+
+        // At the 1_000_000 cap each contribution nets less than a tenth of the
+        // target, so it takes an eleventh contributor to meet it
+        let mut recorded = 0;
+        for _ in 0..10 {
+            let (contributor, _) = new_fee_contributor(&mut program, &maker, mint, 1_000_000);
+            let vault_before = token_balance(&program, &vault);
+            contribute(&mut program, &contributor, mint, fundraiser, 1_000_000).unwrap();
+            let received = token_balance(&program, &vault) - vault_before;
+
+            assert!(received < 1_000_000);
+            assert_eq!(
+                contributor_state(&program, &fundraiser, &contributor.pubkey()).amount,
+                received
+            );
+            recorded += received;
+            assert_eq!(
+                fundraiser_state(&program, &fundraiser).current_amount,
+                recorded
+            );
+        }
+        assert_eq!(recorded, 9_900_000);
+
+        // The overshoot check runs on what arrived: 990_000 net is still too much
+        let (last, _) = new_fee_contributor(&mut program, &maker, mint, 1_000_000);
+        let result = contribute(&mut program, &last, mint, fundraiser, 1_000_000);
+        assert_fundraiser_error(result, FundraiserError::TargetMet);
+
+        // 101_011 less its 1_011 fee lands exactly on the target
+        contribute(&mut program, &last, mint, fundraiser, 101_011).unwrap();
+        recorded += 100_000;
+        assert_eq!(
+            fundraiser_state(&program, &fundraiser).current_amount,
+            recorded
+        );
+        assert_eq!(token_balance(&program, &vault), recorded);
+
+        // The maker claims the whole vault, less the fee on the way out
+        warp_days(&mut program, 3);
+        check_contributions(&mut program, &maker, mint, fundraiser).unwrap();
+        let maker_ata = ata(&maker.pubkey(), &mint, &spl_token_2022::ID);
+        let claimed = token_balance(&program, &maker_ata);
+        assert!(claimed < recorded);
+        assert!(claimed >= recorded - recorded.div_ceil(100));
+        assert_eq!(token_balance(&program, &vault), 0);
+        assert!(is_closed(&program, &fundraiser));
+    }
+}
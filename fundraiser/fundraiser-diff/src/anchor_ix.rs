@@ -0,0 +1,177 @@
+//! Hand-rolled instruction builders for `anchor-fundraiser`.
+//!
+//! The Anchor crate pins an older Solana SDK than LiteSVM, so it is not linked
+//! here; account order mirrors the `#[derive(Accounts)]` structs and data is
+//! `sha256("global:<name>")[..8]` followed by the Borsh-encoded arguments.
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+pub const PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("Eoiuq1dXvHxh6dLx3wh9gj8kSAUpga11krTrbfF5XYsC");
+
+const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const SYSTEM_PROGRAM_ID: Pubkey = solana_sdk_ids::system_program::ID;
+
+const INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+const CONTRIBUTE: [u8; 8] = [82, 33, 68, 131, 32, 0, 205, 95];
+const CHECK_CONTRIBUTIONS: [u8; 8] = [188, 45, 184, 27, 146, 96, 153, 5];
+const REFUND: [u8; 8] = [2, 96, 183, 251, 63, 208, 46, 46];
+
+/// Seeds: ["fundraiser", maker, campaign_id (u64 LE)]
+pub fn find_fundraiser_address(maker: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"fundraiser".as_ref(),
+            maker.as_ref(),
+            &campaign_id.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Seeds: ["receipt", fundraiser]
+pub fn find_receipt_mint(fundraiser: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"receipt".as_ref(), fundraiser.as_ref()], &PROGRAM_ID).0
+}
+
+/// ATA of `owner` for `mint` under the SPL Token program
+fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn find_contributor_address(fundraiser: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"contributor".as_ref(),
+            fundraiser.as_ref(),
+            contributor.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Data: [disc(8), campaign_id(8), amount(8), duration(1)]
+pub fn initialize(
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    vault: &Pubkey,
+    campaign_id: u64,
+    amount: u64,
+    duration: u8,
+) -> Instruction {
+    let mut data = INITIALIZE.to_vec();
+    data.extend_from_slice(&campaign_id.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(duration);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(find_receipt_mint(fundraiser), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Data: [disc(8), amount(8)] — contributor account and receipt ATA are `init_if_needed`
+pub fn contribute(
+    contributor: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    contributor_account: &Pubkey,
+    contributor_ata: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut data = CONTRIBUTE.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    let receipt_mint = find_receipt_mint(fundraiser);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*contributor_account, false),
+            AccountMeta::new(*contributor_ata, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(associated_token_address(contributor, &receipt_mint), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Data: [disc(8)] — maker ATA is `init_if_needed`
+pub fn check_contributions(
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    vault: &Pubkey,
+    maker_ata: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(*maker_ata, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: CHECK_CONTRIBUTIONS.to_vec(),
+    }
+}
+
+/// Data: [disc(8)] — burns the contributor's whole receipt balance
+pub fn refund(
+    contributor: &Pubkey,
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    contributor_account: &Pubkey,
+    contributor_ata: &Pubkey,
+    vault: &Pubkey,
+) -> Instruction {
+    let receipt_mint = find_receipt_mint(fundraiser);
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*maker, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*contributor_account, false),
+            AccountMeta::new(*contributor_ata, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(associated_token_address(contributor, &receipt_mint), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: REFUND.to_vec(),
+    }
+}
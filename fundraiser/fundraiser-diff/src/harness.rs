@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use litesvm::LiteSVM;
+use litesvm_token::{spl_token, CreateAssociatedTokenAccount, CreateMint, MintTo};
+
+use solana_clock::Clock;
+use solana_instruction::{error::InstructionError, Instruction};
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+use pinocchio_fundraiser::sdk;
+
+use crate::{anchor_ix, Failure, Outcome};
+
+const SECONDS_TO_DAYS: i64 = 86400;
+
+/// Anchor keys fundraisers by campaign; pinocchio has one per maker
+const ANCHOR_CAMPAIGN_ID: u64 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Implementation {
+    Anchor,
+    Pinocchio,
+}
+
+impl Implementation {
+    fn so_path(&self) -> PathBuf {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+        match self {
+            Implementation::Anchor => root.join("anchor-fundraiser/target/deploy/fundraiser.so"),
+            Implementation::Pinocchio => root.join(
+                "pinocchio-fundraiser-program/target/sbpf-solana-solana/release/pinocchio_fundraiser.so",
+            ),
+        }
+    }
+
+    fn program_id(&self) -> Pubkey {
+        match self {
+            Implementation::Anchor => anchor_ix::PROGRAM_ID,
+            Implementation::Pinocchio => sdk::program_id(),
+        }
+    }
+}
+
+/// One fundraiser on one implementation. Both harnesses in a scenario are
+/// driven with the same calls; only the wire format differs underneath.
+pub struct Harness {
+    pub implementation: Implementation,
+    pub svm: LiteSVM,
+    pub maker: Keypair,
+    pub mint: Pubkey,
+    pub fundraiser: Pubkey,
+    pub fundraiser_bump: u8,
+    pub vault: Pubkey,
+    /// Pinocchio needs an explicit CreateContributor the first time
+    contributors: HashSet<Pubkey>,
+}
+
+impl Harness {
+    pub fn new(implementation: Implementation) -> Self {
+        let mut svm = LiteSVM::new();
+
+        let so_path = implementation.so_path();
+        let program_data = std::fs::read(&so_path)
+            .unwrap_or_else(|_| panic!("Failed to read program SO file at {:?}", so_path));
+        svm.add_program(implementation.program_id(), &program_data)
+            .expect("Failed to add program");
+
+        // Both implementations run against p-token so CU figures are comparable
+        let p_token_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../pinocchio-fundraiser-program/src/tests/fixtures/pinocchio_token_program.so");
+        let p_token_data = std::fs::read(&p_token_so_path)
+            .unwrap_or_else(|_| panic!("p-token.so not found at {:?}", p_token_so_path));
+        svm.add_program(spl_token::ID, &p_token_data)
+            .expect("Failed to overwrite SPL token with p-token");
+
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+
+        let mint = CreateMint::new(&mut svm, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser, fundraiser_bump) = match implementation {
+            Implementation::Anchor => {
+                anchor_ix::find_fundraiser_address(&maker.pubkey(), ANCHOR_CAMPAIGN_ID)
+            }
+            Implementation::Pinocchio => sdk::find_fundraiser_address(&maker.pubkey()),
+        };
+        let vault = sdk::get_associated_token_address(&fundraiser, &mint);
+
+        Self {
+            implementation,
+            svm,
+            maker,
+            mint,
+            fundraiser,
+            fundraiser_bump,
+            vault,
+            contributors: HashSet::new(),
+        }
+    }
+
+    fn send(&mut self, ixs: &[Instruction], signer: &Keypair) -> Outcome {
+        self.svm.expire_blockhash();
+        let message = Message::new(ixs, Some(&signer.pubkey()));
+        let transaction = Transaction::new(&[signer], message, self.svm.latest_blockhash());
+
+        match self.svm.send_transaction(transaction) {
+            Ok(meta) => Outcome {
+                result: Ok(()),
+                cus: meta.compute_units_consumed,
+            },
+            Err(failed) => {
+                let failure = match failed.err {
+                    TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                        match self.implementation {
+                            Implementation::Anchor => Failure::from_anchor_code(code),
+                            Implementation::Pinocchio => Failure::from_pinocchio_code(code),
+                        }
+                    }
+                    other => Failure::Other(format!("{:?}", other)),
+                };
+                Outcome {
+                    result: Err(failure),
+                    cus: failed.meta.compute_units_consumed,
+                }
+            }
+        }
+    }
+
+    /// Funded contributor with an ATA holding `tokens`
+    pub fn new_contributor(&mut self, tokens: u64) -> Keypair {
+        let contributor = Keypair::new();
+        self.svm
+            .airdrop(&contributor.pubkey(), 2 * LAMPORTS_PER_SOL)
+            .unwrap();
+        let ata = CreateAssociatedTokenAccount::new(&mut self.svm, &contributor, &self.mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut self.svm, &self.maker, &self.mint, &ata, tokens)
+            .send()
+            .unwrap();
+        contributor
+    }
+
+    pub fn warp_days(&mut self, days: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += days * SECONDS_TO_DAYS;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn token_balance(&self, owner: &Pubkey) -> u64 {
+        let ata = sdk::get_associated_token_address(owner, &self.mint);
+        self.svm
+            .get_account(&ata)
+            .filter(|acc| acc.data.len() >= 72)
+            .map(|acc| u64::from_le_bytes(acc.data[64..72].try_into().unwrap()))
+            .unwrap_or(0)
+    }
+
+    pub fn vault_balance(&self) -> u64 {
+        self.token_balance(&self.fundraiser)
+    }
+
+    pub fn fundraiser_closed(&self) -> bool {
+        self.svm
+            .get_account(&self.fundraiser)
+            .map_or(true, |acc| acc.lamports == 0)
+    }
+
+    fn contributor_address(&self, contributor: &Pubkey) -> (Pubkey, u8) {
+        match self.implementation {
+            Implementation::Anchor => {
+                anchor_ix::find_contributor_address(&self.fundraiser, contributor)
+            }
+            Implementation::Pinocchio => {
+                sdk::find_contributor_address(&self.fundraiser, contributor)
+            }
+        }
+    }
+
+    // ─── Instructions ────────────────────────────────────────────────────
+
+    /// Anchor creates the vault itself; pinocchio expects it pre-created
+    /// by the client, which happens outside the measured transaction.
+    pub fn initialize(&mut self, amount: u64, duration: u8) -> Outcome {
+        let maker = self.maker.insecure_clone();
+        let ix = match self.implementation {
+            Implementation::Anchor => anchor_ix::initialize(
+                &maker.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &self.vault,
+                ANCHOR_CAMPAIGN_ID,
+                amount,
+                duration,
+            ),
+            Implementation::Pinocchio => {
+                CreateAssociatedTokenAccount::new(&mut self.svm, &maker, &self.mint)
+                    .owner(&self.fundraiser)
+                    .send()
+                    .unwrap();
+                let clock = self.svm.get_sysvar::<Clock>();
+                sdk::initialize(
+                    &maker.pubkey(),
+                    &self.mint,
+                    &self.fundraiser,
+                    &self.vault,
+                    self.fundraiser_bump,
+                    amount,
+                    duration,
+                    clock.unix_timestamp,
+                )
+            }
+        };
+        self.send(&[ix], &maker)
+    }
+
+    /// First contribution also creates the contributor account on both sides
+    /// (Anchor via `init_if_needed`, pinocchio via a bundled CreateContributor).
+    pub fn contribute(&mut self, contributor: &Keypair, amount: u64) -> Outcome {
+        let (contributor_account, bump) = self.contributor_address(&contributor.pubkey());
+        let contributor_ata = sdk::get_associated_token_address(&contributor.pubkey(), &self.mint);
+
+        let ixs = match self.implementation {
+            Implementation::Anchor => vec![anchor_ix::contribute(
+                &contributor.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &contributor_account,
+                &contributor_ata,
+                &self.vault,
+                amount,
+            )],
+            Implementation::Pinocchio => {
+                let clock = self.svm.get_sysvar::<Clock>();
+                let contribute_ix = sdk::contribute(
+                    &contributor.pubkey(),
+                    &self.mint,
+                    &self.fundraiser,
+                    &contributor_account,
+                    &contributor_ata,
+                    &self.vault,
+                    amount,
+                    clock.unix_timestamp,
+                );
+                if self.contributors.contains(&contributor.pubkey()) {
+                    vec![contribute_ix]
+                } else {
+                    vec![
+                        sdk::create_contributor(
+                            &contributor.pubkey(),
+                            &self.fundraiser,
+                            &contributor_account,
+                            bump,
+                        ),
+                        contribute_ix,
+                    ]
+                }
+            }
+        };
+
+        let outcome = self.send(&ixs, contributor);
+        if outcome.is_ok() {
+            self.contributors.insert(contributor.pubkey());
+        }
+        outcome
+    }
+
+    /// Anchor creates the maker ATA via `init_if_needed`; pinocchio expects
+    /// it pre-created by the client.
+    pub fn check_contributions(&mut self) -> Outcome {
+        let maker = self.maker.insecure_clone();
+        let maker_ata = sdk::get_associated_token_address(&maker.pubkey(), &self.mint);
+
+        if self.implementation == Implementation::Pinocchio
+            && self.svm.get_account(&maker_ata).is_none()
+        {
+            CreateAssociatedTokenAccount::new(&mut self.svm, &maker, &self.mint)
+                .owner(&maker.pubkey())
+                .send()
+                .unwrap();
+        }
+
+        let ix = match self.implementation {
+            Implementation::Anchor => anchor_ix::check_contributions(
+                &maker.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &self.vault,
+                &maker_ata,
+            ),
+            Implementation::Pinocchio => sdk::check_contributions(
+                &maker.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &self.vault,
+                &maker_ata,
+            ),
+        };
+        self.send(&[ix], &maker)
+    }
+
+    pub fn refund(&mut self, contributor: &Keypair) -> Outcome {
+        let (contributor_account, bump) = self.contributor_address(&contributor.pubkey());
+        let contributor_ata = sdk::get_associated_token_address(&contributor.pubkey(), &self.mint);
+
+        let ix = match self.implementation {
+            Implementation::Anchor => anchor_ix::refund(
+                &contributor.pubkey(),
+                &self.maker.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &contributor_account,
+                &contributor_ata,
+                &self.vault,
+            ),
+            Implementation::Pinocchio => sdk::refund(
+                &contributor.pubkey(),
+                &self.maker.pubkey(),
+                &self.mint,
+                &self.fundraiser,
+                &contributor_account,
+                &contributor_ata,
+                &self.vault,
+                bump,
+            ),
+        };
+        self.send(&[ix], contributor)
+    }
+}
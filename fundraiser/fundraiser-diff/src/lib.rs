@@ -0,0 +1,76 @@
+//! Differential LiteSVM harness for the two fundraiser implementations.
+//!
+//! The same scenario is driven through `anchor-fundraiser` and
+//! `pinocchio-fundraiser` via [`Harness`], and the suite in `tests` asserts
+//! both produce the same [`Outcome`]s and balances. With
+//! `FUNDRAISER_DIFF_REPORT` set it also prints CU usage per instruction side
+//! by side.
+//!
+//! Both binaries must be built first:
+//!   (cd ../anchor-fundraiser && anchor build)
+//!   (cd ../pinocchio-fundraiser-program && cargo build-sbf)
+pub mod anchor_ix;
+pub mod harness;
+
+#[cfg(test)]
+mod tests;
+
+pub use harness::{Harness, Implementation};
+
+/// Implementation-agnostic failure reason, mapped from each program's error codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    TargetNotMet,
+    TargetMet,
+    ContributionTooSmall,
+    ContributionTooBig,
+    MaximumContributionsReached,
+    FundraiserNotEnded,
+    FundraiserEnded,
+    InvalidAmount,
+    /// Anything without a counterpart in the other implementation
+    Other(String),
+}
+
+impl Failure {
+    /// `FundraiserError` variants start at Anchor's custom error offset (6000)
+    pub fn from_anchor_code(code: u32) -> Self {
+        match code {
+            6000 => Failure::TargetNotMet,
+            6001 => Failure::TargetMet,
+            6002 => Failure::ContributionTooBig,
+            6003 => Failure::ContributionTooSmall,
+            6004 => Failure::MaximumContributionsReached,
+            6005 => Failure::FundraiserNotEnded,
+            6006 => Failure::FundraiserEnded,
+            6007 => Failure::InvalidAmount,
+            other => Failure::Other(format!("anchor custom error {}", other)),
+        }
+    }
+
+    /// Raw `u32` codes from pinocchio-fundraiser's `error.rs`
+    pub fn from_pinocchio_code(code: u32) -> Self {
+        match code {
+            0x100 => Failure::TargetNotMet,
+            0x101 => Failure::TargetMet,
+            0x102 => Failure::ContributionTooBig,
+            0x104 => Failure::MaximumContributionsReached,
+            0x105 => Failure::FundraiserNotEnded,
+            0x106 => Failure::FundraiserEnded,
+            other => Failure::Other(format!("pinocchio custom error {:#x}", other)),
+        }
+    }
+}
+
+/// Result of a single instruction, plus the CUs it consumed
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub result: Result<(), Failure>,
+    pub cus: u64,
+}
+
+impl Outcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
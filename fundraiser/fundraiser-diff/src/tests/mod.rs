@@ -0,0 +1,236 @@
+#[cfg(test)]
+mod tests {
+    use solana_keypair::Keypair;
+    use solana_signer::Signer;
+
+    use crate::{Failure, Harness, Implementation, Outcome};
+
+    /// Both implementations side by side. Every step runs on each harness and
+    /// asserts the outcomes and all observable balances match before moving on.
+    struct Pair {
+        anchor: Harness,
+        pinocchio: Harness,
+        /// (anchor-side keypair, pinocchio-side keypair) per logical contributor
+        contributors: Vec<(Keypair, Keypair)>,
+        report: Vec<(String, Outcome, Outcome)>,
+    }
+
+    impl Pair {
+        fn new() -> Self {
+            Self {
+                anchor: Harness::new(Implementation::Anchor),
+                pinocchio: Harness::new(Implementation::Pinocchio),
+                contributors: Vec::new(),
+                report: Vec::new(),
+            }
+        }
+
+        fn new_contributor(&mut self, tokens: u64) -> usize {
+            let a = self.anchor.new_contributor(tokens);
+            let p = self.pinocchio.new_contributor(tokens);
+            self.contributors.push((a, p));
+            self.contributors.len() - 1
+        }
+
+        fn compare(&mut self, label: String, a: Outcome, p: Outcome) -> Result<(), Failure> {
+            assert_eq!(a.result, p.result, "{}: outcomes diverge", label);
+            assert_eq!(
+                self.anchor.vault_balance(),
+                self.pinocchio.vault_balance(),
+                "{}: vault balances diverge",
+                label
+            );
+            assert_eq!(
+                self.anchor.token_balance(&self.anchor.maker.pubkey()),
+                self.pinocchio.token_balance(&self.pinocchio.maker.pubkey()),
+                "{}: maker balances diverge",
+                label
+            );
+            for (i, (ca, cp)) in self.contributors.iter().enumerate() {
+                assert_eq!(
+                    self.anchor.token_balance(&ca.pubkey()),
+                    self.pinocchio.token_balance(&cp.pubkey()),
+                    "{}: contributor #{} balances diverge",
+                    label,
+                    i
+                );
+            }
+
+            let result = a.result.clone();
+            self.report.push((label, a, p));
+            result
+        }
+
+        fn initialize(&mut self, amount: u64, duration: u8) -> Result<(), Failure> {
+            let a = self.anchor.initialize(amount, duration);
+            let p = self.pinocchio.initialize(amount, duration);
+            self.compare("Initialize".to_string(), a, p)
+        }
+
+        fn contribute(&mut self, who: usize, amount: u64) -> Result<(), Failure> {
+            let a = self.anchor.contribute(&self.contributors[who].0, amount);
+            let p = self.pinocchio.contribute(&self.contributors[who].1, amount);
+            self.compare(format!("Contribute #{} ({})", who, amount), a, p)
+        }
+
+        fn check_contributions(&mut self) -> Result<(), Failure> {
+            let a = self.anchor.check_contributions();
+            let p = self.pinocchio.check_contributions();
+            self.compare("CheckContributions".to_string(), a, p)
+        }
+
+        fn refund(&mut self, who: usize) -> Result<(), Failure> {
+            let a = self.anchor.refund(&self.contributors[who].0);
+            let p = self.pinocchio.refund(&self.contributors[who].1);
+            self.compare(format!("Refund #{}", who), a, p)
+        }
+
+        fn warp_days(&mut self, days: i64) {
+            self.anchor.warp_days(days);
+            self.pinocchio.warp_days(days);
+        }
+
+        /// Prints the per-instruction CU table when `FUNDRAISER_DIFF_REPORT` is set
+        fn print_report(&self, scenario: &str) {
+            if std::env::var_os("FUNDRAISER_DIFF_REPORT").is_none() {
+                return;
+            }
+
+            let cell = |o: &Outcome| match &o.result {
+                Ok(()) => format!("{:>7}", o.cus),
+                Err(_) => format!("{:>7}*", o.cus),
+            };
+
+            println!("\n── {} ──", scenario);
+            println!(
+                "{:<28} │ {:>10} │ {:>10} │ {}",
+                "Instruction", "Anchor CU", "Pinocchio", "Outcome"
+            );
+            for (label, a, p) in &self.report {
+                let outcome = match &a.result {
+                    Ok(()) => "ok".to_string(),
+                    Err(f) => format!("{:?}", f),
+                };
+                println!(
+                    "{:<28} │ {:>10} │ {:>10} │ {}",
+                    label,
+                    cell(a),
+                    cell(p),
+                    outcome
+                );
+            }
+            println!("(* failed instruction)");
+        }
+    }
+
+    #[test]
+    fn diff_successful_raise() {
+        let mut pair = Pair::new();
+        pair.initialize(10_000_000, 1).unwrap();
+
+        let contributors: Vec<usize> = (0..10).map(|_| pair.new_contributor(10_000_000)).collect();
+        for who in contributors {
+            pair.contribute(who, 1_000_000).unwrap();
+        }
+        assert_eq!(pair.anchor.vault_balance(), 10_000_000);
+
+        assert_eq!(pair.check_contributions(), Err(Failure::FundraiserNotEnded));
+
+        pair.warp_days(2);
+        pair.check_contributions().unwrap();
+
+        assert_eq!(
+            pair.anchor.token_balance(&pair.anchor.maker.pubkey()),
+            10_000_000
+        );
+        assert!(pair.anchor.fundraiser_closed());
+        assert!(pair.pinocchio.fundraiser_closed());
+
+        pair.print_report("successful raise");
+    }
+
+    #[test]
+    fn diff_contribution_limits() {
+        let mut pair = Pair::new();
+        pair.initialize(10_000_000, 10).unwrap();
+        let who = pair.new_contributor(10_000_000);
+
+        // Above the 10% per-contribution cap
+        assert_eq!(
+            pair.contribute(who, 2_000_000),
+            Err(Failure::ContributionTooBig)
+        );
+
+        pair.contribute(who, 600_000).unwrap();
+
+        // Each call is within the cap, but the running total is not
+        assert_eq!(
+            pair.contribute(who, 600_000),
+            Err(Failure::MaximumContributionsReached)
+        );
+
+        pair.contribute(who, 400_000).unwrap();
+        assert_eq!(pair.anchor.vault_balance(), 1_000_000);
+
+        pair.print_report("contribution limits");
+    }
+
+    #[test]
+    fn diff_contribution_overshooting_target() {
+        let mut pair = Pair::new();
+        pair.initialize(10_000_000, 1).unwrap();
+
+        let contributors: Vec<usize> = (0..10).map(|_| pair.new_contributor(10_000_000)).collect();
+        for &who in &contributors[..9] {
+            pair.contribute(who, 1_000_000).unwrap();
+        }
+        pair.contribute(contributors[9], 600_000).unwrap();
+
+        // 600_000 more would take the raise past the target
+        let last = pair.new_contributor(10_000_000);
+        assert_eq!(pair.contribute(last, 600_000), Err(Failure::TargetMet));
+
+        pair.contribute(last, 400_000).unwrap();
+        assert_eq!(pair.anchor.vault_balance(), 10_000_000);
+
+        pair.print_report("contribution overshooting target");
+    }
+
+    #[test]
+    fn diff_contribute_after_deadline() {
+        let mut pair = Pair::new();
+        pair.initialize(10_000_000, 1).unwrap();
+        let who = pair.new_contributor(10_000_000);
+
+        pair.warp_days(1);
+        assert_eq!(pair.contribute(who, 100_000), Err(Failure::FundraiserEnded));
+        assert_eq!(pair.anchor.vault_balance(), 0);
+
+        pair.print_report("contribute after deadline");
+    }
+
+    #[test]
+    fn diff_failed_raise_refund() {
+        let mut pair = Pair::new();
+        pair.initialize(10_000_000, 1).unwrap();
+        let first = pair.new_contributor(10_000_000);
+        let second = pair.new_contributor(10_000_000);
+
+        pair.contribute(first, 1_000_000).unwrap();
+        pair.contribute(second, 500_000).unwrap();
+
+        assert_eq!(pair.refund(first), Err(Failure::FundraiserNotEnded));
+
+        pair.warp_days(2);
+        assert_eq!(pair.check_contributions(), Err(Failure::TargetNotMet));
+
+        pair.refund(first).unwrap();
+        pair.refund(second).unwrap();
+
+        assert_eq!(pair.anchor.vault_balance(), 0);
+        let (ca, _) = &pair.contributors[first];
+        assert_eq!(pair.anchor.token_balance(&ca.pubkey()), 10_000_000);
+
+        pair.print_report("failed raise + refund");
+    }
+}
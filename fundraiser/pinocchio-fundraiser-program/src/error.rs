@@ -7,6 +7,9 @@ pub const ERR_CONTRIBUTION_TOO_BIG: u32 = 0x102;
 pub const ERR_MAX_CONTRIBUTIONS_REACHED: u32 = 0x104;
 pub const ERR_FUNDRAISER_NOT_ENDED: u32 = 0x105;
 pub const ERR_FUNDRAISER_ENDED: u32 = 0x106;
+// Token failures detected before the CPI — the runtime aborts on callee errors
+pub const ERR_INSUFFICIENT_FUNDS: u32 = 0x107;
+pub const ERR_OWNER_MISMATCH: u32 = 0x108;
 
 #[inline(always)]
 pub fn err(code: u32) -> ProgramError {
@@ -0,0 +1,205 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    entrypoint::InstructionContext,
+    error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    error::*,
+    raw_cpi,
+    state::{Contributor, Fundraiser},
+    SECONDS_TO_DAYS,
+};
+
+/// ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
+const ASSOCIATED_TOKEN_PROGRAM_ID: [u8; 32] = [
+    140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131, 11, 90, 19, 153, 218,
+    255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+];
+
+/// Upper bound on contributors refunded per call — keeps the crank inside
+/// the default CU budget and the transaction, at three accounts per
+/// contributor, inside the packet size limit.
+pub const MAX_BATCH_REFUND: usize = 8;
+
+/// Permissionless crank — refunds up to `MAX_BATCH_REFUND` contributors of a
+/// failed raise in one call. No signer required; the fee payer can be anyone.
+///
+/// Accounts:
+/// 0. maker
+/// 1. mint_to_raise
+/// 2. fundraiser        (PDA, mut)
+/// 3. vault             (mut)
+/// 4. token_program
+/// 5. clock
+/// 6.. [contributor (mut), contributor_account (PDA, mut), contributor_ata (mut)] × count
+///
+/// Data: [count: u8, [contributor_bump: u8, ata_bump: u8] × count]
+///
+/// The contributor PDA and the contributor's ATA are both re-derived from the
+/// contributor wallet, so a crank cannot redirect a refund. Each contributor
+/// PDA is closed back to its wallet, as a single `Refund` does. Entries that
+/// are already closed are skipped, so re-running a batch is harmless.
+#[inline(always)]
+pub fn process_batch_refund(ctx: &mut InstructionContext, data: &[u8]) -> ProgramResult {
+    let maker = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let _mint_to_raise = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let fundraiser = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let vault = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let _token_program = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let clock = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+
+    let (count, bumps) = data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let count = *count as usize;
+
+    if count == 0 || count > MAX_BATCH_REFUND || bumps.len() < count * 2 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if (ctx.remaining() as usize) < count * 3 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let current_time = unsafe {
+        let clock_data = clock.try_borrow()?;
+        *(clock_data.as_ptr().add(32) as *const i64)
+    };
+
+    let fund_state = unsafe { Fundraiser::from_account_unchecked(&fundraiser)? };
+
+    if maker.address().as_array() != fund_state.maker().as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if unsafe { vault.owner() } != &pinocchio_token::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // validating fundraiser pda address (prevent fund draining from arbitrary accounts)
+    let expected_fundraiser_seeds: [&[u8]; 2] = [b"fundraiser", fund_state.maker().as_ref()];
+    let expected_fundraiser = pinocchio_pubkey::derive_address(
+        &expected_fundraiser_seeds,
+        Some(fund_state.bump),
+        crate::ID.as_array(),
+    );
+    if fundraiser.address().as_array() != expected_fundraiser.as_ref() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let elapsed_days = ((current_time - fund_state.time_started()) / SECONDS_TO_DAYS) as u8;
+    if elapsed_days < fund_state.duration {
+        return Err(err(ERR_FUNDRAISER_NOT_ENDED));
+    }
+
+    let mut vault_balance = {
+        let vault_data = vault.try_borrow()?;
+        let vault_mint = unsafe { *(vault_data.as_ptr() as *const [u8; 32]) };
+        let vault_owner = unsafe { *(vault_data.as_ptr().add(32) as *const [u8; 32]) };
+        if vault_mint != *fund_state.mint_to_raise()
+            || vault_owner != *fundraiser.address().as_array()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        unsafe { (vault_data.as_ptr().add(64) as *const u64).read_unaligned() }
+    };
+
+    if vault_balance >= fund_state.amount_to_raise() {
+        return Err(err(ERR_TARGET_MET));
+    }
+
+    let bump_bytes = [fund_state.bump];
+    let signer_seeds = [
+        Seed::from(b"fundraiser"),
+        Seed::from(maker.address().as_array()),
+        Seed::from(&bump_bytes),
+    ];
+
+    for entry_bumps in bumps[..count * 2].chunks_exact(2) {
+        let (contributor_bump, ata_bump) = (entry_bumps[0], entry_bumps[1]);
+        let contributor = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+        let contributor_account = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+        let contributor_ata = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+
+        // Already refunded and closed by an earlier batch or a single Refund
+        if contributor_account.lamports() == 0 {
+            continue;
+        }
+
+        if unsafe { contributor_account.owner() } != &crate::ID
+            || contributor_account.data_len() != Contributor::LEN
+        {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // validating contributor PDA address against the contributor wallet
+        let contributor_seeds: [&[u8]; 3] = [
+            b"contributor",
+            fundraiser.address().as_ref(),
+            contributor.address().as_ref(),
+        ];
+        let expected_contributor = pinocchio_pubkey::derive_address(
+            &contributor_seeds,
+            Some(contributor_bump),
+            crate::ID.as_array(),
+        );
+        if contributor_account.address().as_array() != expected_contributor.as_ref() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // validating the ATA address: [wallet, token_program, mint] under the ATA program
+        let ata_seeds: [&[u8]; 3] = [
+            contributor.address().as_ref(),
+            pinocchio_token::ID.as_ref(),
+            fund_state.mint_to_raise().as_ref(),
+        ];
+        let expected_ata = pinocchio_pubkey::derive_address(
+            &ata_seeds,
+            Some(ata_bump),
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        if contributor_ata.address().as_array() != expected_ata.as_ref() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // The ATA's owner can be reassigned, so it must still be the contributor
+        crate::check_token_account(&contributor_ata)?;
+        {
+            let ata_data = contributor_ata.try_borrow()?;
+            let ata_owner = unsafe { *(ata_data.as_ptr().add(32) as *const [u8; 32]) };
+            if ata_owner != *contributor.address().as_array() {
+                return Err(err(ERR_OWNER_MISMATCH));
+            }
+        }
+
+        let cont_state = unsafe { Contributor::from_account_unchecked(&contributor_account)? };
+        let refund_amount = cont_state.amount();
+
+        if refund_amount > 0 {
+            if vault_balance < refund_amount {
+                return Err(err(ERR_INSUFFICIENT_FUNDS));
+            }
+
+            raw_cpi::raw_transfer_signed(
+                &vault,
+                &contributor_ata,
+                &fundraiser,
+                refund_amount,
+                &[Signer::from(&signer_seeds)],
+            )?;
+
+            vault_balance -= refund_amount;
+            fund_state.set_current_amount(fund_state.current_amount() - refund_amount);
+        }
+
+        // Close contributor PDA back to the contributor
+        let cont_lamports = contributor_account.lamports();
+        contributor.set_lamports(contributor.lamports() + cont_lamports);
+        contributor_account.set_lamports(0);
+        contributor_account.close()?;
+    }
+
+    Ok(())
+}
@@ -6,7 +6,7 @@ use pinocchio::{
 };
 
 use crate::{
-    error::{err, ERR_FUNDRAISER_NOT_ENDED, ERR_TARGET_NOT_MET},
+    error::{err, ERR_FUNDRAISER_NOT_ENDED, ERR_OWNER_MISMATCH, ERR_TARGET_NOT_MET},
     raw_cpi,
     state::Fundraiser,
     SECONDS_TO_DAYS,
@@ -83,6 +83,15 @@ pub fn process_check_contributions(ctx: &mut InstructionContext, _data: &[u8]) -
         return Err(err(ERR_TARGET_NOT_MET));
     }
 
+    crate::check_token_account(&maker_ata)?;
+    {
+        let ata_data = maker_ata.try_borrow()?;
+        let ata_owner = unsafe { *(ata_data.as_ptr().add(32) as *const [u8; 32]) };
+        if ata_owner != *maker.address().as_array() {
+            return Err(err(ERR_OWNER_MISMATCH));
+        }
+    }
+
     // Build PDA signer
     let bump_bytes = [fund_state.bump];
     let signer_seeds = [
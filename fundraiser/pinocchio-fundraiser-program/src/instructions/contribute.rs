@@ -103,7 +103,7 @@ pub fn process_contribute(ctx: &mut InstructionContext, data: &[u8]) -> ProgramR
         .ok_or(ProgramError::InvalidArgument)?;
 
     if new_current_amount > fund_state.amount_to_raise() {
-        return Err(ProgramError::InvalidArgument);
+        return Err(err(ERR_TARGET_MET));
     }
 
     let elapsed_days = ((current_time - fund_state.time_started()) / SECONDS_TO_DAYS) as u8;
@@ -123,6 +123,21 @@ pub fn process_contribute(ctx: &mut InstructionContext, data: &[u8]) -> ProgramR
         return Err(err(ERR_MAX_CONTRIBUTIONS_REACHED));
     }
 
+    // Map token failures to our own codes before the CPI (runtime aborts on callee errors)
+    crate::check_token_account(&contributor_ata)?;
+    {
+        let ata_data = contributor_ata.try_borrow()?;
+        let ata_owner = unsafe { *(ata_data.as_ptr().add(32) as *const [u8; 32]) };
+        let ata_balance = unsafe { (ata_data.as_ptr().add(64) as *const u64).read_unaligned() };
+
+        if ata_owner != *contributor.address().as_array() {
+            return Err(err(ERR_OWNER_MISMATCH));
+        }
+        if ata_balance < amount {
+            return Err(err(ERR_INSUFFICIENT_FUNDS));
+        }
+    }
+
     // Raw CPI transfer — bypasses pinocchio_token borrow checks
     raw_cpi::raw_transfer(contributor_ata, vault, contributor, amount)?;
     fund_state.set_current_amount(new_current_amount);
@@ -0,0 +1,80 @@
+use pinocchio::{entrypoint::InstructionContext, error::ProgramError, ProgramResult};
+
+use crate::raw_cpi;
+
+/// Bench-only instruction (feature = "bench") — runs a single token CPI
+/// through either `raw_cpi` or the `pinocchio_token` equivalent so LiteSVM
+/// tests can compare their CU cost side by side. Never shipped on-chain.
+///
+/// Accounts:
+/// 0. authority      (signer, mut)
+/// 1. mint           (mut)
+/// 2. account_a      (mut)
+/// 3. account_b      (mut)
+/// 4. token_program
+///
+/// Data: [op: u8, mode: u8, amount: u64, decimals: u8] = 11 bytes
+///   op:   0 = TransferChecked(a -> b), 1 = MintTo(a), 2 = CloseAccount(a -> authority),
+///         3 = InitializeAccount3(a, owner = authority)
+///   mode: 0 = raw_cpi (targets the passed token_program), 1 = pinocchio_token
+pub fn process_cpi_bench(ctx: &mut InstructionContext, data: &[u8]) -> ProgramResult {
+    let authority = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let mint = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let account_a = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let account_b = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+    let token_program = crate::take_account(unsafe { ctx.next_account_unchecked() })?;
+
+    if data.len() < 11 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let ptr = data.as_ptr();
+    let (op, mode, amount, decimals) = unsafe {
+        (
+            *ptr,
+            *ptr.add(1),
+            (ptr.add(2) as *const u64).read_unaligned(),
+            *ptr.add(10),
+        )
+    };
+
+    let program = token_program.address();
+
+    match (op, mode) {
+        (0, 0) => raw_cpi::raw_transfer_checked(
+            program, &account_a, &mint, &account_b, &authority, amount, decimals,
+        ),
+        (0, 1) => pinocchio_token::instructions::TransferChecked {
+            from: &account_a,
+            mint: &mint,
+            to: &account_b,
+            authority: &authority,
+            amount,
+            decimals,
+        }
+        .invoke(),
+        (1, 0) => raw_cpi::raw_mint_to(program, &mint, &account_a, &authority, amount),
+        (1, 1) => pinocchio_token::instructions::MintTo {
+            mint: &mint,
+            account: &account_a,
+            mint_authority: &authority,
+            amount,
+        }
+        .invoke(),
+        (2, 0) => raw_cpi::raw_close_account(program, &account_a, &authority, &authority),
+        (2, 1) => pinocchio_token::instructions::CloseAccount {
+            account: &account_a,
+            destination: &authority,
+            authority: &authority,
+        }
+        .invoke(),
+        (3, 0) => raw_cpi::raw_initialize_account3(program, &account_a, &mint, authority.address()),
+        (3, 1) => pinocchio_token::instructions::InitializeAccount3 {
+            account: &account_a,
+            mint: &mint,
+            owner: authority.address(),
+        }
+        .invoke(),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
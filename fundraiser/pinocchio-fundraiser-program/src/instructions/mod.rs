@@ -1,15 +1,23 @@
+pub mod batch_refund;
 pub mod check_contributions;
 pub mod contribute;
 pub mod create_contributor;
 pub mod initialize;
 pub mod refund;
 
+#[cfg(feature = "bench")]
+pub mod cpi_bench;
+
+pub use batch_refund::*;
 pub use check_contributions::*;
 pub use contribute::*;
 pub use create_contributor::*;
 pub use initialize::*;
 pub use refund::*;
 
+#[cfg(feature = "bench")]
+pub use cpi_bench::*;
+
 use pinocchio::error::ProgramError;
 
 /// 1-byte instruction discriminator
@@ -19,6 +27,9 @@ pub enum FundraiserInstruction {
     CheckContributions = 2,
     Refund = 3,
     CreateContributor = 4,
+    BatchRefund = 5,
+    #[cfg(feature = "bench")]
+    CpiBench = 0xFE,
 }
 
 impl TryFrom<&u8> for FundraiserInstruction {
@@ -32,6 +43,9 @@ impl TryFrom<&u8> for FundraiserInstruction {
             2 => Ok(FundraiserInstruction::CheckContributions),
             3 => Ok(FundraiserInstruction::Refund),
             4 => Ok(FundraiserInstruction::CreateContributor),
+            5 => Ok(FundraiserInstruction::BatchRefund),
+            #[cfg(feature = "bench")]
+            0xFE => Ok(FundraiserInstruction::CpiBench),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
@@ -115,6 +115,19 @@ pub fn process_refund(ctx: &mut InstructionContext, data: &[u8]) -> ProgramResul
     let cont_state = unsafe { Contributor::from_account_unchecked(contributor_account)? };
     let refund_amount = cont_state.amount();
 
+    if vault_balance < refund_amount {
+        return Err(err(ERR_INSUFFICIENT_FUNDS));
+    }
+
+    crate::check_token_account(&contributor_ata)?;
+    {
+        let ata_data = contributor_ata.try_borrow()?;
+        let ata_owner = unsafe { *(ata_data.as_ptr().add(32) as *const [u8; 32]) };
+        if ata_owner != *contributor.address().as_array() {
+            return Err(err(ERR_OWNER_MISMATCH));
+        }
+    }
+
     // Build PDA signer
     let bump_bytes = [fund_state.bump];
     let signer_seeds = [
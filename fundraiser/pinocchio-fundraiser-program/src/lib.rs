@@ -11,6 +11,9 @@ mod instructions;
 pub mod raw_cpi;
 mod state;
 
+#[cfg(any(test, feature = "sdk"))]
+pub mod sdk;
+
 #[cfg(test)]
 mod tests;
 
@@ -44,6 +47,21 @@ pub fn take_account(maybe: MaybeAccount) -> Result<AccountView, ProgramError> {
     }
 }
 
+// Token account layout: mint 0..32, owner 32..64, amount 64..72
+pub const TOKEN_ACCOUNT_READ_LEN: usize = 72;
+
+/// Check that a token account is owned by the token program and long enough
+/// to read its mint, owner and amount before those bytes are trusted.
+#[inline(always)]
+pub fn check_token_account(account: &AccountView) -> ProgramResult {
+    if unsafe { account.owner() } != &pinocchio_token::ID
+        || account.data_len() < TOKEN_ACCOUNT_READ_LEN
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
 #[inline(always)]
 pub fn process_instruction(mut context: InstructionContext) -> ProgramResult {
     let mut context_clone = unsafe { core::ptr::read(&context as *const InstructionContext) };
@@ -73,5 +91,12 @@ pub fn process_instruction(mut context: InstructionContext) -> ProgramResult {
         FundraiserInstruction::CreateContributor => {
             instructions::process_create_contributor(&mut context, data_slice)
         }
+        FundraiserInstruction::BatchRefund => {
+            instructions::process_batch_refund(&mut context, data_slice)
+        }
+        #[cfg(feature = "bench")]
+        FundraiserInstruction::CpiBench => {
+            instructions::process_cpi_bench(&mut context, data_slice)
+        }
     }
 }
@@ -5,18 +5,19 @@
 ///! program address is needed — the Solana runtime uses p-token's optimized
 ///! implementation internally.
 ///!
-///! Uses invoke_signed_unchecked to skip per-account borrow validation,
-///! going directly to the sol_invoke_signed_c syscall for maximum CU savings.
+///! Helpers other than Transfer take the target program explicitly, so the
+///! same code path serves legacy SPL Token / p-token and Token-2022.
+///!
+///! Calls the sol_invoke_signed_c syscall directly to skip per-account
+///! borrow validation, and surfaces its return code as a ProgramResult.
 use core::mem::MaybeUninit;
 use core::slice::from_raw_parts;
 
 use pinocchio::{
     address::Address,
     cpi::Signer,
-    instruction::{
-        cpi::{invoke_signed_unchecked, CpiAccount},
-        InstructionAccount, InstructionView,
-    },
+    error::ProgramError,
+    instruction::{cpi::CpiAccount, InstructionAccount},
     AccountView, ProgramResult,
 };
 
@@ -27,8 +28,83 @@ const TOKEN_ID: [u8; 32] = [
     95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
 ];
 
+/// Token-2022 program ID
+/// TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+const TOKEN_2022_ID: [u8; 32] = [
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252, 77,
+    131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+];
+
+/// Target for the legacy SPL Token / p-token program
+pub const TOKEN_PROGRAM_ID: Address = Address::new_from_array(TOKEN_ID);
+
+/// Target for the Token-2022 program
+pub const TOKEN_2022_PROGRAM_ID: Address = Address::new_from_array(TOKEN_2022_ID);
+
+// SPL Token instruction discriminators (shared by Token-2022)
+const IX_TRANSFER: u8 = 3;
+const IX_MINT_TO: u8 = 7;
+const IX_CLOSE_ACCOUNT: u8 = 9;
+const IX_TRANSFER_CHECKED: u8 = 12;
+const IX_INITIALIZE_ACCOUNT_3: u8 = 18;
+
 const UNINIT_BYTE: MaybeUninit<u8> = MaybeUninit::<u8>::uninit();
 
+/// C-ABI instruction layout expected by sol_invoke_signed_c
+#[repr(C)]
+struct RawInstruction {
+    program_id: *const Address,
+    accounts: *const InstructionAccount<'static>,
+    accounts_len: u64,
+    data: *const u8,
+    data_len: u64,
+}
+
+/// Dispatch a token instruction straight to the CPI syscall — skips all
+/// borrow validation. Every helper below funnels through here.
+///
+/// A non-zero syscall return is mapped to a ProgramError. Note the runtime
+/// aborts the whole transaction when the callee itself fails, so callers
+/// that want their own error codes must validate before invoking.
+#[inline(always)]
+fn invoke_token<const N: usize>(
+    token_program: &Address,
+    instruction_accounts: &[InstructionAccount; N],
+    cpi_accounts: &[CpiAccount; N],
+    data: &[u8],
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction = RawInstruction {
+        program_id: token_program as *const Address,
+        accounts: instruction_accounts.as_ptr() as *const InstructionAccount<'static>,
+        accounts_len: N as u64,
+        data: data.as_ptr(),
+        data_len: data.len() as u64,
+    };
+
+    #[cfg(target_os = "solana")]
+    let result = unsafe {
+        pinocchio::syscalls::sol_invoke_signed_c(
+            &instruction as *const RawInstruction as *const u8,
+            cpi_accounts.as_ptr() as *const u8,
+            N as u64,
+            signers.as_ptr() as *const u8,
+            signers.len() as u64,
+        )
+    };
+
+    #[cfg(not(target_os = "solana"))]
+    let result = {
+        core::hint::black_box((&instruction, cpi_accounts, signers));
+        0u64
+    };
+
+    match result {
+        0 => Ok(()),
+        code => Err(ProgramError::from(code)),
+    }
+}
+
 /// Token Transfer CPI (no PDA signer) — skips all borrow validation.
 ///
 /// Wire format: [discriminator(1) = 3 | amount(8 LE)] = 9 bytes
@@ -63,25 +139,225 @@ pub fn raw_transfer_signed(
     // [discriminator(1) | amount(8)] = 9 bytes
     let mut data = [UNINIT_BYTE; 9];
     unsafe {
-        (data.as_mut_ptr() as *mut u8).write(3u8); // Transfer discriminator = 3
+        (data.as_mut_ptr() as *mut u8).write(IX_TRANSFER);
         ((data.as_mut_ptr() as *mut u8).add(1) as *mut u64).write_unaligned(amount);
     }
 
-    let instruction = InstructionView {
-        program_id: unsafe { &*(&TOKEN_ID as *const [u8; 32] as *const Address) },
-        accounts: &instruction_accounts,
-        data: unsafe { from_raw_parts(data.as_ptr() as _, 9) },
-    };
-
     let cpi_accounts: [CpiAccount; 3] = [
         CpiAccount::from(from),
         CpiAccount::from(to),
         CpiAccount::from(authority),
     ];
 
+    invoke_token(
+        &TOKEN_PROGRAM_ID,
+        &instruction_accounts,
+        &cpi_accounts,
+        unsafe { from_raw_parts(data.as_ptr() as _, 9) },
+        signers,
+    )
+}
+
+/// TransferChecked CPI (no PDA signer).
+///
+/// Wire format: [discriminator(1) = 12 | amount(8 LE) | decimals(1)] = 10 bytes
+#[inline(always)]
+pub fn raw_transfer_checked(
+    token_program: &Address,
+    from: &AccountView,
+    mint: &AccountView,
+    to: &AccountView,
+    authority: &AccountView,
+    amount: u64,
+    decimals: u8,
+) -> ProgramResult {
+    raw_transfer_checked_signed(
+        token_program,
+        from,
+        mint,
+        to,
+        authority,
+        amount,
+        decimals,
+        &[],
+    )
+}
+
+/// TransferChecked CPI with PDA signer. Required for Token-2022 mints
+/// carrying extensions (transfer fees, hooks) which reject plain Transfer.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub fn raw_transfer_checked_signed(
+    token_program: &Address,
+    from: &AccountView,
+    mint: &AccountView,
+    to: &AccountView,
+    authority: &AccountView,
+    amount: u64,
+    decimals: u8,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts: [InstructionAccount; 4] = [
+        InstructionAccount::writable(from.address()),
+        InstructionAccount::readonly(mint.address()),
+        InstructionAccount::writable(to.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    // [discriminator(1) | amount(8) | decimals(1)] = 10 bytes
+    let mut data = [UNINIT_BYTE; 10];
     unsafe {
-        invoke_signed_unchecked(&instruction, &cpi_accounts, signers);
+        (data.as_mut_ptr() as *mut u8).write(IX_TRANSFER_CHECKED);
+        ((data.as_mut_ptr() as *mut u8).add(1) as *mut u64).write_unaligned(amount);
+        (data.as_mut_ptr() as *mut u8).add(9).write(decimals);
     }
 
-    Ok(())
+    let cpi_accounts: [CpiAccount; 4] = [
+        CpiAccount::from(from),
+        CpiAccount::from(mint),
+        CpiAccount::from(to),
+        CpiAccount::from(authority),
+    ];
+
+    invoke_token(
+        token_program,
+        &instruction_accounts,
+        &cpi_accounts,
+        unsafe { from_raw_parts(data.as_ptr() as _, 10) },
+        signers,
+    )
+}
+
+/// CloseAccount CPI (no PDA signer).
+///
+/// Wire format: [discriminator(1) = 9] = 1 byte
+#[inline(always)]
+pub fn raw_close_account(
+    token_program: &Address,
+    account: &AccountView,
+    destination: &AccountView,
+    authority: &AccountView,
+) -> ProgramResult {
+    raw_close_account_signed(token_program, account, destination, authority, &[])
+}
+
+/// CloseAccount CPI with PDA signer — returns the token account rent to
+/// `destination`. The account balance must already be zero.
+#[inline(always)]
+pub fn raw_close_account_signed(
+    token_program: &Address,
+    account: &AccountView,
+    destination: &AccountView,
+    authority: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts: [InstructionAccount; 3] = [
+        InstructionAccount::writable(account.address()),
+        InstructionAccount::writable(destination.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+
+    let cpi_accounts: [CpiAccount; 3] = [
+        CpiAccount::from(account),
+        CpiAccount::from(destination),
+        CpiAccount::from(authority),
+    ];
+
+    invoke_token(
+        token_program,
+        &instruction_accounts,
+        &cpi_accounts,
+        &[IX_CLOSE_ACCOUNT],
+        signers,
+    )
+}
+
+/// InitializeAccount3 CPI — no rent sysvar account, owner passed as data.
+/// `account` must already be allocated and assigned to `token_program`.
+///
+/// Wire format: [discriminator(1) = 18 | owner(32)] = 33 bytes
+#[inline(always)]
+pub fn raw_initialize_account3(
+    token_program: &Address,
+    account: &AccountView,
+    mint: &AccountView,
+    owner: &Address,
+) -> ProgramResult {
+    let instruction_accounts: [InstructionAccount; 2] = [
+        InstructionAccount::writable(account.address()),
+        InstructionAccount::readonly(mint.address()),
+    ];
+
+    // [discriminator(1) | owner(32)] = 33 bytes
+    let mut data = [UNINIT_BYTE; 33];
+    unsafe {
+        (data.as_mut_ptr() as *mut u8).write(IX_INITIALIZE_ACCOUNT_3);
+        core::ptr::copy_nonoverlapping(
+            owner.as_array().as_ptr(),
+            (data.as_mut_ptr() as *mut u8).add(1),
+            32,
+        );
+    }
+
+    let cpi_accounts: [CpiAccount; 2] = [CpiAccount::from(account), CpiAccount::from(mint)];
+
+    invoke_token(
+        token_program,
+        &instruction_accounts,
+        &cpi_accounts,
+        unsafe { from_raw_parts(data.as_ptr() as _, 33) },
+        &[],
+    )
+}
+
+/// MintTo CPI (no PDA signer).
+///
+/// Wire format: [discriminator(1) = 7 | amount(8 LE)] = 9 bytes
+#[inline(always)]
+pub fn raw_mint_to(
+    token_program: &Address,
+    mint: &AccountView,
+    account: &AccountView,
+    mint_authority: &AccountView,
+    amount: u64,
+) -> ProgramResult {
+    raw_mint_to_signed(token_program, mint, account, mint_authority, amount, &[])
+}
+
+/// MintTo CPI with PDA signer — for mints whose authority is a program PDA.
+#[inline(always)]
+pub fn raw_mint_to_signed(
+    token_program: &Address,
+    mint: &AccountView,
+    account: &AccountView,
+    mint_authority: &AccountView,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction_accounts: [InstructionAccount; 3] = [
+        InstructionAccount::writable(mint.address()),
+        InstructionAccount::writable(account.address()),
+        InstructionAccount::readonly_signer(mint_authority.address()),
+    ];
+
+    // [discriminator(1) | amount(8)] = 9 bytes
+    let mut data = [UNINIT_BYTE; 9];
+    unsafe {
+        (data.as_mut_ptr() as *mut u8).write(IX_MINT_TO);
+        ((data.as_mut_ptr() as *mut u8).add(1) as *mut u64).write_unaligned(amount);
+    }
+
+    let cpi_accounts: [CpiAccount; 3] = [
+        CpiAccount::from(mint),
+        CpiAccount::from(account),
+        CpiAccount::from(mint_authority),
+    ];
+
+    invoke_token(
+        token_program,
+        &instruction_accounts,
+        &cpi_accounts,
+        unsafe { from_raw_parts(data.as_ptr() as _, 9) },
+        signers,
+    )
 }
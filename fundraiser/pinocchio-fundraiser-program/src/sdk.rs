@@ -0,0 +1,318 @@
+//! Host-side SDK — typed instruction builders, PDA/ATA derivation and
+//! account decoders for clients of the fundraiser program.
+//!
+//! std-only: compiled for `cargo test` and behind the `sdk` feature, never
+//! into the on-chain binary.
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::instructions::FundraiserInstruction;
+use crate::state::{Contributor, Fundraiser};
+
+/// SPL Token / p-token program ID
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// Associated Token Account program ID
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+
+pub const CLOCK_SYSVAR_ID: Pubkey =
+    Pubkey::from_str_const("SysvarC1ock11111111111111111111111111111111");
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(*crate::ID.as_array())
+}
+
+// ─── PDA / ATA Derivation ────────────────────────────────────────────
+
+/// Seeds: ["fundraiser", maker]
+pub fn find_fundraiser_address(maker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fundraiser".as_ref(), maker.as_ref()], &program_id())
+}
+
+/// Seeds: ["contributor", fundraiser, contributor]
+pub fn find_contributor_address(fundraiser: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"contributor".as_ref(),
+            fundraiser.as_ref(),
+            contributor.as_ref(),
+        ],
+        &program_id(),
+    )
+}
+
+/// ATA of `owner` for `mint` under the SPL Token program
+pub fn get_associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &TOKEN_PROGRAM_ID)
+}
+
+/// ATA of `owner` for `mint` under an explicit token program (legacy or Token-2022)
+pub fn get_associated_token_address_with_program_id(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Vault = ATA of the fundraiser PDA for the mint to raise
+pub fn find_vault_address(fundraiser: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(fundraiser, mint)
+}
+
+// ─── Instruction Builders ────────────────────────────────────────────
+
+/// Data: [disc(1), bump(1), amount(8), duration(1), timestamp(8)] = 19 bytes
+#[allow(clippy::too_many_arguments)]
+pub fn initialize(
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    vault: &Pubkey,
+    bump: u8,
+    amount: u64,
+    duration: u8,
+    timestamp: i64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(19);
+    data.push(FundraiserInstruction::Initialize as u8);
+    data.push(bump);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(duration);
+    data.extend_from_slice(&timestamp.to_le_bytes());
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new_readonly(*vault, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Data: [disc(1), bump(1)] = 2 bytes
+pub fn create_contributor(
+    contributor: &Pubkey,
+    fundraiser: &Pubkey,
+    contributor_account: &Pubkey,
+    bump: u8,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*fundraiser, false),
+            AccountMeta::new(*contributor_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: vec![FundraiserInstruction::CreateContributor as u8, bump],
+    }
+}
+
+/// Data: [disc(1), amount(8), timestamp(8)] = 17 bytes
+/// Contributor PDA must already exist (see [`create_contributor`])
+#[allow(clippy::too_many_arguments)]
+pub fn contribute(
+    contributor: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    contributor_account: &Pubkey,
+    contributor_ata: &Pubkey,
+    vault: &Pubkey,
+    amount: u64,
+    timestamp: i64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(17);
+    data.push(FundraiserInstruction::Contribute as u8);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&timestamp.to_le_bytes());
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*contributor_account, false),
+            AccountMeta::new(*contributor_ata, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Data: [disc(1)] = 1 byte
+pub fn check_contributions(
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    vault: &Pubkey,
+    maker_ata: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(*maker_ata, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+        ],
+        data: vec![FundraiserInstruction::CheckContributions as u8],
+    }
+}
+
+/// Data: [disc(1), contributor_bump(1)] = 2 bytes
+#[allow(clippy::too_many_arguments)]
+pub fn refund(
+    contributor: &Pubkey,
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    contributor_account: &Pubkey,
+    contributor_ata: &Pubkey,
+    vault: &Pubkey,
+    contributor_bump: u8,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*maker, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*fundraiser, false),
+            AccountMeta::new(*contributor_account, false),
+            AccountMeta::new(*contributor_ata, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+        ],
+        data: vec![FundraiserInstruction::Refund as u8, contributor_bump],
+    }
+}
+
+/// Permissionless refund crank for a failed raise.
+/// Data: [disc(1), count(1), [contributor_bump, ata_bump] × count]
+/// Remaining accounts: [contributor, contributor_account, contributor_ata] per contributor wallet
+pub fn batch_refund(
+    maker: &Pubkey,
+    mint: &Pubkey,
+    fundraiser: &Pubkey,
+    vault: &Pubkey,
+    contributors: &[Pubkey],
+) -> Instruction {
+    let mut data = Vec::with_capacity(2 + 2 * contributors.len());
+    data.push(FundraiserInstruction::BatchRefund as u8);
+    data.push(contributors.len() as u8);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*maker, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*fundraiser, false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(CLOCK_SYSVAR_ID, false),
+    ];
+
+    for contributor in contributors {
+        let (contributor_account, bump) = find_contributor_address(fundraiser, contributor);
+        let (contributor_ata, ata_bump) = Pubkey::find_program_address(
+            &[
+                contributor.as_ref(),
+                TOKEN_PROGRAM_ID.as_ref(),
+                mint.as_ref(),
+            ],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        data.push(bump);
+        data.push(ata_bump);
+        accounts.push(AccountMeta::new(*contributor, false));
+        accounts.push(AccountMeta::new(contributor_account, false));
+        accounts.push(AccountMeta::new(contributor_ata, false));
+    }
+
+    Instruction {
+        program_id: program_id(),
+        accounts,
+        data,
+    }
+}
+
+// ─── Account Decoders ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidLength,
+    InvalidDiscriminator,
+}
+
+/// Owned view of a `Fundraiser` account (91 bytes, disc 0xF0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FundraiserAccount {
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount_to_raise: u64,
+    pub current_amount: u64,
+    pub time_started: i64,
+    pub duration: u8,
+    pub bump: u8,
+}
+
+impl FundraiserAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        if data.len() != Fundraiser::LEN {
+            return Err(DecodeError::InvalidLength);
+        }
+        if data[0] != Fundraiser::DISC {
+            return Err(DecodeError::InvalidDiscriminator);
+        }
+
+        Ok(Self {
+            maker: Pubkey::new_from_array(data[1..33].try_into().unwrap()),
+            mint_to_raise: Pubkey::new_from_array(data[33..65].try_into().unwrap()),
+            amount_to_raise: u64::from_le_bytes(data[65..73].try_into().unwrap()),
+            current_amount: u64::from_le_bytes(data[73..81].try_into().unwrap()),
+            time_started: i64::from_le_bytes(data[81..89].try_into().unwrap()),
+            duration: data[89],
+            bump: data[90],
+        })
+    }
+}
+
+/// Owned view of a `Contributor` account (9 bytes, disc 0xC0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContributorAccount {
+    pub amount: u64,
+}
+
+impl ContributorAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        if data.len() != Contributor::LEN {
+            return Err(DecodeError::InvalidLength);
+        }
+        if data[0] != Contributor::DISC {
+            return Err(DecodeError::InvalidDiscriminator);
+        }
+
+        Ok(Self {
+            amount: u64::from_le_bytes(data[1..9].try_into().unwrap()),
+        })
+    }
+}
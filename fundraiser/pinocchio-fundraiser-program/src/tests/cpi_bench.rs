@@ -0,0 +1,336 @@
+//! raw_cpi vs pinocchio_token CU comparison.
+//!
+//! Requires the program built with the bench instruction:
+//!   cargo build-sbf --features bench && cargo test --features bench -- --nocapture
+#[cfg(all(test, feature = "bench"))]
+mod cpi_bench {
+    use std::path::PathBuf;
+
+    use litesvm::LiteSVM;
+    use litesvm_token::{spl_token, CreateAssociatedTokenAccount, CreateMint, MintTo};
+
+    use solana_account::Account;
+    use solana_instruction::{AccountMeta, Instruction};
+    use solana_keypair::Keypair;
+    use solana_message::Message;
+    use solana_native_token::LAMPORTS_PER_SOL;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+    use solana_transaction::Transaction;
+
+    use crate::sdk;
+
+    const OP_TRANSFER_CHECKED: u8 = 0;
+    const OP_MINT_TO: u8 = 1;
+    const OP_CLOSE_ACCOUNT: u8 = 2;
+    const OP_INITIALIZE_ACCOUNT_3: u8 = 3;
+
+    const MODE_RAW: u8 = 0;
+    const MODE_PINOCCHIO_TOKEN: u8 = 1;
+
+    const TOKEN_ACCOUNT_LEN: usize = 165;
+
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut svm = LiteSVM::new();
+        let authority = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Airdrop failed");
+
+        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("target/sbpf-solana-solana/release/pinocchio_fundraiser.so");
+        let program_data = std::fs::read(&so_path).unwrap_or_else(|_| {
+            panic!(
+                "Failed to read program SO file at {:?}. Run `cargo build-sbf --features bench` first.",
+                so_path
+            )
+        });
+        svm.add_program(sdk::program_id(), &program_data)
+            .expect("Failed to add program");
+
+        let p_token_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/tests/fixtures/pinocchio_token_program.so");
+        let p_token_data = std::fs::read(&p_token_so_path).unwrap_or_else(|_| {
+            panic!(
+                "p-token.so not found at {:?}. Please add the binary.",
+                p_token_so_path
+            )
+        });
+        svm.add_program(spl_token::ID, &p_token_data)
+            .expect("Failed to overwrite SPL token with p-token");
+
+        (svm, authority)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_bench_ix(
+        authority: &Pubkey,
+        mint: &Pubkey,
+        account_a: &Pubkey,
+        account_b: &Pubkey,
+        token_program: &Pubkey,
+        op: u8,
+        mode: u8,
+        amount: u64,
+        decimals: u8,
+    ) -> Instruction {
+        let mut data = vec![0xFE, op, mode];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(decimals);
+
+        Instruction {
+            program_id: sdk::program_id(),
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(*account_a, false),
+                AccountMeta::new(*account_b, false),
+                AccountMeta::new_readonly(*token_program, false),
+            ],
+            data,
+        }
+    }
+
+    fn send_ix(svm: &mut LiteSVM, ix: Instruction, signer: &Keypair) -> u64 {
+        let message = Message::new(&[ix], Some(&signer.pubkey()));
+        let transaction = Transaction::new(&[signer], message, svm.latest_blockhash());
+        let tx = svm.send_transaction(transaction).unwrap();
+        tx.compute_units_consumed
+    }
+
+    fn read_token_balance(svm: &LiteSVM, ata: &Pubkey) -> u64 {
+        let acc = svm.get_account(ata).expect("ATA should exist");
+        u64::from_le_bytes(acc.data[64..72].try_into().unwrap())
+    }
+
+    fn create_mint(svm: &mut LiteSVM, authority: &Keypair, token_program: &Pubkey) -> Pubkey {
+        CreateMint::new(svm, authority)
+            .decimals(6)
+            .authority(&authority.pubkey())
+            .token_program_id(token_program)
+            .send()
+            .unwrap()
+    }
+
+    fn create_ata(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        CreateAssociatedTokenAccount::new(svm, payer, mint)
+            .owner(owner)
+            .token_program_id(token_program)
+            .send()
+            .unwrap()
+    }
+
+    /// Runs one TransferChecked through the bench ix and returns its CU
+    fn bench_transfer_checked(mode: u8, token_program: &Pubkey) -> u64 {
+        let (mut svm, authority) = setup();
+        let mint = create_mint(&mut svm, &authority, token_program);
+        let from = create_ata(
+            &mut svm,
+            &authority,
+            &mint,
+            &authority.pubkey(),
+            token_program,
+        );
+        let to = create_ata(
+            &mut svm,
+            &authority,
+            &mint,
+            &Pubkey::new_unique(),
+            token_program,
+        );
+        MintTo::new(&mut svm, &authority, &mint, &from, 1_000)
+            .token_program_id(token_program)
+            .send()
+            .unwrap();
+
+        let ix = build_bench_ix(
+            &authority.pubkey(),
+            &mint,
+            &from,
+            &to,
+            token_program,
+            OP_TRANSFER_CHECKED,
+            mode,
+            400,
+            6,
+        );
+        let cus = send_ix(&mut svm, ix, &authority);
+
+        assert_eq!(read_token_balance(&svm, &from), 600);
+        assert_eq!(read_token_balance(&svm, &to), 400);
+        cus
+    }
+
+    fn bench_mint_to(mode: u8, token_program: &Pubkey) -> u64 {
+        let (mut svm, authority) = setup();
+        let mint = create_mint(&mut svm, &authority, token_program);
+        let ata = create_ata(
+            &mut svm,
+            &authority,
+            &mint,
+            &authority.pubkey(),
+            token_program,
+        );
+
+        let ix = build_bench_ix(
+            &authority.pubkey(),
+            &mint,
+            &ata,
+            &Pubkey::new_unique(),
+            token_program,
+            OP_MINT_TO,
+            mode,
+            1_000,
+            0,
+        );
+        let cus = send_ix(&mut svm, ix, &authority);
+
+        assert_eq!(read_token_balance(&svm, &ata), 1_000);
+        cus
+    }
+
+    fn bench_close_account(mode: u8, token_program: &Pubkey) -> u64 {
+        let (mut svm, authority) = setup();
+        let mint = create_mint(&mut svm, &authority, token_program);
+        let ata = create_ata(
+            &mut svm,
+            &authority,
+            &mint,
+            &authority.pubkey(),
+            token_program,
+        );
+
+        let ix = build_bench_ix(
+            &authority.pubkey(),
+            &mint,
+            &ata,
+            &Pubkey::new_unique(),
+            token_program,
+            OP_CLOSE_ACCOUNT,
+            mode,
+            0,
+            0,
+        );
+        let cus = send_ix(&mut svm, ix, &authority);
+
+        let closed = svm.get_account(&ata);
+        assert!(
+            closed.is_none() || closed.unwrap().lamports == 0,
+            "Token account should be closed"
+        );
+        cus
+    }
+
+    fn bench_initialize_account3(mode: u8, token_program: &Pubkey) -> u64 {
+        let (mut svm, authority) = setup();
+        let mint = create_mint(&mut svm, &authority, token_program);
+
+        // Pre-allocate the token account so the CU figure covers only the token CPI
+        let account = Pubkey::new_unique();
+        svm.set_account(
+            account,
+            Account {
+                lamports: svm.minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_LEN),
+                data: vec![0u8; TOKEN_ACCOUNT_LEN],
+                owner: *token_program,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let ix = build_bench_ix(
+            &authority.pubkey(),
+            &mint,
+            &account,
+            &Pubkey::new_unique(),
+            token_program,
+            OP_INITIALIZE_ACCOUNT_3,
+            mode,
+            0,
+            0,
+        );
+        let cus = send_ix(&mut svm, ix, &authority);
+
+        let data = svm.get_account(&account).unwrap().data;
+        assert_eq!(&data[0..32], mint.as_ref());
+        assert_eq!(&data[32..64], authority.pubkey().as_ref());
+        cus
+    }
+
+    fn print_row(name: &str, raw: u64, pinocchio_token: u64) {
+        let delta = pinocchio_token as i64 - raw as i64;
+        println!(
+            "║ {:<18} │ {:>8} │ {:>15} │ {:>7} ║",
+            name, raw, pinocchio_token, delta
+        );
+    }
+
+    #[test]
+    pub fn benchmark_raw_cpi_vs_pinocchio_token() {
+        let token = spl_token::ID;
+
+        let rows = [
+            (
+                "TransferChecked",
+                bench_transfer_checked(MODE_RAW, &token),
+                bench_transfer_checked(MODE_PINOCCHIO_TOKEN, &token),
+            ),
+            (
+                "MintTo",
+                bench_mint_to(MODE_RAW, &token),
+                bench_mint_to(MODE_PINOCCHIO_TOKEN, &token),
+            ),
+            (
+                "CloseAccount",
+                bench_close_account(MODE_RAW, &token),
+                bench_close_account(MODE_PINOCCHIO_TOKEN, &token),
+            ),
+            (
+                "InitializeAccount3",
+                bench_initialize_account3(MODE_RAW, &token),
+                bench_initialize_account3(MODE_PINOCCHIO_TOKEN, &token),
+            ),
+        ];
+
+        println!("\n╔═════════════════════════════════════════════════════════╗");
+        println!("║      BENCHMARK: raw_cpi vs pinocchio_token (CUs)        ║");
+        println!("╠════════════════════╤══════════╤═════════════════╤═══════╣");
+        println!("║ Instruction        │ raw_cpi  │ pinocchio_token │ Delta ║");
+        println!("╠════════════════════╪══════════╪═════════════════╪═══════╣");
+        for (name, raw, pinocchio_token) in rows {
+            print_row(name, raw, pinocchio_token);
+        }
+        println!("╚═════════════════════════════════════════════════════════╝");
+
+        for (name, raw, pinocchio_token) in rows {
+            assert!(
+                raw <= pinocchio_token,
+                "{} raw_cpi ({}) should not cost more than pinocchio_token ({})",
+                name,
+                raw,
+                pinocchio_token
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_cpi_targets_token_2022() {
+        let token_2022 = spl_token_2022::ID;
+
+        let transfer_cus = bench_transfer_checked(MODE_RAW, &token_2022);
+        let mint_cus = bench_mint_to(MODE_RAW, &token_2022);
+        let close_cus = bench_close_account(MODE_RAW, &token_2022);
+        let init_cus = bench_initialize_account3(MODE_RAW, &token_2022);
+
+        println!(
+            "Token-2022 raw_cpi — TransferChecked: {}, MintTo: {}, CloseAccount: {}, InitializeAccount3: {}",
+            transfer_cus, mint_cus, close_cus, init_cus
+        );
+    }
+}
@@ -10,21 +10,16 @@ mod tests {
     };
 
     use solana_clock::Clock;
-    use solana_instruction::{AccountMeta, Instruction};
+    use solana_instruction::{error::InstructionError, Instruction};
     use solana_keypair::Keypair;
     use solana_message::Message;
     use solana_native_token::LAMPORTS_PER_SOL;
     use solana_pubkey::Pubkey;
     use solana_signer::Signer;
     use solana_transaction::Transaction;
+    use solana_transaction_error::TransactionError;
 
-    // In tests, raw_cpi.rs routes CPI to SPL Token via #[cfg(test)].
-    // On-chain (cargo build-sbf), CPI targets p-token program ID.
-    const TOKEN_PROGRAM_ID: Pubkey = spl_token::ID;
-
-    fn program_id() -> Pubkey {
-        Pubkey::from(crate::ID)
-    }
+    use crate::sdk::{self, ContributorAccount, FundraiserAccount};
 
     fn current_timestamp() -> i64 {
         SystemTime::now()
@@ -49,12 +44,12 @@ mod tests {
             )
         });
 
-        svm.add_program(program_id(), &program_data)
+        svm.add_program(sdk::program_id(), &program_data)
             .expect("Failed to add program");
 
         //p-token setup
         let p_token_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("/home/pankaj/turbine/acc-class/fundraiser/pinocchio-fundraiser/src/tests/fixtures/pinocchio_token_program.so");
+            .join("src/tests/fixtures/pinocchio_token_program.so");
 
         let p_token_data = std::fs::read(&p_token_so_path).unwrap_or_else(|_| {
             panic!(
@@ -109,170 +104,99 @@ mod tests {
             .map(|tx| tx.compute_units_consumed)
     }
 
+    fn assert_custom_error(
+        result: Result<u64, litesvm::types::FailedTransactionMetadata>,
+        code: u32,
+    ) {
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(code))
+        );
+    }
+
     fn read_token_balance(svm: &LiteSVM, ata: &Pubkey) -> u64 {
         let acc = svm.get_account(ata).expect("ATA should exist");
         u64::from_le_bytes(acc.data[64..72].try_into().unwrap())
     }
 
-    fn derive_fundraiser(maker: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[b"fundraiser".as_ref(), maker.as_ref()], &program_id())
-    }
-
-    fn derive_vault(fundraiser_pda: &Pubkey, mint: &Pubkey) -> Pubkey {
-        spl_associated_token_account::get_associated_token_address(fundraiser_pda, mint)
-    }
-
-    fn derive_contributor_pda(fundraiser: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(
-            &[
-                b"contributor".as_ref(),
-                fundraiser.as_ref(),
-                contributor.as_ref(),
-            ],
-            &program_id(),
-        )
-    }
-
-    // ─── Instruction Builders ────────────────────────────────────────────
-
-    /// Data: [disc(1), bump(1), amount(8), duration(1), timestamp(8)] = 19 bytes
-    fn build_initialize_ix(
-        maker: &Pubkey,
-        mint: &Pubkey,
-        fundraiser_pda: &Pubkey,
-        vault: &Pubkey,
-        bump: u8,
-        amount: u64,
-        duration: u8,
-        timestamp: i64,
-    ) -> Instruction {
-        let data = [
-            vec![0u8],
-            vec![bump],
-            amount.to_le_bytes().to_vec(),
-            vec![duration],
-            timestamp.to_le_bytes().to_vec(),
-        ]
-        .concat();
-
-        Instruction {
-            program_id: program_id(),
-            accounts: vec![
-                AccountMeta::new(*maker, true),
-                AccountMeta::new_readonly(*mint, false),
-                AccountMeta::new(*fundraiser_pda, false),
-                AccountMeta::new_readonly(*vault, false),
-                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-                AccountMeta::new_readonly(solana_sdk_ids::sysvar::clock::ID, false),
-            ],
-            data,
-        }
-    }
+    /// Initializes a 10-day fundraiser for `amount_to_raise` and has each of
+    /// `count` fresh contributors put in `contribution`.
+    /// Returns (mint, fundraiser_pda, vault, [(contributor, contributor_ata)]).
+    fn setup_funded_fundraiser(
+        svm: &mut LiteSVM,
+        maker: &Keypair,
+        amount_to_raise: u64,
+        count: usize,
+        contribution: u64,
+    ) -> (Pubkey, Pubkey, Pubkey, Vec<(Keypair, Pubkey)>) {
+        let mint = CreateMint::new(svm, maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
+        let ts = current_timestamp();
 
-    /// Data: [disc(1), bump(1)] = 2 bytes
-    fn build_create_contributor_ix(
-        contributor: &Pubkey,
-        fundraiser_pda: &Pubkey,
-        contributor_pda: &Pubkey,
-        bump: u8,
-    ) -> Instruction {
-        Instruction {
-            program_id: program_id(),
-            accounts: vec![
-                AccountMeta::new(*contributor, true),
-                AccountMeta::new_readonly(*fundraiser_pda, false),
-                AccountMeta::new(*contributor_pda, false),
-                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
-            ],
-            data: vec![4u8, bump],
-        }
-    }
+        CreateAssociatedTokenAccount::new(svm, maker, &mint)
+            .owner(&fundraiser_pda)
+            .send()
+            .unwrap();
+        send_ix(
+            svm,
+            sdk::initialize(
+                &maker.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &vault,
+                bump,
+                amount_to_raise,
+                10,
+                ts,
+            ),
+            &[maker],
+        );
 
-    /// Data: [disc(1), amount(8), timestamp(8)] = 17 bytes
-    /// No system_program, no contributor_bump — contributor PDA must already exist
-    fn build_contribute_ix(
-        contributor: &Pubkey,
-        mint: &Pubkey,
-        fundraiser_pda: &Pubkey,
-        contributor_pda: &Pubkey,
-        contributor_ata: &Pubkey,
-        vault: &Pubkey,
-        amount: u64,
-        timestamp: i64,
-    ) -> Instruction {
-        let data = [
-            vec![1u8],
-            amount.to_le_bytes().to_vec(),
-            timestamp.to_le_bytes().to_vec(),
-        ]
-        .concat();
-
-        Instruction {
-            program_id: program_id(),
-            accounts: vec![
-                AccountMeta::new(*contributor, true),
-                AccountMeta::new_readonly(*mint, false),
-                AccountMeta::new(*fundraiser_pda, false),
-                AccountMeta::new(*contributor_pda, false),
-                AccountMeta::new(*contributor_ata, false),
-                AccountMeta::new(*vault, false),
-                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-                AccountMeta::new_readonly(solana_sdk_ids::sysvar::clock::ID, false),
-            ],
-            data,
-        }
-    }
+        let mut contributors = Vec::with_capacity(count);
+        for _ in 0..count {
+            let contrib = Keypair::new();
+            svm.airdrop(&contrib.pubkey(), 2 * LAMPORTS_PER_SOL)
+                .unwrap();
+            let contrib_ata = CreateAssociatedTokenAccount::new(svm, &contrib, &mint)
+                .owner(&contrib.pubkey())
+                .send()
+                .unwrap();
+            MintTo::new(svm, maker, &mint, &contrib_ata, contribution)
+                .send()
+                .unwrap();
 
-    fn build_check_contributions_ix(
-        maker: &Pubkey,
-        mint: &Pubkey,
-        fundraiser_pda: &Pubkey,
-        vault: &Pubkey,
-        maker_ata: &Pubkey,
-    ) -> Instruction {
-        Instruction {
-            program_id: program_id(),
-            accounts: vec![
-                AccountMeta::new(*maker, true),
-                AccountMeta::new_readonly(*mint, false),
-                AccountMeta::new(*fundraiser_pda, false),
-                AccountMeta::new(*vault, false),
-                AccountMeta::new(*maker_ata, false),
-                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-                AccountMeta::new_readonly(solana_sdk_ids::sysvar::clock::ID, false),
-            ],
-            data: vec![2u8],
+            let (contrib_pda, contrib_bump) =
+                sdk::find_contributor_address(&fundraiser_pda, &contrib.pubkey());
+            send_2ix(
+                svm,
+                sdk::create_contributor(
+                    &contrib.pubkey(),
+                    &fundraiser_pda,
+                    &contrib_pda,
+                    contrib_bump,
+                ),
+                sdk::contribute(
+                    &contrib.pubkey(),
+                    &mint,
+                    &fundraiser_pda,
+                    &contrib_pda,
+                    &contrib_ata,
+                    &vault,
+                    contribution,
+                    ts,
+                ),
+                &[&contrib],
+            );
+            contributors.push((contrib, contrib_ata));
         }
-    }
 
-    /// Data: [disc(1), contributor_bump(1)] = 2 bytes
-    fn build_refund_ix(
-        contributor: &Pubkey,
-        maker: &Pubkey,
-        mint: &Pubkey,
-        fundraiser_pda: &Pubkey,
-        contributor_pda: &Pubkey,
-        contributor_ata: &Pubkey,
-        vault: &Pubkey,
-        contributor_bump: u8,
-    ) -> Instruction {
-        let data = vec![3u8, contributor_bump];
-
-        Instruction {
-            program_id: program_id(),
-            accounts: vec![
-                AccountMeta::new(*contributor, true),
-                AccountMeta::new_readonly(*maker, false),
-                AccountMeta::new_readonly(*mint, false),
-                AccountMeta::new(*fundraiser_pda, false),
-                AccountMeta::new(*contributor_pda, false),
-                AccountMeta::new(*contributor_ata, false),
-                AccountMeta::new(*vault, false),
-                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-                AccountMeta::new_readonly(solana_sdk_ids::sysvar::clock::ID, false),
-            ],
-            data,
-        }
+        (mint, fundraiser_pda, vault, contributors)
     }
 
     // ─── Tests ───────────────────────────────────────────────────────────
@@ -286,8 +210,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
 
         // Client creates vault ATA
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
@@ -295,7 +219,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let ix = build_initialize_ix(
+        let ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -311,8 +235,15 @@ mod tests {
         let fundraiser_acc = svm
             .get_account(&fundraiser_pda)
             .expect("Fundraiser should exist");
-        assert_eq!(fundraiser_acc.owner, program_id());
+        assert_eq!(fundraiser_acc.owner, sdk::program_id());
         assert_eq!(fundraiser_acc.data.len(), 91);
+
+        let state = FundraiserAccount::decode(&fundraiser_acc.data).unwrap();
+        assert_eq!(state.maker, maker.pubkey());
+        assert_eq!(state.mint_to_raise, mint);
+        assert_eq!(state.amount_to_raise, 30_000_000);
+        assert_eq!(state.current_amount, 0);
+        assert_eq!(state.bump, bump);
         println!("test_initialize PASSED");
     }
 
@@ -328,15 +259,15 @@ mod tests {
             .authority(&maker.pubkey())
             .send()
             .unwrap();
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
 
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
             .owner(&fundraiser_pda)
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -349,8 +280,8 @@ mod tests {
         send_ix(&mut svm, init_ix, &[&maker]);
 
         let (contributor_pda, contrib_bump) =
-            derive_contributor_pda(&fundraiser_pda, &contributor.pubkey());
-        let create_ix = build_create_contributor_ix(
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        let create_ix = sdk::create_contributor(
             &contributor.pubkey(),
             &fundraiser_pda,
             &contributor_pda,
@@ -362,7 +293,7 @@ mod tests {
         let pda_acc = svm
             .get_account(&contributor_pda)
             .expect("Contributor PDA should exist");
-        assert_eq!(pda_acc.owner, program_id());
+        assert_eq!(pda_acc.owner, sdk::program_id());
         println!("test_create_contributor PASSED");
     }
 
@@ -386,8 +317,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
         let ts = current_timestamp();
 
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
@@ -395,7 +326,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -409,8 +340,8 @@ mod tests {
 
         // Create contributor PDA first, then contribute — same tx possible, separate here to measure CU
         let (contributor_pda, contrib_bump) =
-            derive_contributor_pda(&fundraiser_pda, &contributor.pubkey());
-        let create_ix = build_create_contributor_ix(
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        let create_ix = sdk::create_contributor(
             &contributor.pubkey(),
             &fundraiser_pda,
             &contributor_pda,
@@ -419,7 +350,7 @@ mod tests {
         let create_cus = send_ix(&mut svm, create_ix, &[&contributor]);
         println!("CreateContributor — CUs: {}", create_cus);
 
-        let contribute_ix = build_contribute_ix(
+        let contribute_ix = sdk::contribute(
             &contributor.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -436,7 +367,7 @@ mod tests {
         svm.expire_blockhash();
 
         // Repeat contribute — no CreateContributor needed
-        let contribute_ix2 = build_contribute_ix(
+        let contribute_ix2 = sdk::contribute(
             &contributor.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -449,6 +380,13 @@ mod tests {
         let cus2 = send_ix(&mut svm, contribute_ix2, &[&contributor]);
         println!("Contribute #2 — CUs: {}", cus2);
         assert_eq!(read_token_balance(&svm, &vault), 2_000_000);
+
+        let cont_state =
+            ContributorAccount::decode(&svm.get_account(&contributor_pda).unwrap().data).unwrap();
+        assert_eq!(cont_state.amount, 2_000_000);
+        let fund_state =
+            FundraiserAccount::decode(&svm.get_account(&fundraiser_pda).unwrap().data).unwrap();
+        assert_eq!(fund_state.current_amount, 2_000_000);
         println!("test_contribute PASSED");
     }
 
@@ -472,8 +410,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
         let ts = current_timestamp();
 
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
@@ -481,7 +419,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -494,10 +432,10 @@ mod tests {
         send_ix(&mut svm, init_ix, &[&maker]);
 
         let (contributor_pda, contrib_bump) =
-            derive_contributor_pda(&fundraiser_pda, &contributor.pubkey());
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
         send_ix(
             &mut svm,
-            build_create_contributor_ix(
+            sdk::create_contributor(
                 &contributor.pubkey(),
                 &fundraiser_pda,
                 &contributor_pda,
@@ -506,7 +444,7 @@ mod tests {
             &[&contributor],
         );
 
-        let contribute_ix = build_contribute_ix(
+        let contribute_ix = sdk::contribute(
             &contributor.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -541,8 +479,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
         let ts = current_timestamp();
 
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
@@ -550,7 +488,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -563,10 +501,10 @@ mod tests {
         send_ix(&mut svm, init_ix, &[&maker]);
 
         let (contributor_pda, contrib_bump) =
-            derive_contributor_pda(&fundraiser_pda, &contributor.pubkey());
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
         send_ix(
             &mut svm,
-            build_create_contributor_ix(
+            sdk::create_contributor(
                 &contributor.pubkey(),
                 &fundraiser_pda,
                 &contributor_pda,
@@ -575,7 +513,7 @@ mod tests {
             &[&contributor],
         );
 
-        let contribute_ix = build_contribute_ix(
+        let contribute_ix = sdk::contribute(
             &contributor.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -593,7 +531,7 @@ mod tests {
         svm.set_sysvar(&clock);
 
         let balance_before = read_token_balance(&svm, &contributor_ata);
-        let refund_ix = build_refund_ix(
+        let refund_ix = sdk::refund(
             &contributor.pubkey(),
             &maker.pubkey(),
             &mint,
@@ -634,8 +572,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
         let ts = current_timestamp();
 
         CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
@@ -643,7 +581,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -656,10 +594,10 @@ mod tests {
         send_ix(&mut svm, init_ix, &[&maker]);
 
         let (contributor_pda, contrib_bump) =
-            derive_contributor_pda(&fundraiser_pda, &contributor.pubkey());
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
         send_ix(
             &mut svm,
-            build_create_contributor_ix(
+            sdk::create_contributor(
                 &contributor.pubkey(),
                 &fundraiser_pda,
                 &contributor_pda,
@@ -668,7 +606,7 @@ mod tests {
             &[&contributor],
         );
 
-        let contribute_ix = build_contribute_ix(
+        let contribute_ix = sdk::contribute(
             &contributor.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -685,13 +623,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let check_ix = build_check_contributions_ix(
-            &maker.pubkey(),
-            &mint,
-            &fundraiser_pda,
-            &vault,
-            &maker_ata,
-        );
+        let check_ix =
+            sdk::check_contributions(&maker.pubkey(), &mint, &fundraiser_pda, &vault, &maker_ata);
 
         // Target not met AND not ended: fails.
         // Even if we advance time, it should fail because target is not met.
@@ -714,8 +647,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let (fundraiser_pda, bump) = derive_fundraiser(&maker.pubkey());
-        let vault = derive_vault(&fundraiser_pda, &mint);
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
         let ts = current_timestamp();
         let amount_to_raise: u64 = 1000;
 
@@ -724,7 +657,7 @@ mod tests {
             .send()
             .unwrap();
 
-        let init_ix = build_initialize_ix(
+        let init_ix = sdk::initialize(
             &maker.pubkey(),
             &mint,
             &fundraiser_pda,
@@ -750,16 +683,16 @@ mod tests {
                 .unwrap();
 
             let (contrib_pda, contrib_bump) =
-                derive_contributor_pda(&fundraiser_pda, &contrib.pubkey());
+                sdk::find_contributor_address(&fundraiser_pda, &contrib.pubkey());
 
             // Bundle CreateContributor + Contribute in same TX
-            let create_ix = build_create_contributor_ix(
+            let create_ix = sdk::create_contributor(
                 &contrib.pubkey(),
                 &fundraiser_pda,
                 &contrib_pda,
                 contrib_bump,
             );
-            let contribute_ix = build_contribute_ix(
+            let contribute_ix = sdk::contribute(
                 &contrib.pubkey(),
                 &mint,
                 &fundraiser_pda,
@@ -784,13 +717,8 @@ mod tests {
             .send()
             .unwrap();
 
-        let check_ix = build_check_contributions_ix(
-            &maker.pubkey(),
-            &mint,
-            &fundraiser_pda,
-            &vault,
-            &maker_ata,
-        );
+        let check_ix =
+            sdk::check_contributions(&maker.pubkey(), &mint, &fundraiser_pda, &vault, &maker_ata);
 
         // Advance time to pass the duration check (10 days duration)
         let mut clock = svm.get_sysvar::<Clock>();
@@ -809,4 +737,524 @@ mod tests {
         );
         println!("test_full_flow PASSED");
     }
+
+    #[test]
+    fn test_contribute_insufficient_funds() {
+        let (mut svm, maker) = setup();
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let mint = CreateMint::new(&mut svm, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &maker, &mint, &contributor_ata, 500_000)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
+        let ts = current_timestamp();
+
+        CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
+            .owner(&fundraiser_pda)
+            .send()
+            .unwrap();
+        send_ix(
+            &mut svm,
+            sdk::initialize(
+                &maker.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &vault,
+                bump,
+                30_000_000,
+                10,
+                ts,
+            ),
+            &[&maker],
+        );
+
+        let (contributor_pda, contrib_bump) =
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        send_ix(
+            &mut svm,
+            sdk::create_contributor(
+                &contributor.pubkey(),
+                &fundraiser_pda,
+                &contributor_pda,
+                contrib_bump,
+            ),
+            &[&contributor],
+        );
+
+        // Within the 10% cap, but more than the contributor holds
+        let contribute_ix = sdk::contribute(
+            &contributor.pubkey(),
+            &mint,
+            &fundraiser_pda,
+            &contributor_pda,
+            &contributor_ata,
+            &vault,
+            1_000_000,
+            ts,
+        );
+        let result = try_send_ix(&mut svm, contribute_ix, &[&contributor]);
+        assert_custom_error(result, crate::error::ERR_INSUFFICIENT_FUNDS);
+        assert_eq!(read_token_balance(&svm, &vault), 0);
+        println!("test_contribute_insufficient_funds PASSED");
+    }
+
+    #[test]
+    fn test_contribute_owner_mismatch() {
+        let (mut svm, maker) = setup();
+        let contributor = Keypair::new();
+        let other = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let mint = CreateMint::new(&mut svm, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+        // Funded ATA that belongs to someone else
+        let other_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &mint)
+            .owner(&other.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &maker, &mint, &other_ata, 10_000_000)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
+        let ts = current_timestamp();
+
+        CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
+            .owner(&fundraiser_pda)
+            .send()
+            .unwrap();
+        send_ix(
+            &mut svm,
+            sdk::initialize(
+                &maker.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &vault,
+                bump,
+                30_000_000,
+                10,
+                ts,
+            ),
+            &[&maker],
+        );
+
+        let (contributor_pda, contrib_bump) =
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        send_ix(
+            &mut svm,
+            sdk::create_contributor(
+                &contributor.pubkey(),
+                &fundraiser_pda,
+                &contributor_pda,
+                contrib_bump,
+            ),
+            &[&contributor],
+        );
+
+        let contribute_ix = sdk::contribute(
+            &contributor.pubkey(),
+            &mint,
+            &fundraiser_pda,
+            &contributor_pda,
+            &other_ata,
+            &vault,
+            1_000_000,
+            ts,
+        );
+        let result = try_send_ix(&mut svm, contribute_ix, &[&contributor]);
+        assert_custom_error(result, crate::error::ERR_OWNER_MISMATCH);
+        assert_eq!(read_token_balance(&svm, &other_ata), 10_000_000);
+        println!("test_contribute_owner_mismatch PASSED");
+    }
+
+    #[test]
+    fn test_contribute_rejects_spoofed_token_account() {
+        let (mut svm, maker) = setup();
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let mint = CreateMint::new(&mut svm, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
+        let ts = current_timestamp();
+
+        CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
+            .owner(&fundraiser_pda)
+            .send()
+            .unwrap();
+        send_ix(
+            &mut svm,
+            sdk::initialize(
+                &maker.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &vault,
+                bump,
+                30_000_000,
+                10,
+                ts,
+            ),
+            &[&maker],
+        );
+
+        let (contributor_pda, contrib_bump) =
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        send_ix(
+            &mut svm,
+            sdk::create_contributor(
+                &contributor.pubkey(),
+                &fundraiser_pda,
+                &contributor_pda,
+                contrib_bump,
+            ),
+            &[&contributor],
+        );
+
+        // Token-account-shaped data naming the contributor with a large balance,
+        // but owned by the system program instead of the token program
+        let mut spoofed_data = vec![0u8; 165];
+        spoofed_data[..32].copy_from_slice(mint.as_ref());
+        spoofed_data[32..64].copy_from_slice(contributor.pubkey().as_ref());
+        spoofed_data[64..72].copy_from_slice(&u64::MAX.to_le_bytes());
+        let spoofed = Pubkey::new_unique();
+        svm.set_account(
+            spoofed,
+            solana_account::Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: spoofed_data,
+                owner: solana_sdk_ids::system_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        // Too short to hold an owner and amount
+        let short = Pubkey::new_unique();
+        svm.set_account(
+            short,
+            solana_account::Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![0u8; 40],
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        for bad_ata in [spoofed, short] {
+            let contribute_ix = sdk::contribute(
+                &contributor.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &contributor_pda,
+                &bad_ata,
+                &vault,
+                1_000_000,
+                ts,
+            );
+            let failed = try_send_ix(&mut svm, contribute_ix, &[&contributor])
+                .expect_err("Transaction should fail");
+            assert_eq!(
+                failed.err,
+                TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+            );
+        }
+        assert_eq!(read_token_balance(&svm, &vault), 0);
+    }
+
+    #[test]
+    fn test_refund_owner_mismatch() {
+        let (mut svm, maker) = setup();
+        let contributor = Keypair::new();
+        let other = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+
+        let mint = CreateMint::new(&mut svm, &maker)
+            .decimals(6)
+            .authority(&maker.pubkey())
+            .send()
+            .unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &mint)
+            .owner(&contributor.pubkey())
+            .send()
+            .unwrap();
+        let other_ata = CreateAssociatedTokenAccount::new(&mut svm, &contributor, &mint)
+            .owner(&other.pubkey())
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &maker, &mint, &contributor_ata, 10_000_000)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, bump) = sdk::find_fundraiser_address(&maker.pubkey());
+        let vault = sdk::find_vault_address(&fundraiser_pda, &mint);
+        let ts = current_timestamp();
+
+        CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint)
+            .owner(&fundraiser_pda)
+            .send()
+            .unwrap();
+        send_ix(
+            &mut svm,
+            sdk::initialize(
+                &maker.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &vault,
+                bump,
+                30_000_000,
+                10,
+                ts,
+            ),
+            &[&maker],
+        );
+
+        let (contributor_pda, contrib_bump) =
+            sdk::find_contributor_address(&fundraiser_pda, &contributor.pubkey());
+        send_2ix(
+            &mut svm,
+            sdk::create_contributor(
+                &contributor.pubkey(),
+                &fundraiser_pda,
+                &contributor_pda,
+                contrib_bump,
+            ),
+            sdk::contribute(
+                &contributor.pubkey(),
+                &mint,
+                &fundraiser_pda,
+                &contributor_pda,
+                &contributor_ata,
+                &vault,
+                1_000_000,
+                ts,
+            ),
+            &[&contributor],
+        );
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 950400;
+        svm.set_sysvar(&clock);
+
+        // Refund must land in the contributor's own token account
+        let refund_ix = sdk::refund(
+            &contributor.pubkey(),
+            &maker.pubkey(),
+            &mint,
+            &fundraiser_pda,
+            &contributor_pda,
+            &other_ata,
+            &vault,
+            contrib_bump,
+        );
+        let result = try_send_ix(&mut svm, refund_ix, &[&contributor]);
+        assert_custom_error(result, crate::error::ERR_OWNER_MISMATCH);
+        assert_eq!(read_token_balance(&svm, &vault), 1_000_000);
+        println!("test_refund_owner_mismatch PASSED");
+    }
+
+    #[test]
+    fn test_batch_refund() {
+        let (mut svm, maker) = setup();
+        let cranker = Keypair::new();
+        svm.airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let (mint, fundraiser_pda, vault, contributors) =
+            setup_funded_fundraiser(&mut svm, &maker, 30_000_000, 3, 1_000_000);
+        assert_eq!(read_token_balance(&svm, &vault), 3_000_000);
+
+        let wallets: Vec<Pubkey> = contributors.iter().map(|(c, _)| c.pubkey()).collect();
+        let batch_ix = sdk::batch_refund(&maker.pubkey(), &mint, &fundraiser_pda, &vault, &wallets);
+
+        // Raise still running
+        let result = try_send_ix(&mut svm, batch_ix.clone(), &[&cranker]);
+        assert_custom_error(result, crate::error::ERR_FUNDRAISER_NOT_ENDED);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 950400;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+
+        let lamports_before: Vec<u64> = contributors
+            .iter()
+            .map(|(contrib, _)| svm.get_account(&contrib.pubkey()).unwrap().lamports)
+            .collect();
+
+        // Anyone can crank — the cranker is not a contributor or the maker
+        let cus = send_ix(&mut svm, batch_ix.clone(), &[&cranker]);
+        println!("BatchRefund (3 contributors) — CUs: {}", cus);
+
+        assert_eq!(read_token_balance(&svm, &vault), 0);
+        for ((contrib, contrib_ata), before) in contributors.iter().zip(lamports_before) {
+            assert_eq!(read_token_balance(&svm, contrib_ata), 1_000_000);
+
+            // The contributor PDA is closed and its rent returned to the wallet
+            let (contrib_pda, _) =
+                sdk::find_contributor_address(&fundraiser_pda, &contrib.pubkey());
+            let closed = svm.get_account(&contrib_pda);
+            assert!(closed.is_none() || closed.unwrap().lamports == 0);
+            assert_eq!(
+                svm.get_account(&contrib.pubkey()).unwrap().lamports,
+                before + crate::CONTRIBUTOR_RENT
+            );
+        }
+        let fund_state =
+            FundraiserAccount::decode(&svm.get_account(&fundraiser_pda).unwrap().data).unwrap();
+        assert_eq!(fund_state.current_amount, 0);
+
+        // Re-running the same batch is a no-op
+        svm.expire_blockhash();
+        send_ix(&mut svm, batch_ix, &[&cranker]);
+        assert_eq!(read_token_balance(&svm, &contributors[0].1), 1_000_000);
+        println!("test_batch_refund PASSED");
+    }
+
+    #[test]
+    fn test_batch_refund_rejects_redirected_ata() {
+        let (mut svm, maker) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let (mint, fundraiser_pda, vault, contributors) =
+            setup_funded_fundraiser(&mut svm, &maker, 30_000_000, 1, 1_000_000);
+        let attacker_ata = CreateAssociatedTokenAccount::new(&mut svm, &attacker, &mint)
+            .owner(&attacker.pubkey())
+            .send()
+            .unwrap();
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 950400;
+        svm.set_sysvar(&clock);
+
+        // Victim's contributor PDA paired with the attacker's token account
+        let mut batch_ix = sdk::batch_refund(
+            &maker.pubkey(),
+            &mint,
+            &fundraiser_pda,
+            &vault,
+            &[contributors[0].0.pubkey()],
+        );
+        batch_ix.accounts[8].pubkey = attacker_ata;
+
+        let failed =
+            try_send_ix(&mut svm, batch_ix, &[&attacker]).expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+        );
+        assert_eq!(read_token_balance(&svm, &vault), 1_000_000);
+        assert_eq!(read_token_balance(&svm, &attacker_ata), 0);
+        println!("test_batch_refund_rejects_redirected_ata PASSED");
+    }
+
+    #[test]
+    fn test_batch_refund_rejects_mismatched_contributor() {
+        let (mut svm, maker) = setup();
+        let attacker = Keypair::new();
+        svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let (mint, fundraiser_pda, vault, contributors) =
+            setup_funded_fundraiser(&mut svm, &maker, 30_000_000, 2, 1_000_000);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 950400;
+        svm.set_sysvar(&clock);
+
+        // First contributor's wallet and ATA with the second contributor's PDA,
+        // which would pay the second contributor's amount and rent to the first
+        let mut batch_ix = sdk::batch_refund(
+            &maker.pubkey(),
+            &mint,
+            &fundraiser_pda,
+            &vault,
+            &[contributors[0].0.pubkey(), contributors[1].0.pubkey()],
+        );
+        batch_ix.accounts[7].pubkey = batch_ix.accounts[10].pubkey;
+        batch_ix.accounts.truncate(9);
+        batch_ix.data.truncate(4);
+        batch_ix.data[1] = 1;
+        let (_, second_bump) =
+            sdk::find_contributor_address(&fundraiser_pda, &contributors[1].0.pubkey());
+        batch_ix.data[2] = second_bump;
+
+        let failed =
+            try_send_ix(&mut svm, batch_ix, &[&attacker]).expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+        );
+        assert_eq!(read_token_balance(&svm, &vault), 2_000_000);
+    }
+
+    #[test]
+    fn test_batch_refund_target_met() {
+        let (mut svm, maker) = setup();
+        let (mint, fundraiser_pda, vault, contributors) =
+            setup_funded_fundraiser(&mut svm, &maker, 1_000, 10, 100);
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 950400;
+        svm.set_sysvar(&clock);
+
+        let wallets: Vec<Pubkey> = contributors
+            .iter()
+            .take(crate::instructions::MAX_BATCH_REFUND)
+            .map(|(c, _)| c.pubkey())
+            .collect();
+        let batch_ix = sdk::batch_refund(&maker.pubkey(), &mint, &fundraiser_pda, &vault, &wallets);
+        let result = try_send_ix(&mut svm, batch_ix, &[&maker]);
+        assert_custom_error(result, crate::error::ERR_TARGET_MET);
+        assert_eq!(read_token_balance(&svm, &vault), 1_000);
+        println!("test_batch_refund_target_met PASSED");
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_discriminator() {
+        let mut fundraiser_data = [0u8; crate::state::Fundraiser::LEN];
+        assert_eq!(
+            FundraiserAccount::decode(&fundraiser_data),
+            Err(sdk::DecodeError::InvalidDiscriminator)
+        );
+        fundraiser_data[0] = crate::state::Fundraiser::DISC;
+        assert!(FundraiserAccount::decode(&fundraiser_data).is_ok());
+        assert_eq!(
+            FundraiserAccount::decode(&fundraiser_data[..90]),
+            Err(sdk::DecodeError::InvalidLength)
+        );
+
+        // A contributor account must never decode as a fundraiser and vice versa
+        let contributor_data = [crate::state::Contributor::DISC, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(ContributorAccount::decode(&contributor_data).is_ok());
+        assert!(FundraiserAccount::decode(&contributor_data).is_err());
+        assert!(ContributorAccount::decode(&fundraiser_data).is_err());
+    }
 }
+
+mod cpi_bench;
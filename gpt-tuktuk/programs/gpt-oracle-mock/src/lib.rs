@@ -0,0 +1,169 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+//! Stand-in for the Solana GPT Oracle, deployed at the oracle's address inside
+//! LiteSVM. `initialize` creates the identity PDA. `interact_with_llm` takes the same accounts and arguments as the
+//! oracle and stores the interaction at the same PDA. `callback_from_llm`
+//! plays the off-chain agent: it signs with the identity PDA and CPIs the
+//! stored callback with the response, prepending the identity to the stored
+//! callback accounts.
+//!
+//! Contexts, delegation and `is_processed` are not modelled. The context
+//! account is only used as a seed, and an interaction can be answered again.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta as SolanaAccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+declare_id!("LLMrieZMpbJFwN52WgmBNMxYojrpRVYXdC1RCweEbab");
+
+#[program]
+pub mod gpt_oracle_mock {
+    use super::*;
+
+    pub fn initialize(_ctx: Context<Initialize>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn interact_with_llm(
+        ctx: Context<InteractWithLlm>,
+        text: String,
+        callback_program_id: Pubkey,
+        callback_discriminator: [u8; 8],
+        account_metas: Option<Vec<AccountMeta>>,
+    ) -> Result<()> {
+        ctx.accounts.interaction.set_inner(Interaction {
+            context: ctx.accounts.context_account.key(),
+            user: ctx.accounts.payer.key(),
+            text,
+            callback_program_id,
+            callback_discriminator,
+            callback_account_metas: account_metas.unwrap_or_default(),
+            is_processed: false,
+        });
+        Ok(())
+    }
+
+    pub fn callback_from_llm<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CallbackFromLlm<'info>>,
+        response: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .callback(ctx.remaining_accounts, response, ctx.bumps.identity)
+    }
+}
+
+#[account]
+pub struct Identity {}
+
+/// Same layout as the oracle's `AccountMeta`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[account]
+pub struct Interaction {
+    pub context: Pubkey,
+    pub user: Pubkey,
+    pub text: String,
+    pub callback_program_id: Pubkey,
+    pub callback_discriminator: [u8; 8],
+    pub callback_account_metas: Vec<AccountMeta>,
+    pub is_processed: bool,
+}
+
+impl Interaction {
+    // 8 discriminator + 32 context + 32 user + (4 + text) text + 32 callback_program_id
+    // + 8 callback_discriminator + (4 + metas * (32 + 1 + 1)) callback_account_metas + 1 is_processed
+    pub fn space(text_len: usize, metas: usize) -> usize {
+        8 + 32 + 32 + 4 + text_len + 32 + 8 + 4 + metas * (32 + 1 + 1) + 1
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(init, payer = payer, space = 8, seeds = [b"identity"], bump)]
+    pub identity: Account<'info, Identity>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    text: String,
+    callback_program_id: Pubkey,
+    callback_discriminator: [u8; 8],
+    account_metas: Option<Vec<AccountMeta>>
+)]
+pub struct InteractWithLlm<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Interaction::space(text.len(), account_metas.as_ref().map_or(0, Vec::len)),
+        seeds = [b"interaction", payer.key().as_ref(), context_account.key().as_ref()],
+        bump,
+    )]
+    pub interaction: Account<'info, Interaction>,
+    /// CHECK: Contexts are not modelled
+    pub context_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackFromLlm<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [b"identity"], bump)]
+    pub identity: Account<'info, Identity>,
+    #[account(mut)]
+    pub interaction: Account<'info, Interaction>,
+    /// CHECK: Via the address constraint
+    #[account(address = interaction.callback_program_id)]
+    pub program: UncheckedAccount<'info>,
+}
+
+impl<'info> CallbackFromLlm<'info> {
+    // `remaining_accounts` are the stored callback accounts, in order
+    pub fn callback(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        response: String,
+        identity_bump: u8,
+    ) -> Result<()> {
+        let mut accounts = vec![SolanaAccountMeta::new_readonly(self.identity.key(), true)];
+        accounts.extend(self.interaction.callback_account_metas.iter().map(|meta| {
+            if meta.is_writable {
+                SolanaAccountMeta::new(meta.pubkey, meta.is_signer)
+            } else {
+                SolanaAccountMeta::new_readonly(meta.pubkey, meta.is_signer)
+            }
+        }));
+
+        let mut data = self.interaction.callback_discriminator.to_vec();
+        response.serialize(&mut data)?;
+
+        let mut account_infos = vec![self.identity.to_account_info()];
+        account_infos.extend_from_slice(remaining_accounts);
+        account_infos.push(self.program.to_account_info());
+
+        invoke_signed(
+            &Instruction {
+                program_id: self.program.key(),
+                accounts,
+                data,
+            },
+            &account_infos,
+            &[&[b"identity", &[identity_bump]]],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum GptError {
+    #[msg("Prompt is longer than MAX_PROMPT_LEN bytes")]
+    PromptTooLong,
+    #[msg("Result is longer than MAX_RESULT_LEN bytes")]
+    ResultTooLong,
+    #[msg("Follow-up has too many accounts, too long a data prefix, or targets this program")]
+    InvalidFollowUp,
+    #[msg("Accounts passed for the follow-up don't match the registered ones")]
+    FollowUpAccountMismatch,
+    #[msg("Request already has a result")]
+    AlreadyCompleted,
+    #[msg("Recurring requests need a positive interval and at least one run")]
+    InvalidRecurrence,
+    #[msg("No run of this recurring request is waiting for a result")]
+    NoRunInFlight,
+    #[msg("Accounts to re-queue the recurring request are missing or wrong")]
+    InvalidRequeueAccounts,
+    #[msg("No result is waiting for its follow-up")]
+    NoFollowUpPending,
+}
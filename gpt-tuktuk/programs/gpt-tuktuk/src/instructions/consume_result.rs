@@ -1,16 +1,13 @@
-use crate::state::GptRequest;
+use crate::errors::GptError;
+use crate::instructions::{execute_request_task, requeue_accounts};
+use crate::state::{GptRequest, HistoryEntry, RequestStatus, UserRequests, MAX_RESULT_LEN};
 use anchor_lang::prelude::*;
+use tuktuk_program::tuktuk::cpi::{accounts::QueueTaskV0, queue_task_v0};
 
 #[derive(Accounts)]
 pub struct ConsumeResult<'info> {
-    #[account(
-        mut,
-        seeds = [b"gpt_request", user.key().as_ref(), &request_state.task_id.to_le_bytes()],
-        bump = request_state.bump,
-    )]
-    pub request_state: Account<'info, GptRequest>,
-
-    /// The GPT Oracle's Identity PDA that signs the callback
+    /// The GPT Oracle's Identity PDA that signs the callback.
+    /// The oracle puts it ahead of the accounts execute_request registered.
     /// We verify this is owned by the GPT Oracle program
     /// CHECK: Verified as the oracle identity PDA signer
     #[account(
@@ -19,17 +16,194 @@ pub struct ConsumeResult<'info> {
     )]
     pub oracle_identity: UncheckedAccount<'info>,
 
-    /// CHECK: User needed fro PDA seed derivation
+    #[account(
+        mut,
+        seeds = [b"gpt_request", user.key().as_ref(), &request_state.request_id.to_le_bytes()],
+        bump = request_state.bump,
+    )]
+    pub request_state: Account<'info, GptRequest>,
+
+    /// CHECK: User needed fro PDA seed derivation, receives the unused result rent
+    #[account(mut)]
     pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_requests", user.key().as_ref()],
+        bump = user_requests.bump,
+    )]
+    pub user_requests: Account<'info, UserRequests>,
+
+    /// Pays for a recurring request's next run. What's left goes to the user
+    /// once the request is done.
+    /// CHECK: System-owned PDA funded by schedule_request
+    #[account(
+        mut,
+        seeds = [b"task_payer", request_state.key().as_ref()],
+        bump
+    )]
+    pub task_payer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-pub fn consume_result(ctx: Context<ConsumeResult>, result: String) -> Result<()> {
+pub fn consume_result<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ConsumeResult<'info>>,
+    result: String,
+) -> Result<()> {
+    // A result is only consumed once
+    require!(
+        !ctx.accounts.request_state.is_completed,
+        GptError::AlreadyCompleted
+    );
+    require!(result.len() <= MAX_RESULT_LEN, GptError::ResultTooLong);
+
+    let now = Clock::get()?.unix_timestamp;
     let request_state = &mut ctx.accounts.request_state;
 
     msg!("Received GPT Response: {}", result);
-    request_state.result = Some(result);
-    request_state.is_completed = true;
+    request_state.result = Some(result.clone());
+
+    // A recurring request is completed by its last run, and keeps every run's result in history
+    let finished = match &mut request_state.recurrence {
+        Some(recurrence) => {
+            require!(recurrence.awaiting_result, GptError::NoRunInFlight);
+            recurrence.awaiting_result = false;
+            recurrence.runs += 1;
+            let run = recurrence.runs;
+            let finished = run >= recurrence.max_runs;
+            request_state.push_history(HistoryEntry {
+                run,
+                completed_at: now,
+                result: result.clone(),
+            });
+            finished
+        }
+        None => true,
+    };
+    request_state.is_completed = finished;
+
+    // The follow-up runs in its own transaction, so a failing one can't
+    // roll back the result
+    request_state.follow_up_pending = request_state.follow_up.is_some();
+
+    // Fit the account to its contents. schedule_request deposited rent for the
+    // largest it can get, so hand back what's no longer needed: everything past
+    // the current size once the request is done, nothing while runs remain.
+    let space = request_state.size();
+    let reserved = if finished {
+        space
+    } else {
+        request_state.max_size()
+    };
+    let request_info = request_state.to_account_info();
+    request_info.resize(space)?;
+
+    let surplus = request_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(reserved));
+    ctx.accounts.request_state.sub_lamports(surplus)?;
+    ctx.accounts.user.add_lamports(surplus)?;
+
+    if finished {
+        ctx.accounts.user_requests.set_status(
+            ctx.accounts.request_state.request_id,
+            RequestStatus::Completed,
+        );
+    }
+
+    // A recurring request's next run is queued with the re-queue accounts,
+    // passed as remaining accounts
+    if finished {
+        settle_task_payer(&ctx)?;
+    } else {
+        requeue(&ctx, ctx.remaining_accounts, now)?;
+    }
+
+    Ok(())
+}
+
+/// Hands what's left in the task payer back to the user
+fn settle_task_payer(ctx: &Context<ConsumeResult>) -> Result<()> {
+    let request_key = ctx.accounts.request_state.key();
+    let task_payer = &ctx.accounts.task_payer;
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: task_payer.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            &[&[b"task_payer", request_key.as_ref(), &[ctx.bumps.task_payer]]],
+        ),
+        task_payer.lamports(),
+    )?;
+
+    if let Some(recurrence) = &ctx.accounts.request_state.recurrence {
+        msg!(
+            "Recurring GPT Request #{} finished after {} runs",
+            ctx.accounts.request_state.request_id,
+            recurrence.runs
+        );
+    }
+    Ok(())
+}
+
+/// Queues the next run of a recurring request
+fn requeue<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, ConsumeResult<'info>>,
+    accounts: &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<()> {
+    let request_state = &ctx.accounts.request_state;
+    let Some(recurrence) = &request_state.recurrence else {
+        return Ok(());
+    };
+
+    let request_key = request_state.key();
+    let expected = requeue_accounts(request_key, recurrence, request_state.task_id);
+    for (info, (pubkey, _)) in accounts.iter().zip(expected.iter()) {
+        require!(info.key() == *pubkey, GptError::InvalidRequeueAccounts);
+    }
+    let [task_queue, task_queue_authority, task, queue_authority, tuktuk_program] = accounts else {
+        return err!(GptError::InvalidRequeueAccounts);
+    };
+
+    let trigger_time = now + recurrence.interval;
+    let task_args = execute_request_task(
+        request_key,
+        request_state,
+        ctx.accounts.user.key(),
+        trigger_time,
+    )?;
+
+    let (_, queue_authority_bump) = Pubkey::find_program_address(&[b"queue_authority"], &crate::ID);
+    queue_task_v0(
+        CpiContext::new_with_signer(
+            tuktuk_program.clone(),
+            QueueTaskV0 {
+                payer: ctx.accounts.task_payer.to_account_info(),
+                queue_authority: queue_authority.clone(),
+                task_queue: task_queue.clone(),
+                task_queue_authority: task_queue_authority.clone(),
+                task: task.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[
+                &[b"queue_authority", &[queue_authority_bump]],
+                &[b"task_payer", request_key.as_ref(), &[ctx.bumps.task_payer]],
+            ],
+        ),
+        task_args,
+    )?;
 
+    msg!(
+        "Queued run {} of {} for GPT Request #{} at {}",
+        recurrence.runs + 1,
+        recurrence.max_runs,
+        request_state.request_id,
+        trigger_time
+    );
     Ok(())
 }
 
@@ -1,4 +1,6 @@
-use crate::state::GptRequest;
+use crate::errors::GptError;
+use crate::instructions::requeue_accounts;
+use crate::state::{GptRequest, UserRequests};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 
@@ -9,20 +11,37 @@ const GPT_ORACLE_PROGRAM: Pubkey = solana_gpt_oracle::ID_CONST;
 pub struct ExecuteRequest<'info> {
     #[account(
         mut,
-        seeds = [b"gpt_request", user.key().as_ref(), &request_state.task_id.to_le_bytes()],
+        seeds = [b"gpt_request", user.key().as_ref(), &request_state.request_id.to_le_bytes()],
         bump = request_state.bump,
     )]
     pub request_state: Account<'info, GptRequest>,
 
-    /// CHECK: The user who scheduled this request
-    #[account(mut)]
-    pub user: Signer<'info>,
+    /// Passed through to the oracle callback so consume_result can mark the request completed
+    #[account(
+        seeds = [b"user_requests", user.key().as_ref()],
+        bump = user_requests.bump,
+    )]
+    pub user_requests: Account<'info, UserRequests>,
+
+    /// CHECK: The user who scheduled this request. Not a signer, so Tuktuk
+    /// cranks can run the task.
+    pub user: UncheckedAccount<'info>,
+
+    /// Pays for the oracle interaction in place of the user
+    /// CHECK: System-owned PDA funded by schedule_request
+    #[account(
+        mut,
+        seeds = [b"task_payer", request_state.key().as_ref()],
+        bump
+    )]
+    pub task_payer: UncheckedAccount<'info>,
 
     /// The GPT Oracle context account (created via create_llm_context on the oracle)
-    /// CHECK: Validated by the GPT Oracle program
+    /// CHECK: The one the request was scheduled with, validated by the GPT Oracle program
+    #[account(address = request_state.context_account)]
     pub oracle_context_account: UncheckedAccount<'info>,
 
-    /// The interaction PDA on the GPT Oracle
+    /// The interaction PDA on the GPT Oracle, seeded with the task payer
     /// CHECK: Derived and validated by the GPT Oracle program
     #[account(mut)]
     pub oracle_interaction: UncheckedAccount<'info>,
@@ -35,17 +54,23 @@ pub struct ExecuteRequest<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn execute_request(ctx: Context<ExecuteRequest>, prompt: String) -> Result<()> {
+pub fn execute_request(ctx: Context<ExecuteRequest>) -> Result<()> {
     let request_state = &mut ctx.accounts.request_state;
+    require!(!request_state.is_completed, GptError::AlreadyCompleted);
+    // Always the prompt the request was scheduled with, whoever runs it
+    let prompt = request_state.prompt.clone();
 
     msg!(
-        "Executing GPT Request for Task ID: {}",
+        "Executing GPT Request #{} for Task ID: {}",
+        request_state.request_id,
         request_state.task_id
     );
     msg!("Prompt: {}", prompt);
 
-    // Store the context account reference
-    request_state.context_account = ctx.accounts.oracle_context_account.key();
+    // consume_result only takes a recurring request's result while a run is in flight
+    if let Some(recurrence) = &mut request_state.recurrence {
+        recurrence.awaiting_result = true;
+    }
 
     // Build the callback discriminator for our `consume_result` instruction
     // Anchor discriminator = first 8 bytes of sha256("global:consume_result")
@@ -58,19 +83,49 @@ pub fn execute_request(ctx: Context<ExecuteRequest>, prompt: String) -> Result<(
     // to pass when it CPIs back into our consume_result.
     // The oracle automatically prepends its Identity PDA as the first account (signer),
     // so we only specify our additional accounts here.
-    let callback_account_metas: Vec<solana_gpt_oracle::AccountMeta> = vec![
+    let mut callback_account_metas: Vec<solana_gpt_oracle::AccountMeta> = vec![
         solana_gpt_oracle::AccountMeta {
             pubkey: request_state.key(),
             is_signer: false,
             is_writable: true,
         },
+        // Writable so consume_result can return the unused result rent
         solana_gpt_oracle::AccountMeta {
             pubkey: ctx.accounts.user.key(),
             is_signer: false,
+            is_writable: true,
+        },
+        solana_gpt_oracle::AccountMeta {
+            pubkey: ctx.accounts.user_requests.key(),
+            is_signer: false,
+            is_writable: true,
+        },
+        // Settled to the user once the request is done
+        solana_gpt_oracle::AccountMeta {
+            pubkey: ctx.accounts.task_payer.key(),
+            is_signer: false,
+            is_writable: true,
+        },
+        solana_gpt_oracle::AccountMeta {
+            pubkey: ctx.accounts.system_program.key(),
+            is_signer: false,
             is_writable: false,
         },
     ];
 
+    // A recurring request needs the accounts to queue its next run
+    if let Some(recurrence) = &request_state.recurrence {
+        callback_account_metas.extend(
+            requeue_accounts(request_state.key(), recurrence, request_state.task_id)
+                .into_iter()
+                .map(|(pubkey, is_writable)| solana_gpt_oracle::AccountMeta {
+                    pubkey,
+                    is_signer: false,
+                    is_writable,
+                }),
+        );
+    }
+
     // Manually build the interact_with_llm instruction data
     // Anchor discriminator for "interact_with_llm" + borsh-serialized args
     let discriminator = anchor_lang::solana_program::hash::hash(b"global:interact_with_llm")
@@ -106,7 +161,7 @@ pub fn execute_request(ctx: Context<ExecuteRequest>, prompt: String) -> Result<(
         program_id: ctx.accounts.gpt_oracle_program.key(),
         accounts: vec![
             anchor_lang::solana_program::instruction::AccountMeta::new(
-                ctx.accounts.user.key(),
+                ctx.accounts.task_payer.key(),
                 true,
             ),
             anchor_lang::solana_program::instruction::AccountMeta::new(
@@ -125,14 +180,16 @@ pub fn execute_request(ctx: Context<ExecuteRequest>, prompt: String) -> Result<(
         data: ix_data,
     };
 
-    anchor_lang::solana_program::program::invoke(
+    let request_key = ctx.accounts.request_state.key();
+    anchor_lang::solana_program::program::invoke_signed(
         &ix,
         &[
-            ctx.accounts.user.to_account_info(),
+            ctx.accounts.task_payer.to_account_info(),
             ctx.accounts.oracle_interaction.to_account_info(),
             ctx.accounts.oracle_context_account.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
         ],
+        &[&[b"task_payer", request_key.as_ref(), &[ctx.bumps.task_payer]]],
     )?;
 
     msg!("GPT Oracle request sent successfully!");
@@ -1,9 +1,11 @@
 pub mod consume_result;
 pub mod execute_request;
 pub mod initialize;
+pub mod run_follow_up;
 pub mod schedule_request;
 
 pub use consume_result::*;
 pub use execute_request::*;
 pub use initialize::*;
+pub use run_follow_up::*;
 pub use schedule_request::*;
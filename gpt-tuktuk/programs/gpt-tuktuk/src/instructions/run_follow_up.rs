@@ -0,0 +1,89 @@
+use crate::errors::GptError;
+use crate::state::GptRequest;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+#[derive(Accounts)]
+pub struct RunFollowUp<'info> {
+    #[account(
+        mut,
+        seeds = [b"gpt_request", user.key().as_ref(), &request_state.request_id.to_le_bytes()],
+        bump = request_state.bump,
+    )]
+    pub request_state: Account<'info, GptRequest>,
+
+    /// CHECK: User needed for PDA seed derivation
+    pub user: UncheckedAccount<'info>,
+}
+
+/// Invokes the registered follow-up with the latest result. Anyone can send
+/// it once consume_result has stored a result. If the follow-up fails, the
+/// result stays stored and the follow-up stays pending, so it can be retried.
+pub fn run_follow_up<'info>(ctx: Context<'_, '_, 'info, 'info, RunFollowUp<'info>>) -> Result<()> {
+    let request_state = &mut ctx.accounts.request_state;
+    require!(request_state.follow_up_pending, GptError::NoFollowUpPending);
+    let (Some(follow_up), Some(result)) = (&request_state.follow_up, &request_state.result) else {
+        return err!(GptError::NoFollowUpPending);
+    };
+
+    // Remaining accounts are the registered accounts, in order, then the program
+    let (program, accounts) = ctx
+        .remaining_accounts
+        .split_last()
+        .ok_or(GptError::FollowUpAccountMismatch)?;
+    require!(
+        program.key() == follow_up.program_id && accounts.len() == follow_up.accounts.len(),
+        GptError::FollowUpAccountMismatch
+    );
+    for (info, account) in accounts.iter().zip(&follow_up.accounts) {
+        require!(
+            info.key() == account.pubkey,
+            GptError::FollowUpAccountMismatch
+        );
+    }
+
+    let request_key = request_state.key();
+    let account_metas = follow_up
+        .accounts
+        .iter()
+        .map(|account| {
+            let is_signer = account.pubkey == request_key;
+            if account.is_writable {
+                AccountMeta::new(account.pubkey, is_signer)
+            } else {
+                AccountMeta::new_readonly(account.pubkey, is_signer)
+            }
+        })
+        .collect();
+
+    let mut data = follow_up.data_prefix.clone();
+    result.serialize(&mut data)?;
+    let program_id = follow_up.program_id;
+
+    // Persist first, so the follow-up reads the request as it is now
+    request_state.follow_up_pending = false;
+    request_state.exit(&crate::ID)?;
+
+    let user_key = ctx.accounts.user.key();
+    let request_id = request_state.request_id.to_le_bytes();
+    invoke_signed(
+        &Instruction {
+            program_id,
+            accounts: account_metas,
+            data,
+        },
+        ctx.remaining_accounts,
+        &[&[
+            b"gpt_request",
+            user_key.as_ref(),
+            &request_id,
+            &[request_state.bump],
+        ]],
+    )?;
+
+    msg!("Ran follow-up of GPT Request #{}", request_state.request_id);
+    Ok(())
+}
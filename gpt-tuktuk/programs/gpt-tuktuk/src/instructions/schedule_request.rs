@@ -11,16 +11,37 @@ use tuktuk_program::{
     TransactionSourceV0,
 };
 
-use crate::state::GptRequest;
+use crate::errors::GptError;
+use crate::state::{
+    FollowUp, GptRequest, Recurrence, RecurrenceArgs, UserRequests, CRANK_REWARD,
+    MAX_FOLLOW_UP_ACCOUNTS, MAX_FOLLOW_UP_PREFIX_LEN, MAX_PROMPT_LEN, REQUEUE_ACCOUNTS,
+    TASK_RENT_RESERVE,
+};
 
 #[derive(Accounts)]
-#[instruction(task_id: u16)]
+#[instruction(
+    task_id: u16,
+    prompt: String,
+    delay: i64,
+    follow_up: Option<FollowUp>,
+    recurrence: Option<RecurrenceArgs>
+)]
 pub struct ScheduleRequest<'info> {
+    /// Created on the user's first request
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserRequests::LEN,
+        seeds = [b"user_requests", user.key().as_ref()],
+        bump
+    )]
+    pub user_requests: Account<'info, UserRequests>,
+
     #[account(
         init,
         payer = user,
-        space = GptRequest::LEN,
-        seeds = [b"gpt_request", user.key().as_ref(), &task_id.to_le_bytes()],
+        space = GptRequest::space(prompt.len(), None, follow_up.as_ref(), recurrence.is_some()),
+        seeds = [b"gpt_request", user.key().as_ref(), &user_requests.next_request_id.to_le_bytes()],
         bump
     )]
     pub request_state: Account<'info, GptRequest>,
@@ -28,6 +49,15 @@ pub struct ScheduleRequest<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Pays for the oracle interaction and, for a recurring request, its re-queued runs
+    /// CHECK: System-owned PDA, only signs in execute_request and consume_result
+    #[account(
+        mut,
+        seeds = [b"task_payer", request_state.key().as_ref()],
+        bump
+    )]
+    pub task_payer: UncheckedAccount<'info>,
+
     /// CHECK: Safe
     #[account(mut)]
     pub task_queue: UncheckedAccount<'info>,
@@ -61,51 +91,98 @@ pub fn schedule_request(
     task_id: u16,
     prompt: String,
     delay: i64, // Delay in seconds
+    follow_up: Option<FollowUp>,
+    recurrence: Option<RecurrenceArgs>,
 ) -> Result<()> {
-    // 1. Initialize State
+    require!(prompt.len() <= MAX_PROMPT_LEN, GptError::PromptTooLong);
+    if let Some(follow_up) = &follow_up {
+        // Self-CPI is the one re-entrant call the runtime allows, so it's refused here
+        require!(
+            follow_up.program_id != crate::ID
+                && follow_up.accounts.len() <= MAX_FOLLOW_UP_ACCOUNTS
+                && follow_up.data_prefix.len() <= MAX_FOLLOW_UP_PREFIX_LEN,
+            GptError::InvalidFollowUp
+        );
+    }
+
+    if let Some(recurrence) = &recurrence {
+        require!(
+            recurrence.interval > 0 && recurrence.max_runs > 0,
+            GptError::InvalidRecurrence
+        );
+    }
+
+    // 1. Allocate the request ID and initialize State
+    let user_requests = &mut ctx.accounts.user_requests;
+    user_requests.user = ctx.accounts.user.key();
+    user_requests.bump = ctx.bumps.user_requests;
+    let request_id = user_requests.allocate();
+
     let request_state = &mut ctx.accounts.request_state;
+    request_state.request_id = request_id;
     request_state.task_id = task_id;
-    request_state.prompt = prompt.clone();
+    request_state.prompt = prompt;
     request_state.is_completed = false;
     request_state.context_account = ctx.accounts.oracle_context_account.key();
     request_state.bump = ctx.bumps.request_state;
+    request_state.follow_up = follow_up;
+    request_state.follow_up_pending = false;
+    request_state.recurrence = recurrence.map(|recurrence| Recurrence {
+        interval: recurrence.interval,
+        max_runs: recurrence.max_runs,
+        runs: 0,
+        task_queue: ctx.accounts.task_queue.key(),
+        awaiting_result: false,
+    });
+    request_state.history = Vec::new();
 
-    // 2. Derive the interaction PDA on the GPT Oracle
-    // seeds: [b"interaction", user, context_account]
-    let (oracle_interaction, _) = Pubkey::find_program_address(
-        &[
-            b"interaction",
-            ctx.accounts.user.key().as_ref(),
-            ctx.accounts.oracle_context_account.key().as_ref(),
-        ],
-        &solana_gpt_oracle::ID,
-    );
+    // The account only grows to fit results in consume_result, where no one
+    // can pay for it, so the rent for the longest results is deposited now.
+    // consume_result returns whatever the actual results don't need.
+    let rent = Rent::get()?;
+    let result_rent =
+        rent.minimum_balance(request_state.max_size()) - rent.minimum_balance(request_state.size());
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.request_state.to_account_info(),
+            },
+        ),
+        result_rent,
+    )?;
 
-    // 3. Prepare Transaction
-    // The scheduled task will ( call execute_request ) on this program
-    let (compiled_tx, _) = compile_transaction(
-        vec![Instruction {
-            program_id: crate::ID,
-            accounts: vec![
-                AccountMeta::new(request_state.key(), false),
-                AccountMeta::new(ctx.accounts.user.key(), true),
-                AccountMeta::new_readonly(ctx.accounts.oracle_context_account.key(), false),
-                AccountMeta::new(oracle_interaction, false),
-                AccountMeta::new_readonly(solana_gpt_oracle::ID, false),
-                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-            ],
-            data: crate::instruction::ExecuteRequest {
-                prompt: prompt.clone(),
-            }
-            .data(),
-        }],
-        vec![],
-    )
-    .map_err(|_| ProgramError::InvalidInstructionData)?;
+    // The task runs without the user, so this PDA pays for the oracle
+    // interaction. Every run of a recurring request after the first is queued
+    // from consume_result, with this PDA also paying the crank reward and
+    // fronting the task rent tuktuk refunds to it.
+    let request_state = &ctx.accounts.request_state;
+    let requeue_funds = request_state.recurrence.map_or(0, |recurrence| {
+        u64::from(recurrence.max_runs - 1) * CRANK_REWARD + TASK_RENT_RESERVE
+    });
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.task_payer.to_account_info(),
+            },
+        ),
+        rent.minimum_balance(request_state.interaction_size())
+            + requeue_funds
+            + rent.minimum_balance(0),
+    )?;
 
-    // 4. Schedule with Tuktuk
+    // 2. Compile execute_request and schedule it with Tuktuk
     let clock = Clock::get()?;
     let trigger_time = clock.unix_timestamp + delay;
+    let task_args = execute_request_task(
+        ctx.accounts.request_state.key(),
+        &ctx.accounts.request_state,
+        ctx.accounts.user.key(),
+        trigger_time,
+    )?;
 
     queue_task_v0(
         CpiContext::new_with_signer(
@@ -120,16 +197,98 @@ pub fn schedule_request(
             },
             &[&[b"queue_authority", &[ctx.bumps.queue_authority]]],
         ),
-        QueueTaskArgsV0 {
-            trigger: TriggerV0::Timestamp(trigger_time),
-            transaction: TransactionSourceV0::CompiledV0(compiled_tx),
-            crank_reward: Some(1000000),
-            free_tasks: 0,
-            id: task_id,
-            description: "GPT Oracle Request".to_string(),
-        },
+        task_args,
     )?;
 
-    msg!("Scheduled GPT Request Task #{}", task_id);
+    msg!("Scheduled GPT Request #{} as Task #{}", request_id, task_id);
     Ok(())
 }
+
+/// Queue args for the task that runs execute_request on `request_state` at `trigger_time`.
+/// Used for the first run here and for every later run of a recurring request.
+pub(crate) fn execute_request_task(
+    request_key: Pubkey,
+    request_state: &GptRequest,
+    user: Pubkey,
+    trigger_time: i64,
+) -> Result<QueueTaskArgsV0> {
+    let (user_requests, _) =
+        Pubkey::find_program_address(&[b"user_requests", user.as_ref()], &crate::ID);
+    let (task_payer, _) =
+        Pubkey::find_program_address(&[b"task_payer", request_key.as_ref()], &crate::ID);
+
+    // Derive the interaction PDA on the GPT Oracle
+    // seeds: [b"interaction", payer, context_account]
+    let (oracle_interaction, _) = Pubkey::find_program_address(
+        &[
+            b"interaction",
+            task_payer.as_ref(),
+            request_state.context_account.as_ref(),
+        ],
+        &solana_gpt_oracle::ID,
+    );
+
+    // The scheduled task will ( call execute_request ) on this program.
+    // It needs no signers, so any cranker can run it.
+    let (compiled_tx, _) = compile_transaction(
+        vec![Instruction {
+            program_id: crate::ID,
+            accounts: vec![
+                AccountMeta::new(request_key, false),
+                AccountMeta::new_readonly(user_requests, false),
+                AccountMeta::new_readonly(user, false),
+                AccountMeta::new(task_payer, false),
+                AccountMeta::new_readonly(request_state.context_account, false),
+                AccountMeta::new(oracle_interaction, false),
+                AccountMeta::new_readonly(solana_gpt_oracle::ID, false),
+                AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            ],
+            data: crate::instruction::ExecuteRequest {}.data(),
+        }],
+        vec![],
+    )
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    Ok(QueueTaskArgsV0 {
+        trigger: TriggerV0::Timestamp(trigger_time),
+        transaction: TransactionSourceV0::CompiledV0(compiled_tx),
+        crank_reward: Some(CRANK_REWARD),
+        free_tasks: 0,
+        id: request_state.task_id,
+        description: "GPT Oracle Request".to_string(),
+    })
+}
+
+/// Accounts consume_result needs to re-queue a recurring request, in the order
+/// execute_request puts them in the callback, as (address, is_writable)
+pub(crate) fn requeue_accounts(
+    request_key: Pubkey,
+    recurrence: &Recurrence,
+    task_id: u16,
+) -> [(Pubkey, bool); REQUEUE_ACCOUNTS] {
+    let (queue_authority, _) = Pubkey::find_program_address(&[b"queue_authority"], &crate::ID);
+    let (task_queue_authority, _) = Pubkey::find_program_address(
+        &[
+            b"task_queue_authority",
+            recurrence.task_queue.as_ref(),
+            queue_authority.as_ref(),
+        ],
+        &tuktuk_program::tuktuk::ID,
+    );
+    let (task, _) = Pubkey::find_program_address(
+        &[
+            b"task",
+            recurrence.task_queue.as_ref(),
+            &task_id.to_le_bytes(),
+        ],
+        &tuktuk_program::tuktuk::ID,
+    );
+
+    [
+        (recurrence.task_queue, true),
+        (task_queue_authority, false),
+        (task, true),
+        (queue_authority, true),
+        (tuktuk_program::tuktuk::ID, false),
+    ]
+}
@@ -4,10 +4,13 @@ declare_id!("3LWmo92AxMjU5tLjfneoqCYVMQSoA6teNeYhSiQojpSG");
 //solana gpt LLMrieZMpbJFwN52WgmBNMxYojrpRVYXdC1RCweEbab
 //tuktuk devnet LLMrieZMpbJFwN52WgmBNMxYojrpRVYXdC1RCweEbab
 
+pub mod errors;
 pub mod instructions;
 pub mod state;
+mod tests;
 
 use instructions::*;
+use state::{FollowUp, RecurrenceArgs};
 
 #[program]
 pub mod gpt_tuktuk {
@@ -22,15 +25,26 @@ pub mod gpt_tuktuk {
         task_id: u16,
         prompt: String,
         delay: i64,
+        follow_up: Option<FollowUp>,
+        recurrence: Option<RecurrenceArgs>,
     ) -> Result<()> {
-        instructions::schedule_request(ctx, task_id, prompt, delay)
+        instructions::schedule_request(ctx, task_id, prompt, delay, follow_up, recurrence)
     }
 
-    pub fn execute_request(ctx: Context<ExecuteRequest>, prompt: String) -> Result<()> {
-        instructions::execute_request(ctx, prompt)
+    pub fn execute_request(ctx: Context<ExecuteRequest>) -> Result<()> {
+        instructions::execute_request(ctx)
     }
 
-    pub fn consume_result(ctx: Context<ConsumeResult>, result: String) -> Result<()> {
+    pub fn consume_result<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ConsumeResult<'info>>,
+        result: String,
+    ) -> Result<()> {
         instructions::consume_result(ctx, result)
     }
+
+    pub fn run_follow_up<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RunFollowUp<'info>>,
+    ) -> Result<()> {
+        instructions::run_follow_up(ctx)
+    }
 }
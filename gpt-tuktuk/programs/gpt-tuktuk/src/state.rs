@@ -3,18 +3,244 @@ use anchor_lang::prelude::*;
 /// The GPT Oracle Program ID
 pub const GPT_ORACLE_PROGRAM_ID: Pubkey = solana_gpt_oracle::ID_CONST;
 
+/// Longest prompt `schedule_request` accepts, in bytes
+pub const MAX_PROMPT_LEN: usize = 512;
+
+/// Longest result `consume_result` accepts, in bytes
+pub const MAX_RESULT_LEN: usize = 4096;
+
+/// Most accounts a follow-up instruction can take
+pub const MAX_FOLLOW_UP_ACCOUNTS: usize = 8;
+
+/// Longest follow-up data prefix, in bytes
+pub const MAX_FOLLOW_UP_PREFIX_LEN: usize = 64;
+
+/// How many recent results a recurring request keeps in `history`
+pub const HISTORY_LEN: usize = 3;
+
+/// Lamports paid to the cranker for each execute_request task
+pub const CRANK_REWARD: u64 = 1_000_000;
+
+/// Lamports a recurring request sets aside for its re-queued task's rent.
+/// Tuktuk refunds the rent after every run, so one task's worth is enough.
+pub const TASK_RENT_RESERVE: u64 = 10_000_000;
+
+/// Accounts every callback carries, after the oracle identity:
+/// request_state, user, user_requests, task_payer, system_program
+pub const CALLBACK_ACCOUNTS: usize = 5;
+
+/// Accounts a recurring request's callback also carries to re-queue it:
+/// task_queue, task_queue_authority, task, queue_authority, tuktuk
+pub const REQUEUE_ACCOUNTS: usize = 5;
+
+/// How many recent requests `UserRequests` keeps track of
+pub const MAX_RECENT_REQUESTS: usize = 16;
+
 #[account]
 pub struct GptRequest {
-    pub task_id: u16,
+    pub request_id: u64, // Allocated from the user's `UserRequests` counter
+    pub task_id: u16,    // The Tuktuk task slot that runs execute_request
     pub prompt: String,
     pub result: Option<String>,
     pub is_completed: bool,
     pub context_account: Pubkey, // The LLM context account on the GPT Oracle
     pub bump: u8,
+    pub follow_up: Option<FollowUp>, // Invoked by run_follow_up with the result
+    pub follow_up_pending: bool,     // Set by consume_result, cleared by run_follow_up
+    pub recurrence: Option<Recurrence>, // Set for requests that re-run every interval
+    pub history: Vec<HistoryEntry>, // Last HISTORY_LEN results of a recurring request, oldest first
 }
 
 impl GptRequest {
-    // 8 discriminator + 2 task_id + (4 + 200) prompt + (1 + 4 + 200) result option
-    // + 1 is_completed + 32 context_account + 1 bump
-    pub const LEN: usize = 8 + 2 + 4 + 200 + 1 + 4 + 200 + 1 + 32 + 1;
+    /// Account size for a prompt of `prompt_len` bytes, the registered follow-up,
+    /// an empty history and, once it has arrived, a result of `result_len` bytes
+    // 8 discriminator + 8 request_id + 2 task_id + (4 + prompt_len) prompt
+    // + (1 + 4 + result_len) result option + 1 is_completed + 32 context_account + 1 bump
+    // + (1 + follow_up) follow_up option + 1 follow_up_pending + (1 + 45) recurrence option
+    // + 4 history
+    pub fn space(
+        prompt_len: usize,
+        result_len: Option<usize>,
+        follow_up: Option<&FollowUp>,
+        recurring: bool,
+    ) -> usize {
+        let result = result_len.map_or(1, |len| 1 + 4 + len);
+        let follow_up = follow_up.map_or(1, |follow_up| 1 + follow_up.size());
+        let recurrence = if recurring { 1 + Recurrence::SIZE } else { 1 };
+        8 + 8 + 2 + 4 + prompt_len + result + 1 + 32 + 1 + follow_up + 1 + recurrence + 4
+    }
+
+    /// Bytes needed to hold the request as it is now
+    pub fn size(&self) -> usize {
+        Self::space(
+            self.prompt.len(),
+            self.result.as_ref().map(String::len),
+            self.follow_up.as_ref(),
+            self.recurrence.is_some(),
+        ) + self
+            .history
+            .iter()
+            .map(|entry| HistoryEntry::space(entry.result.len()))
+            .sum::<usize>()
+    }
+
+    /// Bytes the request can grow to: a MAX_RESULT_LEN result and, when recurring,
+    /// a full history of them
+    pub fn max_size(&self) -> usize {
+        let history = if self.recurrence.is_some() {
+            HISTORY_LEN * HistoryEntry::space(MAX_RESULT_LEN)
+        } else {
+            0
+        };
+        Self::space(
+            self.prompt.len(),
+            Some(MAX_RESULT_LEN),
+            self.follow_up.as_ref(),
+            self.recurrence.is_some(),
+        ) + history
+    }
+
+    /// Size of the GPT Oracle's `Interaction` account for this request, which
+    /// the task payer creates in execute_request
+    // 8 discriminator + 32 context + 32 user + (4 + prompt_len) text + 32 callback_program_id
+    // + 8 callback_discriminator + (4 + metas * (32 + 1 + 1)) callback_account_metas
+    // + 1 is_processed
+    pub fn interaction_size(&self) -> usize {
+        let metas = if self.recurrence.is_some() {
+            CALLBACK_ACCOUNTS + REQUEUE_ACCOUNTS
+        } else {
+            CALLBACK_ACCOUNTS
+        };
+        8 + 32 + 32 + 4 + self.prompt.len() + 32 + 8 + 4 + metas * (32 + 1 + 1) + 1
+    }
+
+    /// Adds a recurring run's result, dropping the oldest once HISTORY_LEN are kept
+    pub fn push_history(&mut self, entry: HistoryEntry) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.remove(0);
+        }
+        self.history.push(entry);
+    }
+}
+
+/// What the scheduler asks for: run every `interval` seconds, `max_runs` times in total
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecurrenceArgs {
+    pub interval: i64,
+    pub max_runs: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub interval: i64,
+    pub max_runs: u16,
+    pub runs: u16,             // Runs whose result has arrived
+    pub task_queue: Pubkey,    // Queue the next run is queued on
+    pub awaiting_result: bool, // Set by execute_request, cleared by consume_result
+}
+
+impl Recurrence {
+    // 8 interval + 2 max_runs + 2 runs + 32 task_queue + 1 awaiting_result
+    pub const SIZE: usize = 8 + 2 + 2 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub run: u16, // 1 for the first run
+    pub completed_at: i64,
+    pub result: String,
+}
+
+impl HistoryEntry {
+    // 2 run + 8 completed_at + (4 + result_len) result
+    pub fn space(result_len: usize) -> usize {
+        2 + 8 + 4 + result_len
+    }
+}
+
+/// An instruction run_follow_up invokes once the result arrives. Its data is
+/// `data_prefix` followed by the Borsh-encoded result `String`, so an Anchor
+/// handler taking `result: String` can use its discriminator as the prefix.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FollowUp {
+    pub program_id: Pubkey,
+    pub accounts: Vec<FollowUpAccount>,
+    pub data_prefix: Vec<u8>,
+}
+
+/// Follow-ups can't ask for signers. The one exception is the request's own
+/// `GptRequest` account, which is signed for so the follow-up can tell the
+/// call came from this program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FollowUpAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+impl FollowUp {
+    // 32 program_id + (4 + accounts * (32 pubkey + 1 is_writable)) accounts + (4 + prefix) data_prefix
+    pub fn size(&self) -> usize {
+        32 + 4 + self.accounts.len() * (32 + 1) + 4 + self.data_prefix.len()
+    }
+}
+
+/// Per-user request registry: hands out request IDs and remembers the status
+/// of the most recent requests, so clients can list them without
+/// `getProgramAccounts`
+#[account]
+pub struct UserRequests {
+    pub user: Pubkey,
+    pub next_request_id: u64,
+    pub recent: Vec<RequestEntry>, // Ring buffer of at most MAX_RECENT_REQUESTS entries
+    pub head: u8,                  // Slot overwritten next once the ring is full
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestEntry {
+    pub request_id: u64,
+    pub status: RequestStatus,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestStatus {
+    Pending,
+    Completed,
+}
+
+impl UserRequests {
+    // 8 discriminator + 32 user + 8 next_request_id
+    // + (4 + MAX_RECENT_REQUESTS * (8 request_id + 1 status)) recent + 1 head + 1 bump
+    pub const LEN: usize = 8 + 32 + 8 + 4 + MAX_RECENT_REQUESTS * (8 + 1) + 1 + 1;
+
+    /// Takes the next request ID and records it as pending,
+    /// evicting the oldest entry once the ring is full
+    pub fn allocate(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let entry = RequestEntry {
+            request_id,
+            status: RequestStatus::Pending,
+        };
+        if self.recent.len() < MAX_RECENT_REQUESTS {
+            self.recent.push(entry);
+        } else {
+            self.recent[self.head as usize] = entry;
+            self.head = ((self.head as usize + 1) % MAX_RECENT_REQUESTS) as u8;
+        }
+
+        request_id
+    }
+
+    /// Updates the status of `request_id` if it is still in the ring
+    pub fn set_status(&mut self, request_id: u64, status: RequestStatus) {
+        if let Some(entry) = self
+            .recent
+            .iter_mut()
+            .find(|entry| entry.request_id == request_id)
+        {
+            entry.status = status;
+        }
+    }
 }
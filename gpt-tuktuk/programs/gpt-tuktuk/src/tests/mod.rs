@@ -0,0 +1,937 @@
+#[cfg(test)]
+mod tests {
+
+    use {
+        crate::{
+            errors::GptError,
+            state::{
+                FollowUp, FollowUpAccount, GptRequest, RecurrenceArgs, RequestStatus, UserRequests,
+                HISTORY_LEN, MAX_FOLLOW_UP_ACCOUNTS, MAX_RESULT_LEN,
+            },
+        },
+        anchor_lang::{
+            prelude::{msg, Clock},
+            AccountDeserialize, InstructionData, ToAccountMetas,
+        },
+        litesvm::{
+            types::{FailedTransactionMetadata, TransactionMetadata},
+            LiteSVM,
+        },
+        solana_instruction::{error::InstructionError, AccountMeta, Instruction},
+        solana_keypair::Keypair,
+        solana_message::Message,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_pubkey::Pubkey,
+        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_signer::Signer,
+        solana_transaction::Transaction,
+        solana_transaction_error::TransactionError,
+        std::path::PathBuf,
+        tuktuk_program::{
+            tuktuk::{types::TriggerV0, ID as TUKTUK_PROGRAM_ID},
+            TransactionSourceV0,
+        },
+    };
+
+    static PROGRAM_ID: Pubkey = crate::ID;
+    static ORACLE_PROGRAM_ID: Pubkey = gpt_oracle_mock::ID;
+    // SPL Memo, loaded by LiteSVM. It requires every account it's given to sign.
+    static MEMO_PROGRAM_ID: Pubkey =
+        Pubkey::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+    const PROMPT: &str = "What is Solana?";
+
+    // Deploys gpt-tuktuk with the oracle and tuktuk mocks at the real addresses,
+    // and funds the user who schedules requests
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut program = LiteSVM::new();
+        let user = Keypair::new();
+        program
+            .airdrop(&user.pubkey(), 50 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to user");
+
+        let deploy = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy");
+        for (program_id, so_path) in [
+            (PROGRAM_ID, deploy.join("gpt_tuktuk.so")),
+            (ORACLE_PROGRAM_ID, deploy.join("gpt_oracle_mock.so")),
+            // Built by `anchor build` in escrow-litesvm-tuktuk
+            (
+                TUKTUK_PROGRAM_ID,
+                deploy.join("../../../escrow-litesvm-tuktuk/target/deploy/tuktuk_mock.so"),
+            ),
+        ] {
+            let program_data = std::fs::read(&so_path)
+                .unwrap_or_else(|_| panic!("Failed to read {}", so_path.display()));
+            program.add_program(program_id, &program_data);
+        }
+
+        // The oracle's identity PDA signs every callback
+        let initialize_ix = Instruction {
+            program_id: ORACLE_PROGRAM_ID,
+            accounts: gpt_oracle_mock::accounts::Initialize {
+                payer: user.pubkey(),
+                identity: find_identity(),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: gpt_oracle_mock::instruction::Initialize {}.data(),
+        };
+        let message = Message::new(&[initialize_ix], Some(&user.pubkey()));
+        let transaction = Transaction::new(&[&user], message, program.latest_blockhash());
+        program.send_transaction(transaction).unwrap();
+
+        (program, user)
+    }
+
+    fn find_identity() -> Pubkey {
+        Pubkey::find_program_address(&[b"identity"], &ORACLE_PROGRAM_ID).0
+    }
+
+    fn find_user_requests(user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"user_requests", user.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn find_request(user: &Pubkey, request_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"gpt_request", user.as_ref(), &request_id.to_le_bytes()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn find_task_payer(request_state: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"task_payer", request_state.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn find_queue_authority() -> Pubkey {
+        Pubkey::find_program_address(&[b"queue_authority"], &PROGRAM_ID).0
+    }
+
+    fn find_task_queue_authority(task_queue: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"task_queue_authority",
+                task_queue.as_ref(),
+                find_queue_authority().as_ref(),
+            ],
+            &TUKTUK_PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn find_task(task_queue: &Pubkey, task_id: u16) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"task", task_queue.as_ref(), &task_id.to_le_bytes()],
+            &TUKTUK_PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn find_interaction(payer: &Pubkey, context_account: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"interaction", payer.as_ref(), context_account.as_ref()],
+            &ORACLE_PROGRAM_ID,
+        )
+        .0
+    }
+
+    fn task_state(program: &LiteSVM, task: &Pubkey) -> tuktuk_mock::TaskV0 {
+        let task_account = program.get_account(task).unwrap();
+        tuktuk_mock::TaskV0::try_deserialize(&mut task_account.data.as_ref()).unwrap()
+    }
+
+    fn request_state(program: &LiteSVM, request_state: &Pubkey) -> GptRequest {
+        let account = program.get_account(request_state).unwrap();
+        GptRequest::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    fn interaction_state(program: &LiteSVM, interaction: &Pubkey) -> gpt_oracle_mock::Interaction {
+        let account = program.get_account(interaction).unwrap();
+        gpt_oracle_mock::Interaction::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    fn warp_to(program: &mut LiteSVM, timestamp: i64) {
+        let mut clock: Clock = program.get_sysvar();
+        msg!(
+            "Travelled into future: {} -> {}",
+            clock.unix_timestamp,
+            timestamp
+        );
+        clock.unix_timestamp = timestamp;
+        program.set_sysvar(&clock);
+    }
+
+    fn now(program: &LiteSVM) -> i64 {
+        program.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    // Asserts the transaction failed with the given gpt-tuktuk error
+    fn assert_gpt_error<T: std::fmt::Debug>(
+        result: Result<T, FailedTransactionMetadata>,
+        error: GptError,
+    ) {
+        let failed = result.expect_err("Transaction should fail");
+        assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(error as u32 + anchor_lang::error::ERROR_CODE_OFFSET)
+            )
+        );
+    }
+
+    // Address the user's next scheduled request gets
+    fn next_request(program: &LiteSVM, user: &Pubkey) -> Pubkey {
+        let request_id = program
+            .get_account(&find_user_requests(user))
+            .map(|account| {
+                UserRequests::try_deserialize(&mut account.data.as_ref())
+                    .unwrap()
+                    .next_request_id
+            })
+            .unwrap_or(0);
+        find_request(user, request_id)
+    }
+
+    // Schedules the user's next request on `task_id` of `task_queue`,
+    // returning its `GptRequest` address
+    #[allow(clippy::too_many_arguments)]
+    fn try_schedule_request(
+        program: &mut LiteSVM,
+        user: &Keypair,
+        task_queue: Pubkey,
+        task_id: u16,
+        context_account: Pubkey,
+        delay: i64,
+        follow_up: Option<FollowUp>,
+        recurrence: Option<RecurrenceArgs>,
+    ) -> Result<Pubkey, FailedTransactionMetadata> {
+        let user_requests = find_user_requests(&user.pubkey());
+        let request_state = next_request(program, &user.pubkey());
+
+        let schedule_request_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ScheduleRequest {
+                user_requests,
+                request_state,
+                user: user.pubkey(),
+                task_payer: find_task_payer(&request_state),
+                task_queue,
+                task_queue_authority: find_task_queue_authority(&task_queue),
+                task: find_task(&task_queue, task_id),
+                queue_authority: find_queue_authority(),
+                oracle_context_account: context_account,
+                system_program: SYSTEM_PROGRAM_ID,
+                tuktuk_program: TUKTUK_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ScheduleRequest {
+                task_id,
+                prompt: PROMPT.to_string(),
+                delay,
+                follow_up,
+                recurrence,
+            }
+            .data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[schedule_request_ix], Some(&user.pubkey()));
+        let transaction = Transaction::new(&[user], message, program.latest_blockhash());
+        program.send_transaction(transaction)?;
+        Ok(request_state)
+    }
+
+    // Sends execute_request for `user`'s request, with `caller` paying the fee
+    fn try_execute_request(
+        program: &mut LiteSVM,
+        caller: &Keypair,
+        user: Pubkey,
+        request_state: Pubkey,
+        context_account: Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let task_payer = find_task_payer(&request_state);
+        let execute_request_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ExecuteRequest {
+                request_state,
+                user_requests: find_user_requests(&user),
+                user,
+                task_payer,
+                oracle_context_account: context_account,
+                oracle_interaction: find_interaction(&task_payer, &context_account),
+                gpt_oracle_program: ORACLE_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ExecuteRequest {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[execute_request_ix], Some(&caller.pubkey()));
+        let transaction = Transaction::new(&[caller], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Answers an interaction through the oracle mock's `callback_from_llm`,
+    // passing the callback accounts execute_request registered
+    fn try_respond(
+        program: &mut LiteSVM,
+        agent: &Keypair,
+        interaction: Pubkey,
+        response: String,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let interaction_data = interaction_state(program, &interaction);
+
+        let mut accounts = gpt_oracle_mock::accounts::CallbackFromLlm {
+            payer: agent.pubkey(),
+            identity: find_identity(),
+            interaction,
+            program: interaction_data.callback_program_id,
+        }
+        .to_account_metas(None);
+        accounts.extend(interaction_data.callback_account_metas.iter().map(|meta| {
+            if meta.is_writable {
+                AccountMeta::new(meta.pubkey, false)
+            } else {
+                AccountMeta::new_readonly(meta.pubkey, false)
+            }
+        }));
+
+        let callback_ix = Instruction {
+            program_id: ORACLE_PROGRAM_ID,
+            accounts,
+            data: gpt_oracle_mock::instruction::CallbackFromLlm { response }.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[callback_ix], Some(&agent.pubkey()));
+        let transaction = Transaction::new(&[agent], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Cranks a queued task through the tuktuk mock's `run_task_v0` the way a
+    // real cranker does, reading the accounts off the task's compiled transaction
+    fn crank_task(
+        program: &mut LiteSVM,
+        crank_turner: &Keypair,
+        task_queue: Pubkey,
+        task_id: u16,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let task = find_task(&task_queue, task_id);
+        let task_data = task_state(program, &task);
+        let TransactionSourceV0::CompiledV0(transaction) = task_data.transaction else {
+            panic!("Task has no compiled transaction");
+        };
+
+        let num_signers = (transaction.num_rw_signers + transaction.num_ro_signers) as usize;
+        let num_rw = transaction.num_rw as usize;
+        let mut accounts = tuktuk_mock::accounts::RunTaskV0 {
+            crank_turner: crank_turner.pubkey(),
+            rent_refund: task_data.rent_refund,
+            task_queue,
+            task,
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            transaction
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(index, account)| {
+                    if index < transaction.num_rw_signers as usize
+                        || (index >= num_signers && index < num_signers + num_rw)
+                    {
+                        AccountMeta::new(*account, false)
+                    } else {
+                        AccountMeta::new_readonly(*account, false)
+                    }
+                }),
+        );
+
+        let run_task_ix = Instruction {
+            program_id: TUKTUK_PROGRAM_ID,
+            accounts,
+            data: tuktuk_mock::instruction::RunTaskV0 {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[run_task_ix], Some(&crank_turner.pubkey()));
+        let transaction = Transaction::new(&[crank_turner], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // Sends run_follow_up from `caller` with `accounts` as the follow-up accounts
+    fn try_run_follow_up(
+        program: &mut LiteSVM,
+        caller: &Keypair,
+        user: Pubkey,
+        request_state: Pubkey,
+        accounts: Vec<AccountMeta>,
+    ) -> Result<TransactionMetadata, FailedTransactionMetadata> {
+        let mut metas = crate::accounts::RunFollowUp {
+            request_state,
+            user,
+        }
+        .to_account_metas(None);
+        metas.extend(accounts);
+
+        let run_follow_up_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: metas,
+            data: crate::instruction::RunFollowUp {}.data(),
+        };
+
+        program.expire_blockhash();
+        let message = Message::new(&[run_follow_up_ix], Some(&caller.pubkey()));
+        let transaction = Transaction::new(&[caller], message, program.latest_blockhash());
+        program.send_transaction(transaction)
+    }
+
+    // A follow-up that logs the result as a memo, signed by `signer`
+    fn memo_follow_up(signer: Pubkey) -> FollowUp {
+        FollowUp {
+            program_id: MEMO_PROGRAM_ID,
+            accounts: vec![FollowUpAccount {
+                pubkey: signer,
+                is_writable: false,
+            }],
+            data_prefix: vec![],
+        }
+    }
+
+    // The accounts run_follow_up takes for `memo_follow_up(signer)`
+    fn memo_follow_up_accounts(signer: Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(signer, false),
+            AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+        ]
+    }
+
+    #[test]
+    fn test_execute_request_sends_stored_prompt() {
+        let (mut program, user) = setup();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+        let interaction_data = interaction_state(&program, &interaction);
+        assert_eq!(interaction_data.text, PROMPT);
+        assert_eq!(interaction_data.callback_program_id, PROGRAM_ID);
+    }
+
+    #[test]
+    fn test_consume_result_rejects_oversized_result() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+
+        let result = try_respond(
+            &mut program,
+            &agent,
+            interaction,
+            "a".repeat(MAX_RESULT_LEN + 1),
+        );
+        assert_gpt_error(result, GptError::ResultTooLong);
+        assert!(request_state(&program, &request).result.is_none());
+
+        // The longest result allowed still fits
+        try_respond(
+            &mut program,
+            &agent,
+            interaction,
+            "a".repeat(MAX_RESULT_LEN),
+        )
+        .unwrap();
+        let request_data = request_state(&program, &request);
+        assert_eq!(request_data.result.unwrap().len(), MAX_RESULT_LEN);
+        assert!(request_data.is_completed);
+    }
+
+    #[test]
+    fn test_consume_result_resizes_and_returns_rent_surplus() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            None,
+            None,
+        )
+        .unwrap();
+
+        // schedule_request deposits the rent a MAX_RESULT_LEN result needs
+        let request_data = request_state(&program, &request);
+        let scheduled = program.get_account(&request).unwrap();
+        assert_eq!(scheduled.data.len(), request_data.size());
+        assert_eq!(
+            scheduled.lamports,
+            program.minimum_balance_for_rent_exemption(request_data.max_size())
+        );
+
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+        let user_before = program.get_balance(&user.pubkey()).unwrap();
+        let task_payer_before = program.get_balance(&find_task_payer(&request)).unwrap();
+
+        let answer = "Solana is a fast blockchain.".to_string();
+        try_respond(&mut program, &agent, interaction, answer.clone()).unwrap();
+
+        // The account shrinks to fit the actual result, and the rest of the
+        // deposit goes back to the user, along with what the task payer has left
+        let request_data = request_state(&program, &request);
+        assert_eq!(request_data.result.as_deref(), Some(answer.as_str()));
+        let answered = program.get_account(&request).unwrap();
+        assert_eq!(answered.data.len(), request_data.size());
+        assert_eq!(
+            answered.data.len(),
+            GptRequest::space(PROMPT.len(), Some(answer.len()), None, false)
+        );
+        let rent = program.minimum_balance_for_rent_exemption(answered.data.len());
+        assert_eq!(answered.lamports, rent);
+        assert_eq!(
+            program.get_balance(&user.pubkey()).unwrap(),
+            user_before + scheduled.lamports - rent + task_payer_before
+        );
+    }
+
+    #[test]
+    fn test_follow_up_runs_once_after_result() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        let cranker = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        program
+            .airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        // The request signs the memo, so the memo proves where the call came from
+        let request = next_request(&program, &user.pubkey());
+        try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            Some(memo_follow_up(request)),
+            None,
+        )
+        .unwrap();
+
+        // Nothing to follow up on before the result is in
+        let result = try_run_follow_up(
+            &mut program,
+            &cranker,
+            user.pubkey(),
+            request,
+            memo_follow_up_accounts(request),
+        );
+        assert_gpt_error(result, GptError::NoFollowUpPending);
+
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+        let answer = "Solana is a fast blockchain.".to_string();
+        try_respond(&mut program, &agent, interaction, answer.clone()).unwrap();
+        assert!(request_state(&program, &request).follow_up_pending);
+
+        // Anyone can run it, with the result as the memo
+        let tx = try_run_follow_up(
+            &mut program,
+            &cranker,
+            user.pubkey(),
+            request,
+            memo_follow_up_accounts(request),
+        )
+        .unwrap();
+        assert!(tx.logs.iter().any(|log| log.contains(&answer)));
+        assert!(!request_state(&program, &request).follow_up_pending);
+
+        // It runs once per result
+        let result = try_run_follow_up(
+            &mut program,
+            &cranker,
+            user.pubkey(),
+            request,
+            memo_follow_up_accounts(request),
+        );
+        assert_gpt_error(result, GptError::NoFollowUpPending);
+
+        // And a replayed callback can't bring it back
+        let result = try_respond(&mut program, &agent, interaction, answer);
+        assert_gpt_error(result, GptError::AlreadyCompleted);
+        assert!(!request_state(&program, &request).follow_up_pending);
+    }
+
+    #[test]
+    fn test_failing_follow_up_keeps_result() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        // The program can't sign for the user, so the memo always fails
+        try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            Some(memo_follow_up(user.pubkey())),
+            None,
+        )
+        .unwrap();
+        let request = find_request(&user.pubkey(), 0);
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+        try_respond(&mut program, &agent, interaction, "Answer".to_string()).unwrap();
+
+        let result = try_run_follow_up(
+            &mut program,
+            &agent,
+            user.pubkey(),
+            request,
+            memo_follow_up_accounts(user.pubkey()),
+        );
+        assert!(result.is_err());
+
+        // The result is stored and the follow-up can still be retried
+        let request_data = request_state(&program, &request);
+        assert_eq!(request_data.result.as_deref(), Some("Answer"));
+        assert!(request_data.is_completed);
+        assert!(request_data.follow_up_pending);
+    }
+
+    #[test]
+    fn test_run_follow_up_rejects_other_accounts() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = next_request(&program, &user.pubkey());
+        try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            Some(memo_follow_up(request)),
+            None,
+        )
+        .unwrap();
+        try_execute_request(&mut program, &user, user.pubkey(), request, context_account).unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+        try_respond(&mut program, &agent, interaction, "Answer".to_string()).unwrap();
+
+        for accounts in [
+            // Another account in place of the registered one
+            memo_follow_up_accounts(Pubkey::new_unique()),
+            // Another program
+            vec![
+                AccountMeta::new_readonly(request, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+            // Missing the registered account
+            vec![AccountMeta::new_readonly(MEMO_PROGRAM_ID, false)],
+            vec![],
+        ] {
+            let result = try_run_follow_up(&mut program, &agent, user.pubkey(), request, accounts);
+            assert_gpt_error(result, GptError::FollowUpAccountMismatch);
+        }
+        assert!(request_state(&program, &request).follow_up_pending);
+    }
+
+    #[test]
+    fn test_schedule_rejects_invalid_follow_up() {
+        let (mut program, user) = setup();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+        let request = next_request(&program, &user.pubkey());
+
+        // Calling back into gpt-tuktuk
+        let self_cpi = FollowUp {
+            program_id: PROGRAM_ID,
+            ..memo_follow_up(request)
+        };
+        // One account over MAX_FOLLOW_UP_ACCOUNTS
+        let too_many_accounts = FollowUp {
+            accounts: vec![
+                FollowUpAccount {
+                    pubkey: request,
+                    is_writable: false,
+                };
+                MAX_FOLLOW_UP_ACCOUNTS + 1
+            ],
+            ..memo_follow_up(request)
+        };
+
+        for follow_up in [self_cpi, too_many_accounts] {
+            let result = try_schedule_request(
+                &mut program,
+                &user,
+                task_queue,
+                0,
+                context_account,
+                0,
+                Some(follow_up),
+                None,
+            );
+            assert_gpt_error(result, GptError::InvalidFollowUp);
+        }
+    }
+
+    #[test]
+    fn test_cranker_runs_request_without_user() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        let cranker = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        program
+            .airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            60,
+            None,
+            None,
+        )
+        .unwrap();
+        let task_payer = find_task_payer(&request);
+
+        // The compiled task asks for no signatures
+        let task = find_task(&task_queue, 0);
+        let TransactionSourceV0::CompiledV0(transaction) = task_state(&program, &task).transaction
+        else {
+            panic!("Task has no compiled transaction");
+        };
+        assert_eq!(transaction.num_rw_signers + transaction.num_ro_signers, 0);
+
+        assert!(crank_task(&mut program, &cranker, task_queue, 0).is_err());
+        warp_to(&mut program, now(&program) + 60);
+        crank_task(&mut program, &cranker, task_queue, 0).unwrap();
+        assert_eq!(program.get_balance(&task).unwrap_or(0), 0);
+
+        // The task payer paid for the interaction, with the stored prompt
+        let interaction = find_interaction(&task_payer, &context_account);
+        let interaction_data = interaction_state(&program, &interaction);
+        assert_eq!(interaction_data.user, task_payer);
+        assert_eq!(interaction_data.text, PROMPT);
+
+        // Once the request is done, the user gets back what the task payer has left
+        let user_before = program.get_balance(&user.pubkey()).unwrap();
+        let task_payer_before = program.get_balance(&task_payer).unwrap();
+        let request_before = program.get_balance(&request).unwrap();
+        try_respond(&mut program, &agent, interaction, "Answer".to_string()).unwrap();
+
+        assert!(request_state(&program, &request).is_completed);
+        assert_eq!(program.get_balance(&task_payer).unwrap_or(0), 0);
+        let request_after = program.get_balance(&request).unwrap();
+        assert_eq!(
+            program.get_balance(&user.pubkey()).unwrap(),
+            user_before + task_payer_before + request_before - request_after
+        );
+
+        // A completed request can't be sent to the oracle again
+        let result = try_execute_request(
+            &mut program,
+            &cranker,
+            user.pubkey(),
+            request,
+            context_account,
+        );
+        assert_gpt_error(result, GptError::AlreadyCompleted);
+    }
+
+    #[test]
+    fn test_recurring_request_requeues_until_last_run() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        let cranker = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        program
+            .airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        // Enough runs for the history to roll over twice
+        let max_runs = HISTORY_LEN as u16 + 2;
+        let interval = 3600;
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            60,
+            None,
+            Some(RecurrenceArgs { interval, max_runs }),
+        )
+        .unwrap();
+        let task_payer = find_task_payer(&request);
+        let interaction = find_interaction(&task_payer, &context_account);
+        let max_size_rent = program
+            .minimum_balance_for_rent_exemption(request_state(&program, &request).max_size());
+
+        let mut trigger = now(&program) + 60;
+        for run in 1..=max_runs {
+            warp_to(&mut program, trigger);
+            crank_task(&mut program, &cranker, task_queue, 0).unwrap();
+
+            let user_before = program.get_balance(&user.pubkey()).unwrap();
+            let task_payer_before = program.get_balance(&task_payer).unwrap();
+            let request_before = program.get_balance(&request).unwrap();
+            try_respond(&mut program, &agent, interaction, format!("Answer {}", run)).unwrap();
+
+            let request_data = request_state(&program, &request);
+            let recurrence = request_data.recurrence.unwrap();
+            assert_eq!(recurrence.runs, run);
+            assert!(!recurrence.awaiting_result);
+            assert_eq!(request_data.result, Some(format!("Answer {}", run)));
+
+            // The last HISTORY_LEN results, oldest first
+            let first_kept = run.saturating_sub(HISTORY_LEN as u16) + 1;
+            let history: Vec<(u16, String)> = request_data
+                .history
+                .iter()
+                .map(|entry| (entry.run, entry.result.clone()))
+                .collect();
+            let expected: Vec<(u16, String)> = (first_kept..=run)
+                .map(|run| (run, format!("Answer {}", run)))
+                .collect();
+            assert_eq!(history, expected);
+            assert!(request_data
+                .history
+                .iter()
+                .all(|entry| entry.completed_at <= trigger));
+
+            let request_account = program.get_account(&request).unwrap();
+            assert_eq!(request_account.data.len(), request_data.size());
+
+            if run < max_runs {
+                // Re-queued on the same task, paid for by the task payer
+                assert!(!request_data.is_completed);
+                assert_eq!(request_account.lamports, max_size_rent);
+                let task_data = task_state(&program, &find_task(&task_queue, 0));
+                assert_eq!(task_data.rent_refund, task_payer);
+                let TriggerV0::Timestamp(next) = task_data.trigger else {
+                    panic!("Task has no timestamp trigger");
+                };
+                assert_eq!(next, trigger + interval);
+                trigger = next;
+            } else {
+                // The last run settles the task payer and the unused rent to the user
+                assert!(request_data.is_completed);
+                let task = find_task(&task_queue, 0);
+                assert_eq!(program.get_balance(&task).unwrap_or(0), 0);
+                assert_eq!(program.get_balance(&task_payer).unwrap_or(0), 0);
+                assert_eq!(
+                    request_account.lamports,
+                    program.minimum_balance_for_rent_exemption(request_account.data.len())
+                );
+                assert_eq!(
+                    program.get_balance(&user.pubkey()).unwrap(),
+                    user_before + task_payer_before + request_before - request_account.lamports
+                );
+            }
+        }
+
+        let user_requests = program
+            .get_account(&find_user_requests(&user.pubkey()))
+            .unwrap();
+        let user_requests =
+            UserRequests::try_deserialize(&mut user_requests.data.as_ref()).unwrap();
+        assert_eq!(user_requests.recent[0].status, RequestStatus::Completed);
+    }
+
+    #[test]
+    fn test_consume_result_needs_run_in_flight() {
+        let (mut program, user) = setup();
+        let agent = Keypair::new();
+        let cranker = Keypair::new();
+        program.airdrop(&agent.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        program
+            .airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL)
+            .unwrap();
+        let task_queue = Pubkey::new_unique();
+        let context_account = Pubkey::new_unique();
+
+        let request = try_schedule_request(
+            &mut program,
+            &user,
+            task_queue,
+            0,
+            context_account,
+            0,
+            None,
+            Some(RecurrenceArgs {
+                interval: 3600,
+                max_runs: 3,
+            }),
+        )
+        .unwrap();
+        let interaction = find_interaction(&find_task_payer(&request), &context_account);
+
+        crank_task(&mut program, &cranker, task_queue, 0).unwrap();
+        try_respond(&mut program, &agent, interaction, "Answer 1".to_string()).unwrap();
+
+        // The next run hasn't been sent yet, so a replayed answer is refused
+        let result = try_respond(&mut program, &agent, interaction, "Answer 1".to_string());
+        assert_gpt_error(result, GptError::NoRunInFlight);
+        let request_data = request_state(&program, &request);
+        assert_eq!(request_data.recurrence.unwrap().runs, 1);
+        assert_eq!(request_data.history.len(), 1);
+    }
+}
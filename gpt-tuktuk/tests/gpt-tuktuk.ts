@@ -26,25 +26,21 @@ describe("gpt-tuktuk", () => {
   const taskId = 3;
   const prompt = "What is Solana?";
 
-  // Request state PDA
-  const [requestState] = PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("gpt_request"),
-      provider.publicKey.toBuffer(),
-      new anchor.BN(taskId).toArrayLike(Buffer, 'le', 2)
-    ],
+  // Per-user registry that allocates request IDs
+  const [userRequests] = PublicKey.findProgramAddressSync(
+    [Buffer.from("user_requests"), provider.publicKey.toBuffer()],
     program.programId
   );
 
-  // Oracle interaction PDA
-  const [oracleInteraction] = PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("interaction"),
-      provider.publicKey.toBuffer(),
-      ORACLE_CONTEXT_ACCOUNT.toBuffer(),
-    ],
-    GPT_ORACLE_PROGRAM_ID
-  );
+  // Request state PDA, keyed by the request ID the program allocates.
+  // Set in the scheduling test from the registry's counter.
+  let requestId: anchor.BN;
+  let requestState: PublicKey;
+
+  // The request's task payer pays for, and so seeds, the oracle interaction.
+  // Both are set in the scheduling test.
+  let taskPayer: PublicKey;
+  let oracleInteraction: PublicKey;
 
   it("Is initialized!", async () => {
     try {
@@ -75,9 +71,34 @@ describe("gpt-tuktuk", () => {
       TUKTUK_PROGRAM_ID
     );
 
+    // The next ID is 0 until the registry exists
+    const registry = await program.account.userRequests.fetchNullable(userRequests);
+    requestId = registry ? registry.nextRequestId : new anchor.BN(0);
+    [requestState] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gpt_request"),
+        provider.publicKey.toBuffer(),
+        requestId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    [taskPayer] = PublicKey.findProgramAddressSync(
+      [Buffer.from("task_payer"), requestState.toBuffer()],
+      program.programId
+    );
+    [oracleInteraction] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("interaction"),
+        taskPayer.toBuffer(),
+        ORACLE_CONTEXT_ACCOUNT.toBuffer(),
+      ],
+      GPT_ORACLE_PROGRAM_ID
+    );
+
     try {
-      await program.methods.scheduleRequest(taskId, prompt, new anchor.BN(delay))
+      await program.methods.scheduleRequest(taskId, prompt, new anchor.BN(delay), null, null)
         .accounts({
+          userRequests,
           requestState,
           user: provider.publicKey,
           taskQueue: TASK_QUEUE,
@@ -87,18 +108,84 @@ describe("gpt-tuktuk", () => {
         } as any)
         .rpc();
       console.log(" Scheduled GPT Request via Tuktuk");
+
+      const registryAfter = await program.account.userRequests.fetch(userRequests);
+      assert.equal(registryAfter.nextRequestId.toString(), requestId.addn(1).toString());
+      const entry = registryAfter.recent.find((e: any) => e.requestId.eq(requestId));
+      assert.ok(entry, "new request is listed in the registry");
+      assert.ok("pending" in entry.status);
     } catch (e: any) {
       console.log("Scheduling failed:", e.message?.slice(0, 200));
       throw e;
     }
   });
 
+  it("Rejects prompts over MAX_PROMPT_LEN", async () => {
+    const registry = await program.account.userRequests.fetch(userRequests);
+    const [longRequestState] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gpt_request"),
+        provider.publicKey.toBuffer(),
+        registry.nextRequestId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    try {
+      await program.methods.scheduleRequest(taskId, "x".repeat(513), new anchor.BN(5), null, null)
+        .accounts({
+          userRequests,
+          requestState: longRequestState,
+          user: provider.publicKey,
+          taskQueue: TASK_QUEUE,
+          oracleContextAccount: ORACLE_CONTEXT_ACCOUNT,
+        } as any)
+        .rpc();
+      assert.fail("schedule_request accepted an oversized prompt");
+    } catch (e: any) {
+      assert.include(e.message, "PromptTooLong");
+    }
+  });
+
+  it("Rejects recurring requests without runs", async () => {
+    const registry = await program.account.userRequests.fetch(userRequests);
+    const [recurringRequestState] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("gpt_request"),
+        provider.publicKey.toBuffer(),
+        registry.nextRequestId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .scheduleRequest(taskId, prompt, new anchor.BN(5), null, {
+          interval: new anchor.BN(86400),
+          maxRuns: 0,
+        })
+        .accounts({
+          userRequests,
+          requestState: recurringRequestState,
+          user: provider.publicKey,
+          taskQueue: TASK_QUEUE,
+          oracleContextAccount: ORACLE_CONTEXT_ACCOUNT,
+        } as any)
+        .rpc();
+      assert.fail("schedule_request accepted a recurrence with no runs");
+    } catch (e: any) {
+      assert.include(e.message, "InvalidRecurrence");
+    }
+  });
+
   it("Executes a GPT request (calls GPT Oracle CPI)", async () => {
     try {
-      await program.methods.executeRequest(prompt)
+      await program.methods.executeRequest()
         .accounts({
           requestState,
+          userRequests,
           user: provider.publicKey,
+          taskPayer,
           oracleContextAccount: ORACLE_CONTEXT_ACCOUNT,
           oracleInteraction,
         } as any)
@@ -174,6 +261,7 @@ describe("gpt-tuktuk", () => {
 
   it("Polls for GPT response (devnet integration)", async () => {
     console.log("Waiting for Oracle agent to process the interaction...");
+    console.log("Request ID:", requestId.toString());
     console.log("Request State:", requestState.toBase58());
     console.log("Oracle Interaction:", oracleInteraction.toBase58());
 
@@ -189,6 +277,10 @@ describe("gpt-tuktuk", () => {
           console.log("   Response:", state.result);
           assert.isTrue(state.isCompleted);
           assert.isNotNull(state.result);
+
+          const registry = await program.account.userRequests.fetch(userRequests);
+          const entry = registry.recent.find((e: any) => e.requestId.eq(requestId));
+          assert.ok(entry && "completed" in entry.status);
           return;
         }
         if (i % 5 === 0) {